@@ -0,0 +1,65 @@
+use better_chess::{AbsolutePosition, Board, Color, Game, Position, STARTING_FEN};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+/// Whether a piece of `color` attacks `square`, found by scanning every tile of the board
+fn board_is_attacked(board: &Board, square: &AbsolutePosition, color: &Color) -> bool{
+    board.get_tiles().into_iter().any(|(tile, from)| match tile.get_piece(){
+        Some(piece) if piece.color() == color => {
+            let (path, valid) = piece.valid_capture(&square.to_relative(&from));
+
+            valid && path.iter().all(|step| {
+                let position = step.to_absolute(&from).unwrap();
+                board.get_tile(&position).get_piece().is_none()
+            })
+        },
+        _ => false,
+    })
+}
+
+/// Asks whether every tile is attacked by white, once on the board and once on the bitboards
+fn attacks(c: &mut Criterion){
+    let mut group = c.benchmark_group("attacked_tiles");
+
+    for (name, fen) in [("start", STARTING_FEN), ("kiwipete", KIWIPETE)]{
+        let game = Game::from_fen(fen).unwrap();
+        let board = game.board();
+        let position = *game.position();
+        let squares : Vec<AbsolutePosition> = (0..64).map(|i| AbsolutePosition::new(i % 8, i / 8)).collect();
+
+        group.bench_with_input(BenchmarkId::new("board", name), &board, |b, board| {
+            b.iter(|| squares.iter().filter(|square| board_is_attacked(board, square, &Color::White)).count())
+        });
+
+        group.bench_with_input(BenchmarkId::new("bitboard", name), &position, |b, position| {
+            b.iter(|| squares.iter().filter(|square| position.is_attacked(square, &Color::White)).count())
+        });
+    }
+
+    group.finish();
+}
+
+/// Converts between the two representations
+fn conversion(c: &mut Criterion){
+    let game = Game::from_fen(KIWIPETE).unwrap();
+    let board = game.board();
+
+    c.bench_function("position_to_board", |b| b.iter(|| black_box(game.position()).to_board()));
+    c.bench_function("board_to_position", |b| b.iter(|| Position::from_board(black_box(&board))));
+}
+
+fn perft(c: &mut Criterion){
+    let mut group = c.benchmark_group("perft");
+
+    for (name, fen) in [("start", STARTING_FEN), ("kiwipete", KIWIPETE)]{
+        let game = Game::from_fen(fen).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("depth_3", name), &game, |b, game| b.iter(|| game.perft(3)));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, attacks, conversion, perft);
+criterion_main!(benches);
@@ -1,66 +1,97 @@
-use crate::chess::{movement::{Movement, chess_notation::ChessNotationPosition}, game::{classic::ClassicGame, GameState}};
+use better_chess::{engine::level::Level, prelude::*, Clock, TimeControl};
 
-mod chess;
+mod cli;
+#[cfg(feature = "tui")]
+mod tui;
+
+const USAGE: &str = "Usage: better_chess [--tui] [--clock <control>] [--vs-engine [white|black]] [--level <1-6>] | --uci";
 
 fn main() {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    if arguments.iter().any(|argument| argument == "--uci") {
+        better_chess::engine::uci::run(std::io::stdin().lock(), std::io::stdout());
+        return;
+    }
+
+    if arguments.iter().any(|argument| argument == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
     let mut game = ClassicGame::new();
 
-    loop {
-        print!("\x1B[2J\x1B[1;1H");
-        println!("{}", game.board());
-        println!("It's {}'s turn", game.turn());
-        let movement = ask_for_movement();
-        match game.move_piece(movement) {
-            Ok(_) => (),
-            Err(error) => println!("{}", error),
-        }
+    if let Some(control) = option(&arguments, "--clock") {
+        let control = control.ok_or_else(|| String::from("--clock needs a time control such as 300+2"))
+            .and_then(|control| control.parse::<TimeControl>().map_err(|error| error.to_string()));
 
-        match game.state() {
-            GameState::Check(_) => {
-                println!("Check!");
-            }
-            GameState::Checkmate(color) => {
-                let opposite_color = match color {
-                    chess::color::Color::White => chess::color::Color::Black,
-                    chess::color::Color::Black => chess::color::Color::White,
-                };
-
-                println!("Checkmate! {} wins!", opposite_color);
-                break;
-            }
-            GameState::Stalemate => {
-                println!("Stalemate!");
-                break;
-            }
-            _ => (),
-        }
+        game.set_clock(Clock::new(exit_on_error(control)));
     }
 
+    let level = match option(&arguments, "--level") {
+        Some(level) => exit_on_error(level.unwrap_or_default().parse::<Level>().map_err(|error| error.to_string())),
+        None => Level::default(),
+    };
+
+    let color = option(&arguments, "--vs-engine").map(|color| {
+        let color = match color {
+            None | Some("white") => Ok(Color::White),
+            Some("black") => Ok(Color::Black),
+            Some(color) => Err(format!("--vs-engine takes white or black, not {}", color)),
+        };
 
-}
+        exit_on_error(color)
+    });
 
+    let result = match arguments.iter().any(|argument| argument == "--tui") {
+        true => run_tui(game, color, level),
+        false => {
+            let mut cli = match color {
+                Some(color) => cli::Cli::against_engine(game, color, level),
+                None => cli::Cli::new(game),
+            };
 
-fn ask_for_movement() -> Movement{
-    let mut movement = String::new();
-    println!("Please enter your movement: ");
-    std::io::stdin().read_line(&mut movement).expect("Failed to read line");
-    let movement = movement.trim();
-    
-    let from = match ChessNotationPosition::from_str(&movement[0..2]) {
-        Ok(position) => position,
-        Err(error) => {
-            println!("{}", error);
-            return ask_for_movement();
+            cli.run(std::io::stdin().lock(), &mut std::io::stdout())
         }
     };
 
-    let to = match ChessNotationPosition::from_str(&movement[2..4]) {
-        Ok(position) => position,
-        Err(error) => {
-            println!("{}", error);
-            return ask_for_movement();
-        }
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(game: Game, color: Option<Color>, level: Level) -> std::io::Result<()> {
+    let mut app = match color {
+        Some(color) => tui::App::against_engine(game, color, level),
+        None => tui::App::new(game),
     };
 
-    Movement::new(from.to_position(), to.to_position())
-}
\ No newline at end of file
+    app.run()
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_game: Game, _color: Option<Color>, _level: Level) -> std::io::Result<()> {
+    exit_on_error(Err(String::from("--tui needs the binary built with the tui feature")))
+}
+
+/// Looks for `name` in the arguments
+///
+/// ### Returns
+///
+/// * `None` - If the option is not given
+/// * `Some(None)` - If the option is given without a value
+/// * `Some(Some(value))` - If the option is followed by a value
+fn option<'a>(arguments: &'a [String], name: &str) -> Option<Option<&'a str>> {
+    let index = arguments.iter().position(|argument| argument == name)?;
+
+    Some(arguments.get(index + 1).map(String::as_str).filter(|value| !value.starts_with("--")))
+}
+
+fn exit_on_error<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        std::process::exit(1);
+    })
+}
@@ -0,0 +1,190 @@
+//! Attack tables computed at compile time
+//!
+//! Knights, kings and pawns read their attacks straight from a table. Sliding pieces follow
+//! a precomputed ray in each direction and cut it at the first occupied tile.
+
+use crate::chess::{color::Color, movement::absolute_position::AbsolutePosition};
+
+use super::{square_index, Bitboard};
+
+
+const KNIGHT_DELTAS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_DELTAS: [(i8, i8); 8] = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+const WHITE_PAWN_DELTAS: [(i8, i8); 2] = [(-1, 1), (1, 1)];
+const BLACK_PAWN_DELTAS: [(i8, i8); 2] = [(-1, -1), (1, -1)];
+
+/// North, east, north east and north west grow the index, the other four shrink it
+const DIRECTIONS: [(i8, i8); 8] = [(0, 1), (1, 0), (1, 1), (-1, 1), (0, -1), (-1, 0), (-1, -1), (1, -1)];
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_table(&KNIGHT_DELTAS);
+const KING_ATTACKS: [u64; 64] = leaper_table(&KING_DELTAS);
+const PAWN_ATTACKS: [[u64; 64]; 2] = [leaper_table(&WHITE_PAWN_DELTAS), leaper_table(&BLACK_PAWN_DELTAS)];
+const RAYS: [[u64; 64]; 8] = ray_table();
+
+const fn leaper_table(deltas: &[(i8, i8)]) -> [u64; 64]{
+    let mut table = [0; 64];
+    let mut square = 0;
+
+    while square < 64{
+        let mut i = 0;
+
+        while i < deltas.len(){
+            let file = (square % 8) as i8 + deltas[i].0;
+            let rank = (square / 8) as i8 + deltas[i].1;
+
+            if file >= 0 && file < 8 && rank >= 0 && rank < 8{
+                table[square] |= 1 << (rank * 8 + file);
+            }
+
+            i += 1;
+        }
+
+        square += 1;
+    }
+
+    table
+}
+
+const fn ray_table() -> [[u64; 64]; 8]{
+    let mut table = [[0; 64]; 8];
+    let mut direction = 0;
+
+    while direction < 8{
+        let mut square = 0;
+
+        while square < 64{
+            let mut file = (square % 8) as i8 + DIRECTIONS[direction].0;
+            let mut rank = (square / 8) as i8 + DIRECTIONS[direction].1;
+
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8{
+                table[direction][square] |= 1 << (rank * 8 + file);
+                file += DIRECTIONS[direction].0;
+                rank += DIRECTIONS[direction].1;
+            }
+
+            square += 1;
+        }
+
+        direction += 1;
+    }
+
+    table
+}
+
+fn ray_attacks(direction: usize, square: usize, occupied: u64) -> u64{
+    let ray = RAYS[direction][square];
+    let blockers = ray & occupied;
+
+    if blockers == 0{
+        return ray;
+    }
+
+    let blocker = match direction < 4{
+        true => blockers.trailing_zeros() as usize,
+        false => 63 - blockers.leading_zeros() as usize,
+    };
+
+    ray ^ RAYS[direction][blocker]
+}
+
+pub fn knight_attacks(square: &AbsolutePosition) -> Bitboard{
+    Bitboard(KNIGHT_ATTACKS[square_index(square)])
+}
+
+pub fn king_attacks(square: &AbsolutePosition) -> Bitboard{
+    Bitboard(KING_ATTACKS[square_index(square)])
+}
+
+/// The tiles a pawn of `color` standing on `square` captures on
+pub fn pawn_attacks(color: &Color, square: &AbsolutePosition) -> Bitboard{
+    Bitboard(PAWN_ATTACKS[*color as usize][square_index(square)])
+}
+
+/// The tiles a rook on `square` reaches, including the first occupied tile in each direction
+pub fn rook_attacks(square: &AbsolutePosition, occupied: Bitboard) -> Bitboard{
+    let square = square_index(square);
+
+    Bitboard([0, 1, 4, 5].iter().fold(0, |attacks, direction| attacks | ray_attacks(*direction, square, occupied.0)))
+}
+
+/// The tiles a bishop on `square` reaches, including the first occupied tile in each direction
+pub fn bishop_attacks(square: &AbsolutePosition, occupied: Bitboard) -> Bitboard{
+    let square = square_index(square);
+
+    Bitboard([2, 3, 6, 7].iter().fold(0, |attacks, direction| attacks | ray_attacks(*direction, square, occupied.0)))
+}
+
+pub fn queen_attacks(square: &AbsolutePosition, occupied: Bitboard) -> Bitboard{
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+/// The direction leading from `from` to `to`, `None` when they share no rank, file or diagonal
+fn direction_between(from: usize, to: usize) -> Option<usize>{
+    (0..8).find(|direction| RAYS[*direction][from] & (1 << to) != 0)
+}
+
+/// The tiles strictly between `a` and `b`, empty when they share no rank, file or diagonal
+pub fn between(a: &AbsolutePosition, b: &AbsolutePosition) -> Bitboard{
+    let (a, b) = (square_index(a), square_index(b));
+
+    match direction_between(a, b){
+        Some(direction) => Bitboard((RAYS[direction][a] ^ RAYS[direction][b]) & !(1 << b)),
+        None => Bitboard::EMPTY,
+    }
+}
+
+/// The whole rank, file or diagonal through `a` and `b`, empty when they share none
+pub fn line(a: &AbsolutePosition, b: &AbsolutePosition) -> Bitboard{
+    let (a, b) = (square_index(a), square_index(b));
+
+    match direction_between(a, b){
+        Some(direction) => Bitboard(RAYS[direction][a] | RAYS[(direction + 4) % 8][a] | (1 << a)),
+        None => Bitboard::EMPTY,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn squares(names: &[&str]) -> Bitboard{
+        names.iter().fold(Bitboard::EMPTY, |bitboard, name| {
+            let mut chars = name.chars();
+            let file = chars.next().unwrap() as usize - 'a' as usize;
+            let rank = chars.next().unwrap() as usize - '1' as usize;
+
+            bitboard | Bitboard::from_square(&AbsolutePosition::new(file, rank))
+        })
+    }
+
+    #[test]
+    fn test_leaper_attacks_stay_on_the_board(){
+        assert_eq!(knight_attacks(&AbsolutePosition::new(0, 0)), squares(&["b3", "c2"]));
+        assert_eq!(king_attacks(&AbsolutePosition::new(7, 7)), squares(&["g8", "g7", "h7"]));
+        assert_eq!(knight_attacks(&AbsolutePosition::new(3, 3)).count(), 8);
+    }
+
+    #[test]
+    fn test_pawn_attacks_depend_on_color(){
+        assert_eq!(pawn_attacks(&Color::White, &AbsolutePosition::new(4, 3)), squares(&["d5", "f5"]));
+        assert_eq!(pawn_attacks(&Color::Black, &AbsolutePosition::new(0, 3)), squares(&["b3"]));
+    }
+
+    #[test]
+    fn test_sliders_stop_on_the_first_blocker(){
+        let occupied = squares(&["d6", "b4", "d2", "f4", "f6"]);
+
+        assert_eq!(rook_attacks(&AbsolutePosition::new(3, 3), occupied), squares(&["d5", "d6", "c4", "b4", "e4", "f4", "d3", "d2"]));
+        assert_eq!(bishop_attacks(&AbsolutePosition::new(3, 3), occupied), squares(&["e5", "f6", "c5", "b6", "a7", "c3", "b2", "a1", "e3", "f2", "g1"]));
+        assert_eq!(queen_attacks(&AbsolutePosition::new(0, 0), Bitboard::EMPTY).count(), 21);
+    }
+
+    #[test]
+    fn test_between_and_line_follow_shared_lines(){
+        assert_eq!(between(&AbsolutePosition::new(0, 0), &AbsolutePosition::new(3, 3)), squares(&["b2", "c3"]));
+        assert_eq!(between(&AbsolutePosition::new(4, 7), &AbsolutePosition::new(4, 5)), squares(&["e7"]));
+        assert_eq!(between(&AbsolutePosition::new(0, 0), &AbsolutePosition::new(1, 2)), Bitboard::EMPTY);
+        assert_eq!(line(&AbsolutePosition::new(2, 1), &AbsolutePosition::new(4, 3)), squares(&["b1", "c2", "d3", "e4", "f5", "g6", "h7"]));
+        assert_eq!(line(&AbsolutePosition::new(0, 0), &AbsolutePosition::new(1, 2)), Bitboard::EMPTY);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{fmt::Display, ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not}};
+
+use super::movement::absolute_position::AbsolutePosition;
+
+pub mod attacks;
+pub mod position;
+pub mod zobrist;
+
+
+/// A set of tiles packed in 64 bits, bit `rank * 8 + file` is set when the tile belongs to the set
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard{
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// Creates a set holding only `square`
+    pub fn from_square(square: &AbsolutePosition) -> Self{
+        Bitboard(1 << square_index(square))
+    }
+
+    /// Creates a set holding every tile of `rank`, 0 being the first rank
+    pub fn rank(rank: usize) -> Self{
+        Bitboard(0xff << (rank * 8))
+    }
+
+    /// Creates a set holding every tile of `file`, 0 being the a-file
+    pub fn file(file: usize) -> Self{
+        Bitboard(0x0101_0101_0101_0101 << file)
+    }
+
+    pub fn contains(&self, square: &AbsolutePosition) -> bool{
+        self.0 & (1 << square_index(square)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.0 == 0
+    }
+
+    /// Returns the number of tiles in the set
+    pub fn count(&self) -> u32{
+        self.0.count_ones()
+    }
+
+    /// Returns the tile with the lowest index, a1 first and h8 last
+    pub fn first(&self) -> Option<AbsolutePosition>{
+        match self.0{
+            0 => None,
+            bits => Some(square_at(bits.trailing_zeros() as usize)),
+        }
+    }
+}
+
+/// Iterates over the tiles of a bitboard from a1 to h8
+pub struct Squares(u64);
+
+impl Iterator for Squares{
+    type Item = AbsolutePosition;
+
+    fn next(&mut self) -> Option<AbsolutePosition>{
+        if self.0 == 0{
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+
+        Some(square_at(index))
+    }
+}
+
+impl IntoIterator for Bitboard{
+    type Item = AbsolutePosition;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Squares{
+        Squares(self.0)
+    }
+}
+
+impl FromIterator<AbsolutePosition> for Bitboard{
+    fn from_iter<I: IntoIterator<Item = AbsolutePosition>>(squares: I) -> Bitboard{
+        squares.into_iter().fold(Bitboard::EMPTY, |bitboard, square| bitboard | Bitboard::from_square(&square))
+    }
+}
+
+impl BitAnd for Bitboard{
+    type Output = Bitboard;
+
+    fn bitand(self, other: Bitboard) -> Bitboard{
+        Bitboard(self.0 & other.0)
+    }
+}
+
+impl BitOr for Bitboard{
+    type Output = Bitboard;
+
+    fn bitor(self, other: Bitboard) -> Bitboard{
+        Bitboard(self.0 | other.0)
+    }
+}
+
+impl BitXor for Bitboard{
+    type Output = Bitboard;
+
+    fn bitxor(self, other: Bitboard) -> Bitboard{
+        Bitboard(self.0 ^ other.0)
+    }
+}
+
+impl Not for Bitboard{
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard{
+        Bitboard(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard{
+    fn bitand_assign(&mut self, other: Bitboard){
+        self.0 &= other.0;
+    }
+}
+
+impl BitOrAssign for Bitboard{
+    fn bitor_assign(&mut self, other: Bitboard){
+        self.0 |= other.0;
+    }
+}
+
+impl BitXorAssign for Bitboard{
+    fn bitxor_assign(&mut self, other: Bitboard){
+        self.0 ^= other.0;
+    }
+}
+
+/// Draws the set as 8 rows of `1` and `.`, the eighth rank first
+impl Display for Bitboard{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in (0..8).rev(){
+            for file in 0..8{
+                match self.contains(&AbsolutePosition::new(file, rank)){
+                    true => write!(f, "1")?,
+                    false => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn square_index(square: &AbsolutePosition) -> usize{
+    square.rank * 8 + square.file
+}
+
+pub(crate) fn square_at(index: usize) -> AbsolutePosition{
+    AbsolutePosition::new(index % 8, index / 8)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_squares_are_iterated_from_a1(){
+        let squares : Vec<AbsolutePosition> = (Bitboard::from_square(&AbsolutePosition::new(7, 7)) | Bitboard::from_square(&AbsolutePosition::new(2, 0))).into_iter().collect();
+
+        assert_eq!(squares, vec![AbsolutePosition::new(2, 0), AbsolutePosition::new(7, 7)]);
+    }
+
+    #[test]
+    fn test_rank_and_file_cross_on_one_tile(){
+        let cross = Bitboard::rank(3) & Bitboard::file(4);
+
+        assert_eq!(cross.count(), 1);
+        assert_eq!(cross.first(), Some(AbsolutePosition::new(4, 3)));
+        assert!((Bitboard::rank(3) | Bitboard::file(4)).contains(&AbsolutePosition::new(4, 0)));
+    }
+}
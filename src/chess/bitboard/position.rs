@@ -0,0 +1,223 @@
+use crate::chess::{board::Board, color::Color, movement::absolute_position::AbsolutePosition, piece::{Piece, PieceType}};
+
+use super::{attacks, zobrist, Bitboard};
+
+
+/// The pieces on the board as one bitboard per piece type and one per color
+///
+/// A position is small and `Copy`, so trying a move on a copy is cheap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct Position{
+    pieces: [Bitboard; 6],
+    colors: [Bitboard; 2],
+    zobrist: u64,
+}
+
+impl Position{
+    /// Creates a position without any piece
+    pub fn empty() -> Self{
+        Position::default()
+    }
+
+    /// Creates a position holding the pieces of `board`
+    pub fn from_board(board: &Board) -> Self{
+        let mut position = Position::empty();
+
+        for (tile, square) in board.get_tiles(){
+            if let Some(piece) = tile.get_piece(){
+                position.set_piece(&square, *piece.color(), piece.piece_type());
+            }
+        }
+
+        position
+    }
+
+    /// Creates a tile by tile board of the position
+    ///
+    /// Pawns away from their starting rank are marked as moved, so they lose their double step.
+    pub fn to_board(&self) -> Board{
+        let mut board = Board::new();
+
+        for square in self.occupied(){
+            let (color, piece_type) = self.piece_at(&square).expect("An occupied tile holds a piece");
+            let mut piece = Piece::new(color, piece_type);
+
+            let starting_rank = match color{
+                Color::White => 1,
+                Color::Black => 6,
+            };
+
+            if piece_type == PieceType::Pawn && square.rank != starting_rank{
+                piece.moved();
+            }
+
+            board.get_tile_mut(&square).set_piece(piece);
+        }
+
+        board
+    }
+
+    /// Returns the color and type of the piece on `square`
+    pub fn piece_at(&self, square: &AbsolutePosition) -> Option<(Color, PieceType)>{
+        let color = match (self.colors[0].contains(square), self.colors[1].contains(square)){
+            (true, _) => Color::White,
+            (_, true) => Color::Black,
+            _ => return None,
+        };
+
+        PieceType::ALL.iter()
+            .find(|piece_type| self.pieces[**piece_type as usize].contains(square))
+            .map(|piece_type| (color, *piece_type))
+    }
+
+    /// Puts a piece on `square`, replacing whatever stood there
+    pub fn set_piece(&mut self, square: &AbsolutePosition, color: Color, piece_type: PieceType){
+        self.remove_piece(square);
+
+        let bit = Bitboard::from_square(square);
+
+        self.pieces[piece_type as usize] |= bit;
+        self.colors[color as usize] |= bit;
+        self.zobrist ^= zobrist::piece_key(&color, piece_type, square);
+    }
+
+    /// Takes the piece off `square`
+    ///
+    /// ### Returns
+    ///
+    /// Returns the color and type of the removed piece, `None` if the tile was empty
+    pub fn remove_piece(&mut self, square: &AbsolutePosition) -> Option<(Color, PieceType)>{
+        let piece = self.piece_at(square)?;
+        let bit = !Bitboard::from_square(square);
+
+        self.pieces[piece.1 as usize] &= bit;
+        self.colors[piece.0 as usize] &= bit;
+        self.zobrist ^= zobrist::piece_key(&piece.0, piece.1, square);
+
+        Some(piece)
+    }
+
+    /// Returns the Zobrist hash of the piece placement, kept up to date as pieces are set and removed
+    pub fn zobrist_hash(&self) -> u64{
+        self.zobrist
+    }
+
+    /// Returns the tiles holding a piece of `color` and `piece_type`
+    pub fn pieces(&self, color: &Color, piece_type: PieceType) -> Bitboard{
+        self.pieces[piece_type as usize] & self.colors[*color as usize]
+    }
+
+    /// Returns the tiles holding a piece of `color`
+    pub fn pieces_of(&self, color: &Color) -> Bitboard{
+        self.colors[*color as usize]
+    }
+
+    /// Returns the tiles holding a piece of `piece_type`, of either color
+    pub fn pieces_of_type(&self, piece_type: PieceType) -> Bitboard{
+        self.pieces[piece_type as usize]
+    }
+
+    pub fn occupied(&self) -> Bitboard{
+        self.colors[0] | self.colors[1]
+    }
+
+    pub fn king(&self, color: &Color) -> Option<AbsolutePosition>{
+        self.pieces(color, PieceType::King).first()
+    }
+
+    /// Returns the tiles the piece on `square` attacks, empty if there is no piece
+    pub fn attacks_from(&self, square: &AbsolutePosition) -> Bitboard{
+        let occupied = self.occupied();
+
+        match self.piece_at(square){
+            Some((color, PieceType::Pawn)) => attacks::pawn_attacks(&color, square),
+            Some((_, PieceType::Knight)) => attacks::knight_attacks(square),
+            Some((_, PieceType::Bishop)) => attacks::bishop_attacks(square, occupied),
+            Some((_, PieceType::Rook)) => attacks::rook_attacks(square, occupied),
+            Some((_, PieceType::Queen)) => attacks::queen_attacks(square, occupied),
+            Some((_, PieceType::King)) => attacks::king_attacks(square),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the pieces of `color` that attack `square`
+    pub fn attackers(&self, square: &AbsolutePosition, color: &Color) -> Bitboard{
+        self.attackers_with(square, color, self.occupied())
+    }
+
+    /// Returns the pieces of `color` that would attack `square` if only the tiles in `occupied` blocked sliding pieces
+    ///
+    /// Taking the king out of `occupied` shows the tiles it cannot step back to along the line of a check.
+    pub fn attackers_with(&self, square: &AbsolutePosition, color: &Color, occupied: Bitboard) -> Bitboard{
+        let straight = self.pieces(color, PieceType::Rook) | self.pieces(color, PieceType::Queen);
+        let diagonal = self.pieces(color, PieceType::Bishop) | self.pieces(color, PieceType::Queen);
+
+        (attacks::pawn_attacks(&color.opposite(), square) & self.pieces(color, PieceType::Pawn))
+            | (attacks::knight_attacks(square) & self.pieces(color, PieceType::Knight))
+            | (attacks::king_attacks(square) & self.pieces(color, PieceType::King))
+            | (attacks::rook_attacks(square, occupied) & straight)
+            | (attacks::bishop_attacks(square, occupied) & diagonal)
+    }
+
+    pub fn is_attacked(&self, square: &AbsolutePosition, color: &Color) -> bool{
+        !self.attackers(square, color).is_empty()
+    }
+
+    /// Whether the king of `color` is attacked, a position without that king is never in check
+    pub fn in_check(&self, color: &Color) -> bool{
+        match self.king(color){
+            Some(king) => self.is_attacked(&king, &color.opposite()),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::classic::ClassicGame;
+
+    use super::*;
+
+    #[test]
+    fn test_board_round_trips(){
+        let board = ClassicGame::new().board();
+        let position = Position::from_board(&board);
+
+        assert_eq!(position.occupied().count(), 32);
+        assert_eq!(position.pieces(&Color::White, PieceType::Pawn), Bitboard::rank(1));
+        assert_eq!(position.king(&Color::Black), Some(AbsolutePosition::new(4, 7)));
+        assert_eq!(Position::from_board(&position.to_board()), position);
+    }
+
+    #[test]
+    fn test_set_piece_replaces_the_previous_one(){
+        let mut position = Position::empty();
+        let square = AbsolutePosition::new(3, 3);
+
+        position.set_piece(&square, Color::White, PieceType::Knight);
+        position.set_piece(&square, Color::Black, PieceType::Queen);
+
+        assert_eq!(position.piece_at(&square), Some((Color::Black, PieceType::Queen)));
+        assert_eq!(position.occupied().count(), 1);
+        assert_eq!(position.remove_piece(&square), Some((Color::Black, PieceType::Queen)));
+        assert_eq!(position.occupied(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_attackers_of_a_square(){
+        let mut position = Position::empty();
+
+        position.set_piece(&AbsolutePosition::new(4, 0), Color::White, PieceType::King);
+        position.set_piece(&AbsolutePosition::new(4, 7), Color::Black, PieceType::Rook);
+        position.set_piece(&AbsolutePosition::new(3, 1), Color::Black, PieceType::Pawn);
+        position.set_piece(&AbsolutePosition::new(3, 2), Color::Black, PieceType::Knight);
+
+        assert_eq!(position.attackers(&AbsolutePosition::new(4, 0), &Color::Black).count(), 3);
+        assert!(position.in_check(&Color::White));
+
+        position.set_piece(&AbsolutePosition::new(4, 4), Color::White, PieceType::Bishop);
+
+        assert_eq!(position.attackers(&AbsolutePosition::new(4, 0), &Color::Black).count(), 2);
+        assert!(!position.in_check(&Color::Black));
+    }
+}
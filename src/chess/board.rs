@@ -1,11 +1,10 @@
 use std::fmt::Display;
 
-use colored::Colorize;
+use self::{render::{BoardRenderer, RenderOptions, UnicodeRenderer}, tile::Tile};
 
-use self::tile::Tile;
-
-use super::movement::absolute_position::AbsolutePosition;
+use super::{color::Color, movement::absolute_position::AbsolutePosition};
 
+pub mod render;
 pub mod tile;
 
 #[derive(Clone)]
@@ -34,7 +33,7 @@ impl Board{
         &self.tiles[position.file][position.rank]
     }
 
-    pub fn get_king_position(&self, color: &super::color::Color) -> Option<AbsolutePosition>{
+    pub fn get_king_position(&self, color: &Color) -> Option<AbsolutePosition>{
         for (tile, position) in self.get_tiles(){
             if let Some(piece) = tile.get_piece(){
                 if piece.color() == color && piece.prefix() == 'K'{
@@ -45,6 +44,23 @@ impl Board{
         None
     }
 
+    /// Draws the board with `renderer`
+    ///
+    /// ### Arguments
+    ///
+    /// * `renderer` - How the squares look, such as `AsciiRenderer` or `ColoredRenderer`
+    /// * `options` - The side drawn at the bottom, the coordinates, the highlights and the arrows
+    pub fn render<R: BoardRenderer>(&self, renderer: &R, options: &RenderOptions) -> String{
+        renderer.render(self, options)
+    }
+
+    /// Draws the board in Unicode without colours, with the pieces of `perspective` at the bottom
+    ///
+    /// `Display` draws it from White's side.
+    pub fn display_from(&self, perspective: &Color) -> String{
+        self.render(&UnicodeRenderer, &RenderOptions::from_side(*perspective))
+    }
+
     pub fn get_tiles(&self) -> Vec<(&Tile, AbsolutePosition)>{
         self.tiles.iter().enumerate().flat_map(|(file_i, rank)|{
             rank.iter().enumerate().map(move |(rank_i, tile)|{
@@ -56,22 +72,7 @@ impl Board{
 
 impl Display for Board{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut board = String::new();
-        for (rank_i, _) in self.tiles.iter().enumerate().rev(){
-            board.push_str(&format!("{} ",rank_i + 1));
-            for (file_i, _) in self.tiles[rank_i].iter().enumerate(){
-                if (rank_i + file_i) % 2 == 0{
-                    board.push_str(&format!("{}", self.tiles[file_i][rank_i]).on_bright_black().to_string());
-                }else{
-                    board.push_str(&format!("{}", self.tiles[file_i][rank_i]).on_bright_white().to_string());
-                }
-            }
-            board.push('\n');
-        }
-
-        board.push_str("   a  b  c  d  e  f  g  h\n");
-
-        write!(f, "{}", board)
+        write!(f, "{}", self.display_from(&Color::White))
     }
 }
 
@@ -121,6 +122,18 @@ mod tests{
         assert_eq!(got_king_position, king_position);
     }
 
+    #[test]
+    fn test_board_is_drawn_from_either_side(){
+        let board = Board::new();
+
+        let white = board.display_from(&Color::White);
+        let black = board.display_from(&Color::Black);
+
+        assert_eq!(white, board.to_string());
+        assert!(white.starts_with("8 ") && white.ends_with("\n   a  b  c  d  e  f  g  h\n"));
+        assert!(black.starts_with("1 ") && black.ends_with("\n   h  g  f  e  d  c  b  a\n"));
+    }
+
     #[test]
     fn test_should_not_find_missing_king(){
         let board = Board::new();
@@ -0,0 +1,427 @@
+use colored::Colorize;
+
+use crate::chess::{color::Color, movement::{absolute_position::AbsolutePosition, Movement}, piece::Piece};
+
+use super::Board;
+
+
+/// Which way a part of an arrow points on the drawn board, up being away from the player at the bottom
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction{
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+/// The part of an arrow drawn on an empty square
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArrowMark{
+    /// A square the arrow crosses
+    Shaft(Direction),
+    /// The square the arrow points to, without a direction for a knight's jump
+    Head(Option<Direction>),
+}
+
+/// What is drawn on one square
+#[derive(Clone, Copy)]
+pub struct Cell<'a>{
+    pub position: AbsolutePosition,
+    pub piece: Option<&'a Piece>,
+    pub highlighted: bool,
+    /// The arrow on the square, only set when the square is empty
+    pub arrow: Option<ArrowMark>,
+}
+
+impl Cell<'_>{
+    /// Whether the square is dark, a1 being dark
+    pub fn is_dark(&self) -> bool{
+        (self.position.file + self.position.rank).is_multiple_of(2)
+    }
+}
+
+/// What to draw on top of the pieces and from which side
+#[derive(Clone, Debug)]
+pub struct RenderOptions{
+    /// The player whose pieces are drawn at the bottom
+    pub perspective: Color,
+    /// Whether the rank numbers and file letters are drawn along the board
+    pub coordinates: bool,
+    pub highlights: Vec<AbsolutePosition>,
+    /// Arrows drawn from the start to the end of each movement, across the empty squares
+    pub arrows: Vec<Movement>,
+}
+
+impl Default for RenderOptions{
+    fn default() -> Self{
+        RenderOptions{
+            perspective: Color::White,
+            coordinates: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+}
+
+impl RenderOptions{
+    /// Creates the default options with the pieces of `perspective` at the bottom
+    pub fn from_side(perspective: Color) -> Self{
+        RenderOptions{perspective, ..Default::default()}
+    }
+
+    /// Returns the same options seen from the other side of the board
+    pub fn flipped(&self) -> Self{
+        RenderOptions{perspective: self.perspective.opposite(), ..self.clone()}
+    }
+}
+
+/// Draws a board as text
+///
+/// A renderer only decides how a square looks, every square being three characters wide, and
+/// `render` lays them out with the coordinates from the side given by the options.
+///
+/// ## Examples
+///
+/// ```
+/// use better_chess::{AsciiRenderer, BoardRenderer, ClassicGame, RenderOptions};
+///
+/// let game = ClassicGame::new();
+/// let options = RenderOptions{coordinates: false, ..Default::default()};
+///
+/// let board = AsciiRenderer.render(&game.board(), &options);
+///
+/// assert!(board.starts_with(" r  n  b  q  k  b  n  r \n p  p "));
+/// ```
+pub trait BoardRenderer{
+    /// Draws one square, three characters wide
+    fn square(&self, cell: &Cell) -> String;
+
+    /// Returns the character drawn for a part of an arrow
+    fn arrow(&self, mark: ArrowMark) -> char{
+        match mark{
+            ArrowMark::Shaft(Direction::Up | Direction::Down) => '|',
+            ArrowMark::Shaft(Direction::Left | Direction::Right) => '-',
+            ArrowMark::Shaft(Direction::UpRight | Direction::DownLeft) => '/',
+            ArrowMark::Shaft(Direction::UpLeft | Direction::DownRight) => '\\',
+            ArrowMark::Head(Some(Direction::Up)) => '^',
+            ArrowMark::Head(Some(Direction::Down)) => 'v',
+            ArrowMark::Head(Some(Direction::Left)) => '<',
+            ArrowMark::Head(Some(Direction::Right)) => '>',
+            ArrowMark::Head(_) => '*',
+        }
+    }
+
+    /// Draws `board` with the highlights, arrows and coordinates of `options`
+    fn render(&self, board: &Board, options: &RenderOptions) -> String{
+        draw_grid(self, board, options)
+    }
+}
+
+/// Letters for the pieces as in FEN, uppercase for White and lowercase for Black, dots for empty squares
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AsciiRenderer;
+
+impl BoardRenderer for AsciiRenderer{
+    fn square(&self, cell: &Cell) -> String{
+        let symbol = match (cell.piece, cell.arrow){
+            (Some(piece), _) if piece.color() == &Color::Black => piece.prefix().to_ascii_lowercase(),
+            (Some(piece), _) => piece.prefix(),
+            (None, Some(mark)) => self.arrow(mark),
+            (None, None) => '.',
+        };
+
+        framed(symbol, cell.highlighted)
+    }
+}
+
+/// Chess symbols, outlined for White and filled for Black, without any colour
+#[derive(Clone, Copy, Default, Debug)]
+pub struct UnicodeRenderer;
+
+impl BoardRenderer for UnicodeRenderer{
+    fn square(&self, cell: &Cell) -> String{
+        let symbol = match (cell.piece, cell.arrow){
+            (Some(piece), _) => piece.icon(),
+            (None, Some(mark)) => self.arrow(mark),
+            (None, None) => '·',
+        };
+
+        framed(symbol, cell.highlighted)
+    }
+
+    fn arrow(&self, mark: ArrowMark) -> char{
+        match mark{
+            ArrowMark::Shaft(Direction::Up | Direction::Down) => '│',
+            ArrowMark::Shaft(Direction::Left | Direction::Right) => '─',
+            ArrowMark::Shaft(Direction::UpRight | Direction::DownLeft) => '╱',
+            ArrowMark::Shaft(Direction::UpLeft | Direction::DownRight) => '╲',
+            ArrowMark::Head(Some(Direction::Up)) => '↑',
+            ArrowMark::Head(Some(Direction::Down)) => '↓',
+            ArrowMark::Head(Some(Direction::Left)) => '←',
+            ArrowMark::Head(Some(Direction::Right)) => '→',
+            ArrowMark::Head(Some(Direction::UpLeft)) => '↖',
+            ArrowMark::Head(Some(Direction::UpRight)) => '↗',
+            ArrowMark::Head(Some(Direction::DownLeft)) => '↙',
+            ArrowMark::Head(Some(Direction::DownRight)) => '↘',
+            ArrowMark::Head(None) => '●',
+        }
+    }
+}
+
+/// Chess symbols on coloured squares for a terminal, highlighted squares in yellow
+///
+/// The colours follow the `colored` crate, which leaves them out when `NO_COLOR` is set.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ColoredRenderer;
+
+impl BoardRenderer for ColoredRenderer{
+    fn square(&self, cell: &Cell) -> String{
+        let symbol = match (cell.piece, cell.arrow){
+            (Some(piece), _) => piece.icon(),
+            (None, Some(mark)) => UnicodeRenderer.arrow(mark),
+            (None, None) => ' ',
+        };
+
+        let square = format!(" {} ", symbol).black();
+
+        match (cell.highlighted, cell.is_dark()){
+            (true, _) => square.on_yellow().to_string(),
+            (false, true) => square.on_green().to_string(),
+            (false, false) => square.on_bright_white().to_string(),
+        }
+    }
+}
+
+/// Draws the board of another renderer from the other side
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Flipped<R: BoardRenderer>(pub R);
+
+impl<R: BoardRenderer> BoardRenderer for Flipped<R>{
+    fn square(&self, cell: &Cell) -> String{
+        self.0.square(cell)
+    }
+
+    fn arrow(&self, mark: ArrowMark) -> char{
+        self.0.arrow(mark)
+    }
+
+    fn render(&self, board: &Board, options: &RenderOptions) -> String{
+        self.0.render(board, &options.flipped())
+    }
+}
+
+/// Puts square brackets around a highlighted symbol, spaces otherwise
+fn framed(symbol: char, highlighted: bool) -> String{
+    match highlighted{
+        true => format!("[{}]", symbol),
+        false => format!(" {} ", symbol),
+    }
+}
+
+/// Lays out the squares of `renderer` rank by rank from the side of the options
+fn draw_grid<R: BoardRenderer + ?Sized>(renderer: &R, board: &Board, options: &RenderOptions) -> String{
+    let (ranks, files): (Vec<usize>, Vec<usize>) = match options.perspective{
+        Color::White => ((0..8).rev().collect(), (0..8).collect()),
+        Color::Black => ((0..8).collect(), (0..8).rev().collect()),
+    };
+
+    let arrows = arrow_marks(&options.arrows, &options.perspective);
+
+    let mut text = String::new();
+    for rank in &ranks{
+        if options.coordinates{
+            text.push_str(&format!("{} ", rank + 1));
+        }
+
+        for file in &files{
+            let position = AbsolutePosition::new(*file, *rank);
+            let piece = board.get_tile(&position).get_piece().as_ref();
+
+            let cell = Cell{
+                position,
+                piece,
+                highlighted: options.highlights.contains(&position),
+                arrow: arrows.iter().rev().find(|(square, _)| square == &position && piece.is_none()).map(|(_, mark)| *mark),
+            };
+
+            text.push_str(&renderer.square(&cell));
+        }
+        text.push('\n');
+    }
+
+    if options.coordinates{
+        let letters: Vec<String> = files.iter().map(|file| ((b'a' + *file as u8) as char).to_string()).collect();
+        text.push_str(&format!("   {}\n", letters.join("  ")));
+    }
+
+    text
+}
+
+/// Returns the squares every arrow crosses and ends on, with how it looks from `perspective`
+///
+/// Arrows along a rank, a file or a diagonal have a shaft, any other only has a head.
+fn arrow_marks(arrows: &[Movement], perspective: &Color) -> Vec<(AbsolutePosition, ArrowMark)>{
+    let mut marks = Vec::new();
+
+    for arrow in arrows{
+        let files = arrow.to().file as isize - arrow.from().file as isize;
+        let ranks = arrow.to().rank as isize - arrow.from().rank as isize;
+
+        let is_line = files == 0 || ranks == 0 || files.abs() == ranks.abs();
+
+        if !is_line{
+            marks.push((*arrow.to(), ArrowMark::Head(None)));
+            continue;
+        }
+
+        let (step_file, step_rank) = (files.signum(), ranks.signum());
+
+        // The board drawn from Black's side is turned around, so is the arrow
+        let direction = match perspective{
+            Color::White => direction(step_file, step_rank),
+            Color::Black => direction(-step_file, -step_rank),
+        };
+
+        let mut position = *arrow.from();
+        loop{
+            position = AbsolutePosition::new(
+                position.file.saturating_add_signed(step_file),
+                position.rank.saturating_add_signed(step_rank),
+            );
+
+            if &position == arrow.to(){
+                break;
+            }
+
+            marks.push((position, ArrowMark::Shaft(direction)));
+        }
+
+        marks.push((*arrow.to(), ArrowMark::Head(Some(direction))));
+    }
+
+    marks
+}
+
+/// Returns the direction of a step of one square at most along the files and the ranks as drawn
+fn direction(files: isize, ranks: isize) -> Direction{
+    match (files, ranks){
+        (0, 1) => Direction::Up,
+        (0, _) => Direction::Down,
+        (-1, 0) => Direction::Left,
+        (_, 0) => Direction::Right,
+        (-1, 1) => Direction::UpLeft,
+        (_, 1) => Direction::UpRight,
+        (-1, _) => Direction::DownLeft,
+        _ => Direction::DownRight,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::{classic::ClassicGame, Game};
+
+    use super::*;
+
+    fn square(name: &str) -> AbsolutePosition{
+        name.parse().unwrap()
+    }
+
+    fn arrow(from: &str, to: &str) -> Movement{
+        Movement::new(square(from), square(to))
+    }
+
+    #[test]
+    fn test_ascii_board_has_letters_and_coordinates(){
+        let board = ClassicGame::new().board();
+
+        assert_eq!(AsciiRenderer.render(&board, &RenderOptions::default()), concat!(
+            "8  r  n  b  q  k  b  n  r \n",
+            "7  p  p  p  p  p  p  p  p \n",
+            "6  .  .  .  .  .  .  .  . \n",
+            "5  .  .  .  .  .  .  .  . \n",
+            "4  .  .  .  .  .  .  .  . \n",
+            "3  .  .  .  .  .  .  .  . \n",
+            "2  P  P  P  P  P  P  P  P \n",
+            "1  R  N  B  Q  K  B  N  R \n",
+            "   a  b  c  d  e  f  g  h\n",
+        ));
+    }
+
+    #[test]
+    fn test_unicode_board_draws_black_filled(){
+        let board = ClassicGame::new().board();
+        let options = RenderOptions{coordinates: false, ..Default::default()};
+
+        let text = UnicodeRenderer.render(&board, &options);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], " ♜  ♞  ♝  ♛  ♚  ♝  ♞  ♜ ");
+        assert_eq!(lines[7], " ♖  ♘  ♗  ♕  ♔  ♗  ♘  ♖ ");
+        assert!(!text.contains('\x1B'));
+    }
+
+    #[test]
+    fn test_flipped_board_is_seen_from_black(){
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap().board();
+
+        let flipped = Flipped(AsciiRenderer).render(&board, &RenderOptions::default());
+
+        assert_eq!(flipped, AsciiRenderer.render(&board, &RenderOptions::from_side(Color::Black)));
+        assert!(flipped.starts_with("1  .  .  .  K  .  .  .  R \n"));
+        assert!(flipped.ends_with("   h  g  f  e  d  c  b  a\n"));
+
+        // Flipping twice comes back to White's side
+        assert_eq!(Flipped(Flipped(AsciiRenderer)).render(&board, &RenderOptions::default()), AsciiRenderer.render(&board, &RenderOptions::default()));
+    }
+
+    #[test]
+    fn test_highlights_and_arrows(){
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap().board();
+        let options = RenderOptions{
+            coordinates: false,
+            highlights: vec![square("e1"), square("e3")],
+            arrows: vec![arrow("a1", "a4"), arrow("e1", "h4"), arrow("e8", "f6")],
+            ..Default::default()
+        };
+
+        let text = AsciiRenderer.render(&board, &options);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], " .  .  .  .  k  .  .  . ");
+        assert_eq!(lines[2], " .  .  .  .  .  *  .  . ");
+        assert_eq!(lines[4], " ^  .  .  .  .  .  .  * ");
+        assert_eq!(lines[5], " |  .  .  . [.] .  /  . ");
+        assert_eq!(lines[6], " |  .  .  .  .  /  .  . ");
+        assert_eq!(lines[7], " R  .  .  . [K] .  .  . ");
+
+        // Turned around, the arrow up the a-file points down on the right
+        let text = AsciiRenderer.render(&board, &options.flipped());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[3], " *  .  .  .  .  .  .  v ");
+
+        let text = UnicodeRenderer.render(&board, &options);
+
+        assert!(text.lines().nth(4).unwrap().ends_with(" ↗ "));
+        assert!(text.lines().nth(5).unwrap().starts_with(" │ "));
+    }
+
+    #[test]
+    fn test_colored_board_paints_the_squares(){
+        colored::control::set_override(true);
+
+        let board = ClassicGame::new().board();
+        let options = RenderOptions{highlights: vec![square("e2")], ..Default::default()};
+
+        let text = ColoredRenderer.render(&board, &options);
+
+        assert!(text.contains("\x1B["));
+        assert!(text.contains(&" ♔ ".black().on_green().to_string()));
+        assert!(text.contains(&" ♙ ".black().on_yellow().to_string()));
+        assert!(text.ends_with("   a  b  c  d  e  f  g  h\n"));
+    }
+}
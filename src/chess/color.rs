@@ -14,4 +14,13 @@ impl Display for Color{
             Color::Black => write!(f, "Black"),
         }
     }
-}
\ No newline at end of file
+}
+impl Color{
+    /// Returns the color of the other player
+    pub fn opposite(&self) -> Color{
+        match self{
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
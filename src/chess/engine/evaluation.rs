@@ -0,0 +1,617 @@
+//! Static evaluation of a position, a sum of terms each scored for both players
+//!
+//! Every term has a middlegame and an endgame score, the two are blended by the material left
+//! on the board so a king hiding behind its pawns early on walks to the center once the queens
+//! are gone. `Evaluation` keeps the terms apart so a position can be explained, not only scored.
+
+use std::{fmt::Display, ops::{Add, AddAssign, Mul}};
+
+use crate::chess::{bitboard::{attacks, position::Position, Bitboard}, color::Color, game::Game, movement::absolute_position::AbsolutePosition, piece::PieceType};
+
+
+/// The phase of a position with every piece on the board, a position without pieces has phase 0
+pub const MAX_PHASE: i32 = 24;
+
+const BISHOP_PAIR: Phased = Phased::new(30, 50);
+const DOUBLED_PAWN: Phased = Phased::new(-10, -20);
+const ISOLATED_PAWN: Phased = Phased::new(-15, -10);
+/// The bonus of a passed pawn by the rank it reached, counted from its own side
+const PASSED_PAWN: [Phased; 8] = [
+    Phased::new(0, 0), Phased::new(5, 10), Phased::new(10, 15), Phased::new(15, 25),
+    Phased::new(25, 45), Phased::new(40, 75), Phased::new(60, 110), Phased::new(0, 0),
+];
+const ROOK_OPEN_FILE: Phased = Phased::new(25, 10);
+const ROOK_HALF_OPEN_FILE: Phased = Phased::new(12, 6);
+const PAWN_SHIELD: i32 = 10;
+const OPEN_FILE_NEAR_KING: i32 = -20;
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    10,  10,  20,  30,  30,  20,  10,  10,
+     5,   5,  10,  25,  25,  10,   5,   5,
+     0,   0,   0,  20,  20,   0,   0,   0,
+     5,  -5, -10,   0,   0, -10,  -5,   5,
+     5,  10,  10, -20, -20,  10,  10,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    60,  60,  60,  60,  60,  60,  60,  60,
+    35,  35,  35,  35,  35,  35,  35,  35,
+    20,  20,  20,  20,  20,  20,  20,  20,
+    10,  10,  10,  10,  10,  10,  10,  10,
+     5,   5,   5,   5,   5,   5,   5,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+   -50, -40, -30, -30, -30, -30, -40, -50,
+   -40, -20,   0,   0,   0,   0, -20, -40,
+   -30,   0,  10,  15,  15,  10,   0, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   0,  15,  20,  20,  15,   0, -30,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+   -20, -10, -10, -10, -10, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   5,   5,  10,  10,   5,   5, -10,
+   -10,   0,  10,  10,  10,  10,   0, -10,
+   -10,  10,  10,  10,  10,  10,  10, -10,
+   -10,   5,   0,   0,   0,   0,   5, -10,
+   -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+     5,  10,  10,  10,  10,  10,  10,   5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+     0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+    -5,   0,   5,   5,   5,   5,   0,  -5,
+     0,   0,   5,   5,   5,   5,   0,  -5,
+   -10,   5,   5,   5,   5,   5,   0, -10,
+   -10,   0,   5,   0,   0,   0,   0, -10,
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -20, -30, -30, -40, -40, -30, -30, -20,
+   -10, -20, -20, -20, -20, -20, -20, -10,
+    20,  20,   0,   0,   0,   0,  20,  20,
+    20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+   -50, -40, -30, -20, -20, -30, -40, -50,
+   -30, -20, -10,   0,   0, -10, -20, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -30,   0,   0,   0,   0, -30, -30,
+   -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+/// The value of each piece type in centipawns
+pub fn piece_value(piece_type: PieceType) -> i32{
+    match piece_type{
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+/// A score in centipawns with a middlegame and an endgame part
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Phased{
+    pub middlegame: i32,
+    pub endgame: i32,
+}
+
+impl Phased{
+    pub const fn new(middlegame: i32, endgame: i32) -> Self{
+        Phased{middlegame, endgame}
+    }
+
+    /// Blends both parts, `phase` going from 0 in a bare endgame to `MAX_PHASE` at the start
+    pub fn taper(&self, phase: i32) -> i32{
+        (self.middlegame * phase + self.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl Add for Phased{
+    type Output = Phased;
+
+    fn add(self, other: Phased) -> Phased{
+        Phased::new(self.middlegame + other.middlegame, self.endgame + other.endgame)
+    }
+}
+
+impl AddAssign for Phased{
+    fn add_assign(&mut self, other: Phased){
+        *self = *self + other;
+    }
+}
+
+impl Mul<i32> for Phased{
+    type Output = Phased;
+
+    fn mul(self, factor: i32) -> Phased{
+        Phased::new(self.middlegame * factor, self.endgame * factor)
+    }
+}
+
+/// One part of the evaluation
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Term{
+    /// The value of the pieces
+    Material,
+    /// Where the pieces stand, from the piece-square tables
+    PieceSquares,
+    /// A bonus for keeping both bishops
+    BishopPair,
+    /// Doubled and isolated pawns cost, passed pawns earn more the further they went
+    PawnStructure,
+    /// The tiles the pieces reach that are not guarded by enemy pawns
+    Mobility,
+    /// The pawns in front of the king, the open files next to it and the enemy pieces attacking it
+    KingSafety,
+    /// Rooks on files without pawns of their own
+    RookFiles,
+}
+
+impl Term{
+    pub const ALL: [Term; 7] = [Term::Material, Term::PieceSquares, Term::BishopPair, Term::PawnStructure, Term::Mobility, Term::KingSafety, Term::RookFiles];
+}
+
+impl Display for Term{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self{
+            Term::Material => "Material",
+            Term::PieceSquares => "Piece squares",
+            Term::BishopPair => "Bishop pair",
+            Term::PawnStructure => "Pawn structure",
+            Term::Mobility => "Mobility",
+            Term::KingSafety => "King safety",
+            Term::RookFiles => "Rook files",
+        };
+
+        f.pad(name)
+    }
+}
+
+/// The score of each term for each player
+///
+/// ## Examples
+///
+/// ```
+/// use better_chess::{Game, Color, engine::evaluation::{Evaluation, Term}};
+///
+/// let game = Game::from_fen("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1").unwrap();
+/// let evaluation = Evaluation::of(&game);
+///
+/// assert!(evaluation.term(Term::BishopPair, &Color::White) > 0);
+/// assert_eq!(evaluation.term(Term::BishopPair, &Color::Black), 0);
+/// assert_eq!(evaluation.total(), Term::ALL.iter().map(|term| evaluation.balance(*term)).sum::<i32>());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Evaluation{
+    scores: [[Phased; 2]; 7],
+    phase: i32,
+}
+
+impl Evaluation{
+    /// Evaluates every term of the position of `game` for both players
+    pub fn of(game: &Game) -> Self{
+        let position = game.position();
+        let mut scores = [[Phased::default(); 2]; 7];
+
+        for color in [Color::White, Color::Black]{
+            let terms = [
+                material(position, &color),
+                piece_squares(position, &color),
+                bishop_pair(position, &color),
+                pawn_structure(position, &color),
+                mobility(position, &color),
+                king_safety(position, &color),
+                rook_files(position, &color),
+            ];
+
+            for (term, score) in terms.into_iter().enumerate(){
+                scores[term][color as usize] = score;
+            }
+        }
+
+        Evaluation{scores, phase: game_phase(position)}
+    }
+
+    /// How much material is left, from 0 with only kings and pawns to `MAX_PHASE`
+    pub fn phase(&self) -> i32{
+        self.phase
+    }
+
+    /// Returns the score of `term` for the player of `color` before taper
+    pub fn phased(&self, term: Term, color: &Color) -> Phased{
+        self.scores[term as usize][*color as usize]
+    }
+
+    /// Returns the tapered score of `term` for the player of `color`
+    pub fn term(&self, term: Term, color: &Color) -> i32{
+        self.phased(term, color).taper(self.phase)
+    }
+
+    /// Returns how much `term` favours white, negative when it favours black
+    pub fn balance(&self, term: Term) -> i32{
+        self.term(term, &Color::White) - self.term(term, &Color::Black)
+    }
+
+    /// Returns the score of the position in centipawns from the point of view of white
+    pub fn total(&self) -> i32{
+        Term::ALL.iter().map(|term| self.balance(*term)).sum()
+    }
+
+    /// Returns the score of the position in centipawns from the point of view of the player of `color`
+    pub fn for_player(&self, color: &Color) -> i32{
+        match color{
+            Color::White => self.total(),
+            Color::Black => -self.total(),
+        }
+    }
+}
+
+/// Writes one line per term with the score of each player and the balance, in pawns
+impl Display for Evaluation{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16}{:>8}{:>8}{:>8}", "Term", "White", "Black", "Balance")?;
+
+        for term in Term::ALL{
+            let white = self.term(term, &Color::White) as f64 / 100.0;
+            let black = self.term(term, &Color::Black) as f64 / 100.0;
+            let balance = self.balance(term) as f64 / 100.0;
+
+            writeln!(f, "{:<16}{:>8.2}{:>8.2}{:>+8.2}", term, white, black, balance)?;
+        }
+
+        write!(f, "{:<16}{:>24}", "Total", format!("{:+.2}", self.total() as f64 / 100.0))
+    }
+}
+
+/// Scores the position in centipawns from the point of view of the player to move
+pub fn evaluate(game: &Game) -> i32{
+    Evaluation::of(game).for_player(game.turn())
+}
+
+fn game_phase(position: &Position) -> i32{
+    let phase = position.pieces_of_type(PieceType::Knight).count()
+        + position.pieces_of_type(PieceType::Bishop).count()
+        + 2 * position.pieces_of_type(PieceType::Rook).count()
+        + 4 * position.pieces_of_type(PieceType::Queen).count();
+
+    (phase as i32).min(MAX_PHASE)
+}
+
+/// Returns the rank of `square` counted from the side of the player of `color`, 0 being their first rank
+fn relative_rank(color: &Color, square: &AbsolutePosition) -> usize{
+    match color{
+        Color::White => square.rank,
+        Color::Black => 7 - square.rank,
+    }
+}
+
+/// Returns the index of `square` in a piece-square table, which is written from white's side, eighth rank first
+fn table_index(color: &Color, square: &AbsolutePosition) -> usize{
+    (7 - relative_rank(color, square)) * 8 + square.file
+}
+
+/// Returns the files next to `file`
+fn adjacent_files(file: usize) -> Bitboard{
+    let left = match file{
+        0 => Bitboard::EMPTY,
+        file => Bitboard::file(file - 1),
+    };
+    let right = match file{
+        7 => Bitboard::EMPTY,
+        file => Bitboard::file(file + 1),
+    };
+
+    left | right
+}
+
+/// Returns the ranks in front of `square` for the player of `color`
+fn ranks_ahead(color: &Color, square: &AbsolutePosition) -> Bitboard{
+    let ranks = match color{
+        Color::White => square.rank + 1..8,
+        Color::Black => 0..square.rank,
+    };
+
+    ranks.fold(Bitboard::EMPTY, |ahead, rank| ahead | Bitboard::rank(rank))
+}
+
+fn material(position: &Position, color: &Color) -> Phased{
+    PieceType::ALL.iter()
+        .map(|piece_type| Phased::new(piece_value(*piece_type), piece_value(*piece_type)) * position.pieces(color, *piece_type).count() as i32)
+        .fold(Phased::default(), |total, score| total + score)
+}
+
+fn piece_squares(position: &Position, color: &Color) -> Phased{
+    let mut score = Phased::default();
+
+    for piece_type in PieceType::ALL{
+        let (middlegame, endgame) = match piece_type{
+            PieceType::Pawn => (&PAWN_MIDDLEGAME, &PAWN_ENDGAME),
+            PieceType::Knight => (&KNIGHT, &KNIGHT),
+            PieceType::Bishop => (&BISHOP, &BISHOP),
+            PieceType::Rook => (&ROOK, &ROOK),
+            PieceType::Queen => (&QUEEN, &QUEEN),
+            PieceType::King => (&KING_MIDDLEGAME, &KING_ENDGAME),
+        };
+
+        for square in position.pieces(color, piece_type){
+            let index = table_index(color, &square);
+
+            score += Phased::new(middlegame[index], endgame[index]);
+        }
+    }
+
+    score
+}
+
+fn bishop_pair(position: &Position, color: &Color) -> Phased{
+    match position.pieces(color, PieceType::Bishop).count() >= 2{
+        true => BISHOP_PAIR,
+        false => Phased::default(),
+    }
+}
+
+fn pawn_structure(position: &Position, color: &Color) -> Phased{
+    let own = position.pieces(color, PieceType::Pawn);
+    let theirs = position.pieces(&color.opposite(), PieceType::Pawn);
+    let mut score = Phased::default();
+
+    for file in 0..8{
+        let count = (own & Bitboard::file(file)).count() as i32;
+
+        if count > 1{
+            score += DOUBLED_PAWN * (count - 1);
+        }
+
+        if count > 0 && (own & adjacent_files(file)).is_empty(){
+            score += ISOLATED_PAWN * count;
+        }
+    }
+
+    for square in own{
+        let ahead = ranks_ahead(color, &square);
+        let is_blocked_by_own = !(own & ahead & Bitboard::file(square.file)).is_empty();
+        let is_passed = (theirs & ahead & (Bitboard::file(square.file) | adjacent_files(square.file))).is_empty();
+
+        if is_passed && !is_blocked_by_own{
+            score += PASSED_PAWN[relative_rank(color, &square)];
+        }
+    }
+
+    score
+}
+
+fn mobility(position: &Position, color: &Color) -> Phased{
+    let opponent = color.opposite();
+    let guarded_by_pawns = position.pieces(&opponent, PieceType::Pawn).into_iter()
+        .fold(Bitboard::EMPTY, |guarded, square| guarded | attacks::pawn_attacks(&opponent, &square));
+    let area = !position.pieces_of(color) & !guarded_by_pawns;
+    let mut score = Phased::default();
+
+    for (piece_type, baseline, weight) in [
+        (PieceType::Knight, 4, Phased::new(4, 4)),
+        (PieceType::Bishop, 6, Phased::new(5, 5)),
+        (PieceType::Rook, 7, Phased::new(2, 4)),
+        (PieceType::Queen, 13, Phased::new(1, 2)),
+    ]{
+        for square in position.pieces(color, piece_type){
+            let moves = (position.attacks_from(&square) & area).count() as i32;
+
+            score += weight * (moves - baseline);
+        }
+    }
+
+    score
+}
+
+/// Only scored in the middlegame, once the attackers are traded the king is safe enough
+fn king_safety(position: &Position, color: &Color) -> Phased{
+    let king = match position.king(color){
+        Some(king) => king,
+        None => return Phased::default(),
+    };
+
+    let own_pawns = position.pieces(color, PieceType::Pawn);
+    let files_around = Bitboard::file(king.file) | adjacent_files(king.file);
+    let shield_ranks = [1, 2].iter()
+        .map(|step| king.rank as i32 + step * direction(color))
+        .filter(|rank| (0..8).contains(rank))
+        .fold(Bitboard::EMPTY, |ranks, rank| ranks | Bitboard::rank(rank as usize));
+    let shield = (own_pawns & files_around & shield_ranks).count().min(3) as i32;
+
+    let open_files = (0..8)
+        .filter(|file| files_around.contains(&AbsolutePosition::new(*file, 0)) && (own_pawns & Bitboard::file(*file)).is_empty())
+        .count() as i32;
+
+    let opponent = color.opposite();
+    let zone = attacks::king_attacks(&king) | Bitboard::from_square(&king);
+    let mut attack_units = 0;
+
+    for (piece_type, weight) in [(PieceType::Knight, 2), (PieceType::Bishop, 2), (PieceType::Rook, 3), (PieceType::Queen, 5)]{
+        for square in position.pieces(&opponent, piece_type){
+            attack_units += weight * (position.attacks_from(&square) & zone).count() as i32;
+        }
+    }
+
+    let middlegame = shield * PAWN_SHIELD + open_files * OPEN_FILE_NEAR_KING - (attack_units * attack_units / 4).min(500);
+
+    Phased::new(middlegame, 0)
+}
+
+/// The direction pawns of `color` move in, up the ranks for white
+fn direction(color: &Color) -> i32{
+    match color{
+        Color::White => 1,
+        Color::Black => -1,
+    }
+}
+
+fn rook_files(position: &Position, color: &Color) -> Phased{
+    let own_pawns = position.pieces(color, PieceType::Pawn);
+    let pawns = position.pieces_of_type(PieceType::Pawn);
+    let mut score = Phased::default();
+
+    for square in position.pieces(color, PieceType::Rook){
+        let file = Bitboard::file(square.file);
+
+        if (pawns & file).is_empty(){
+            score += ROOK_OPEN_FILE;
+        }
+        else if (own_pawns & file).is_empty(){
+            score += ROOK_HALF_OPEN_FILE;
+        }
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::fen::STARTING_FEN;
+
+    use super::*;
+
+    fn evaluate_fen(fen: &str) -> Evaluation{
+        Evaluation::of(&Game::from_fen(fen).unwrap())
+    }
+
+    #[test]
+    fn test_symmetric_positions_are_even(){
+        let evaluation = evaluate_fen(STARTING_FEN);
+
+        assert_eq!(evaluation.phase(), MAX_PHASE);
+        assert_eq!(evaluation.total(), 0);
+
+        for term in Term::ALL{
+            assert_eq!(evaluation.balance(term), 0, "{}", term);
+        }
+
+        assert_eq!(evaluation.term(Term::Material, &Color::White), 4000);
+    }
+
+    #[test]
+    fn test_score_is_for_the_player_to_move(){
+        let white = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let black = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1").unwrap();
+
+        assert!(evaluate(&white) > 400);
+        assert_eq!(evaluate(&black), -evaluate(&white));
+    }
+
+    #[test]
+    fn test_terms_add_up_to_the_total(){
+        let evaluation = evaluate_fen("r1bqk2r/pp1n1ppp/2pbpn2/3p4/2PP4/2N1PN2/PPQ2PPP/R1B1KB1R w KQkq - 2 7");
+
+        assert_eq!(evaluation.total(), Term::ALL.iter().map(|term| evaluation.balance(*term)).sum::<i32>());
+        assert_eq!(evaluation.for_player(&Color::Black), -evaluation.total());
+    }
+
+    #[test]
+    fn test_the_king_belongs_in_the_center_once_pieces_are_traded(){
+        let sheltered = evaluate_fen("4k3/8/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let centralised = evaluate_fen("4k3/8/8/8/4K3/8/5PPP/8 w - - 0 1");
+
+        assert_eq!(sheltered.phase(), 0);
+        assert!(centralised.term(Term::PieceSquares, &Color::White) > sheltered.term(Term::PieceSquares, &Color::White));
+
+        let sheltered = evaluate_fen("rnbqkbnr/8/8/8/8/8/5PPP/RNBQ1RK1 w - - 0 1");
+        let centralised = evaluate_fen("rnbqkbnr/8/8/8/4K3/8/5PPP/RNBQ1R2 w - - 0 1");
+
+        assert!(centralised.term(Term::PieceSquares, &Color::White) < sheltered.term(Term::PieceSquares, &Color::White));
+        assert!(centralised.term(Term::KingSafety, &Color::White) < sheltered.term(Term::KingSafety, &Color::White));
+    }
+
+    #[test]
+    fn test_pawn_structure(){
+        let doubled_and_isolated = evaluate_fen("4k3/8/8/8/8/2P5/2P3PP/4K3 w - - 0 1");
+        let healthy = evaluate_fen("4k3/8/8/8/8/8/1PP3PP/4K3 w - - 0 1");
+
+        assert!(doubled_and_isolated.term(Term::PawnStructure, &Color::White) < healthy.term(Term::PawnStructure, &Color::White));
+
+        let passed = evaluate_fen("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1");
+        let stopped = evaluate_fen("4k3/2p5/3P4/8/8/8/8/4K3 w - - 0 1");
+
+        assert_eq!(passed.phased(Term::PawnStructure, &Color::White), ISOLATED_PAWN + PASSED_PAWN[5]);
+        assert_eq!(stopped.phased(Term::PawnStructure, &Color::White), ISOLATED_PAWN);
+        assert_eq!(stopped.phased(Term::PawnStructure, &Color::Black), ISOLATED_PAWN);
+    }
+
+    #[test]
+    fn test_pieces_bonuses(){
+        let evaluation = evaluate_fen("1r2k3/1p6/8/8/8/8/1P6/2BRKB2 w - - 0 1");
+
+        assert_eq!(evaluation.phased(Term::BishopPair, &Color::White), BISHOP_PAIR);
+        assert_eq!(evaluation.phased(Term::BishopPair, &Color::Black), Phased::default());
+        assert_eq!(evaluation.phased(Term::RookFiles, &Color::White), ROOK_OPEN_FILE);
+        assert_eq!(evaluation.phased(Term::RookFiles, &Color::Black), Phased::default());
+
+        let cornered = evaluate_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+        let centralised = evaluate_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1");
+
+        assert!(centralised.term(Term::Mobility, &Color::White) > cornered.term(Term::Mobility, &Color::White));
+    }
+
+    #[test]
+    fn test_king_safety(){
+        let sheltered = evaluate_fen("r5k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let exposed = evaluate_fen("r5k1/5ppp/8/8/8/8/8/6K1 w - - 0 1");
+        let attacked = evaluate_fen("6k1/5ppp/8/8/8/8/5PPq/6K1 w - - 0 1");
+
+        assert_eq!(sheltered.phased(Term::KingSafety, &Color::White), Phased::new(3 * PAWN_SHIELD, 0));
+        assert!(exposed.term(Term::KingSafety, &Color::White) < 0);
+        assert!(attacked.term(Term::KingSafety, &Color::White) < sheltered.term(Term::KingSafety, &Color::White));
+    }
+
+    #[test]
+    fn test_display_lists_every_term(){
+        let text = evaluate_fen(STARTING_FEN).to_string();
+
+        assert_eq!(text.lines().count(), Term::ALL.len() + 2);
+        assert!(text.contains("Bishop pair"));
+        assert!(text.ends_with("+0.00"));
+    }
+}
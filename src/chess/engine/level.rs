@@ -0,0 +1,175 @@
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::chess::{error::ChessError, game::Game, movement::Movement};
+
+use super::{evaluation::evaluate, Engine, Score, SearchLimits};
+
+
+/// How strongly the engine plays against a person, from 1 to 6
+///
+/// The lowest levels play random legal moves, always at level 1 and half of the time at
+/// level 2. The others search deeper and longer as the level goes up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Level(u8);
+
+impl Level{
+    pub const MIN: Level = Level(1);
+    pub const MAX: Level = Level(6);
+
+    /// Creates a level
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing the level, or `ChessError::InvalidLevel` outside of 1 to 6
+    pub fn new(level: u8) -> Result<Self, ChessError>{
+        match (Level::MIN.0..=Level::MAX.0).contains(&level){
+            true => Ok(Level(level)),
+            false => Err(ChessError::InvalidLevel(level)),
+        }
+    }
+
+    pub fn value(&self) -> u8{
+        self.0
+    }
+
+    /// Returns the chance of playing a random legal move instead of searching, from 0 to 1
+    pub fn random_move_chance(&self) -> f64{
+        match self.0{
+            1 => 1.0,
+            2 => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns how far the engine searches when it does not play a random move
+    pub fn limits(&self) -> SearchLimits{
+        match self.0{
+            1 | 2 => SearchLimits::depth(1),
+            3 => SearchLimits::depth(2),
+            4 => SearchLimits::depth(4),
+            5 => SearchLimits{depth: Some(6), time: Some(Duration::from_secs(1)), nodes: None},
+            _ => SearchLimits::time(Duration::from_secs(3)),
+        }
+    }
+}
+
+impl Default for Level{
+    fn default() -> Self{
+        Level(3)
+    }
+}
+
+impl FromStr for Level{
+    type Err = ChessError;
+
+    fn from_str(string: &str) -> Result<Self, ChessError>{
+        let level = string.trim().parse::<u8>()
+            .map_err(|_| ChessError::ParseError(format!("Invalid level {:?}, expected {} to {}", string, Level::MIN, Level::MAX)))?;
+
+        Level::new(level)
+    }
+}
+
+impl Display for Level{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The move the engine picked with `Engine::play`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Choice{
+    pub movement: Movement,
+    /// The score of the position for the engine, the static evaluation after a random move
+    pub score: Score,
+    /// Whether the move was picked at random instead of searched
+    pub is_random: bool,
+}
+
+impl Engine{
+    /// Picks the move to play for the player to move in `game` at `level`
+    ///
+    /// ### Returns
+    ///
+    /// Returns the chosen move with its score, `None` when there is no legal move
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use better_chess::{ClassicGame, engine::{Engine, level::Level}};
+    ///
+    /// let game = ClassicGame::new();
+    /// let choice = Engine::new().play(&game, Level::MIN).unwrap();
+    ///
+    /// assert!(choice.is_random);
+    /// assert!(game.legal_moves().contains(&choice.movement));
+    /// ```
+    pub fn play(&mut self, game: &Game, level: Level) -> Option<Choice>{
+        let legal_moves = game.legal_moves();
+        let mut rng = rand::thread_rng();
+
+        if rng.gen_bool(level.random_move_chance()){
+            let movement = *legal_moves.choose(&mut rng)?;
+            let mut after = game.clone();
+
+            after.move_piece(movement).ok()?;
+
+            return Some(Choice{
+                movement,
+                score: Score::Centipawns(-evaluate(&after)),
+                is_random: true,
+            });
+        }
+
+        let result = self.search(game, level.limits());
+
+        Some(Choice{
+            movement: result.best_move?,
+            score: result.score,
+            is_random: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::classic::ClassicGame;
+
+    use super::*;
+
+    #[test]
+    fn test_levels_go_from_random_to_searching(){
+        assert_eq!(Level::new(0), Err(ChessError::InvalidLevel(0)));
+        assert_eq!(Level::new(7), Err(ChessError::InvalidLevel(7)));
+        assert_eq!("4".parse::<Level>(), Ok(Level(4)));
+        assert!("four".parse::<Level>().is_err());
+
+        assert_eq!(Level::MIN.random_move_chance(), 1.0);
+        assert_eq!(Level::new(3).unwrap().random_move_chance(), 0.0);
+        assert_eq!(Level::MAX.limits().time, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_searching_levels_find_the_mate(){
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let choice = Engine::new().play(&game, Level::new(3).unwrap()).unwrap();
+
+        assert!(!choice.is_random);
+        assert_eq!(game.to_san(&choice.movement), "Ra8#");
+        assert_eq!(choice.score, Score::Mate(1));
+    }
+
+    #[test]
+    fn test_no_move_once_the_game_is_over(){
+        let mut game = ClassicGame::new();
+
+        for san in ["f3", "e5", "g4", "Qh4#"]{
+            game.move_piece(game.parse_san(san).unwrap()).unwrap();
+        }
+
+        assert_eq!(Engine::new().play(&game, Level::MIN), None);
+        assert_eq!(Engine::new().play(&game, Level::MAX), None);
+    }
+}
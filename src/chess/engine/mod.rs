@@ -0,0 +1,328 @@
+//! A chess engine: iterative deepening alpha-beta search over a `Game`
+//!
+//! The search is a negamax with a quiescence search on captures, MVV-LVA, killer and history
+//! move ordering and a transposition table keyed by the Zobrist hash of the positions.
+
+use std::{fmt::Display, sync::atomic::AtomicBool, time::{Duration, Instant}};
+
+use self::{evaluation::evaluate, ordering::MoveOrdering, search::Search, transposition::TranspositionTable};
+
+use super::{game::Game, movement::Movement};
+
+pub mod evaluation;
+pub mod level;
+pub mod ordering;
+pub mod transposition;
+pub mod uci;
+mod search;
+
+
+/// The deepest the search ever looks, in plies from the root
+pub const MAX_PLY: usize = 128;
+
+/// The score of a checkmate on the board, a mate further away scores one less per ply
+pub const MATE: i32 = 30_000;
+
+/// The number of transposition table entries of `Engine::new`
+pub const DEFAULT_TABLE_SIZE: usize = 1 << 16;
+
+/// When a search has to stop, a search without any limit goes on until `MAX_PLY`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SearchLimits{
+    /// The deepest iteration, in plies
+    pub depth: Option<u32>,
+    /// The number of positions after which the search stops
+    pub nodes: Option<u64>,
+    /// How long the search may run
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits{
+    pub fn depth(depth: u32) -> Self{
+        SearchLimits{depth: Some(depth), ..SearchLimits::default()}
+    }
+
+    pub fn nodes(nodes: u64) -> Self{
+        SearchLimits{nodes: Some(nodes), ..SearchLimits::default()}
+    }
+
+    pub fn time(time: Duration) -> Self{
+        SearchLimits{time: Some(time), ..SearchLimits::default()}
+    }
+}
+
+/// The score of a position for the player to move
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Score{
+    /// The advantage in hundredths of a pawn
+    Centipawns(i32),
+    /// Mate in that many moves, negative when the player to move gets mated
+    Mate(i32),
+}
+
+impl Score{
+    /// Converts a score of the search, where mates count down from `MATE`
+    pub fn from_search(score: i32) -> Self{
+        let plies = MATE - score.abs();
+
+        if plies > MAX_PLY as i32{
+            return Score::Centipawns(score);
+        }
+
+        match score > 0{
+            true => Score::Mate((plies + 1) / 2),
+            false => Score::Mate(-plies / 2),
+        }
+    }
+}
+
+/// Writes centipawns as pawns, like `+0.35`, and mates as `#3` or `#-2`
+impl Display for Score{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            Score::Centipawns(centipawns) => write!(f, "{:+.2}", *centipawns as f64 / 100.0),
+            Score::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+/// What the deepest finished iteration of a search found
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchResult{
+    /// The move to play, `None` when the game is over
+    pub best_move: Option<Movement>,
+    pub score: Score,
+    /// The expected line of play, starting with the best move
+    pub principal_variation: Vec<Movement>,
+    /// The number of positions visited
+    pub nodes: u64,
+    /// The depth of the last finished iteration
+    pub depth: u32,
+    /// How long the search has been running
+    pub time: Duration,
+}
+
+/// Finds moves for the player to move in a `Game`
+///
+/// The engine keeps its transposition table and move ordering between searches, so searching
+/// the positions of one game in turn reuses what was learned. Call `clear` before a new game.
+///
+/// ## Examples
+///
+/// ```
+/// use better_chess::{Game, engine::{Engine, SearchLimits, Score}};
+///
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+/// let result = Engine::new().search(&game, SearchLimits::depth(3));
+///
+/// assert_eq!(result.score, Score::Mate(1));
+/// assert_eq!(game.to_san(&result.best_move.unwrap()), "Ra8#");
+/// ```
+pub struct Engine{
+    table: TranspositionTable,
+    ordering: MoveOrdering,
+}
+
+impl Engine{
+    pub fn new() -> Self{
+        Engine::with_table_size(DEFAULT_TABLE_SIZE)
+    }
+
+    /// Creates an engine whose transposition table holds `size` entries, rounded down to a power of two
+    pub fn with_table_size(size: usize) -> Self{
+        Engine{
+            table: TranspositionTable::new(size),
+            ordering: MoveOrdering::new(),
+        }
+    }
+
+    /// Forgets everything learned in earlier searches
+    pub fn clear(&mut self){
+        self.table.clear();
+        self.ordering = MoveOrdering::new();
+    }
+
+    /// Searches the position of `game` one ply deeper at a time until a limit is reached
+    ///
+    /// ### Arguments
+    ///
+    /// * `game` - The game to find a move in, it is left untouched
+    /// * `limits` - When to stop, an iteration cut short by the node or time limit is thrown away
+    ///
+    /// ### Returns
+    ///
+    /// Returns the result of the deepest finished iteration. If not even the first one finished,
+    /// the best move is the first legal move.
+    pub fn search(&mut self, game: &Game, limits: SearchLimits) -> SearchResult{
+        self.search_with(game, limits, &AtomicBool::new(false), |_| ())
+    }
+
+    /// Searches like `search`, but can be stopped from another thread and reports each iteration
+    ///
+    /// ### Arguments
+    ///
+    /// * `game` - The game to find a move in, it is left untouched
+    /// * `limits` - When to stop, an iteration cut short by a limit or by `stop` is thrown away
+    /// * `stop` - Stops the search soon after it is set
+    /// * `report` - Called with the result of every finished iteration
+    ///
+    /// ### Returns
+    ///
+    /// Returns the result of the deepest finished iteration
+    pub fn search_with(&mut self, game: &Game, limits: SearchLimits, stop: &AtomicBool, mut report: impl FnMut(&SearchResult)) -> SearchResult{
+        let start = Instant::now();
+        let mut game = game.clone();
+        let legal_moves = game.legal_moves();
+
+        let mut result = SearchResult{
+            best_move: legal_moves.first().copied(),
+            score: Score::Centipawns(evaluate(&game)),
+            principal_variation: Vec::new(),
+            nodes: 0,
+            depth: 0,
+            time: Duration::ZERO,
+        };
+
+        if legal_moves.is_empty(){
+            if game.position().in_check(game.turn()){
+                result.score = Score::Mate(0);
+            }
+
+            return result;
+        }
+
+        let max_depth = limits.depth.unwrap_or(MAX_PLY as u32 - 1).min(MAX_PLY as u32 - 1);
+        let mut search = Search::new(&mut self.table, &mut self.ordering, limits, stop);
+
+        for depth in 1..=max_depth{
+            let mut line = Vec::new();
+            let score = search.negamax(&mut game, depth, -MATE - 1, MATE + 1, 0, &mut line);
+
+            if search.stopped{
+                break;
+            }
+
+            result = SearchResult{
+                best_move: line.first().copied().or(result.best_move),
+                score: Score::from_search(score),
+                principal_variation: line,
+                nodes: search.nodes,
+                depth,
+                time: start.elapsed(),
+            };
+
+            report(&result);
+
+            let is_mate_found = MATE - score.abs() <= depth as i32;
+
+            if is_mate_found || search.is_out_of_time(){
+                break;
+            }
+        }
+
+        result.nodes = search.nodes;
+        result.time = start.elapsed();
+
+        result
+    }
+}
+
+impl Default for Engine{
+    fn default() -> Self{
+        Engine::new()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{game::fen::STARTING_FEN, movement::absolute_position::AbsolutePosition};
+
+    use super::*;
+
+    fn search(fen: &str, limits: SearchLimits) -> (Game, SearchResult){
+        let game = Game::from_fen(fen).unwrap();
+        let result = Engine::new().search(&game, limits);
+
+        (game, result)
+    }
+
+    #[test]
+    fn test_finds_mate_in_one(){
+        let (game, result) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", SearchLimits::depth(4));
+
+        assert_eq!(result.score, Score::Mate(1));
+        assert_eq!(game.to_san(&result.best_move.unwrap()), "Ra8#");
+        assert_eq!(result.principal_variation.len(), 1);
+    }
+
+    #[test]
+    fn test_finds_mate_in_two(){
+        let (game, result) = search("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", SearchLimits::depth(3));
+
+        assert_eq!(game.to_san(&result.best_move.unwrap()), "Qxf7#");
+
+        let (game, result) = search("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1", SearchLimits::depth(5));
+
+        assert_eq!(result.score, Score::Mate(2));
+        assert_eq!(game.to_san(&result.best_move.unwrap()), "Ra6");
+        assert_eq!(result.principal_variation.len(), 3);
+    }
+
+    #[test]
+    fn test_sees_the_side_to_move_getting_mated(){
+        let (_, result) = search("7k/8/8/8/8/8/5PPP/r5K1 w - - 0 1", SearchLimits::depth(2));
+
+        assert_eq!(result.score, Score::Mate(0));
+        assert_eq!(result.best_move, None);
+
+        let (_, result) = search("6k1/8/8/8/8/8/r7/6K1 b - - 0 1", SearchLimits::depth(2));
+
+        assert!(matches!(result.score, Score::Centipawns(score) if score > 400));
+    }
+
+    #[test]
+    fn test_wins_a_hanging_piece_and_avoids_losing_one(){
+        let (game, result) = search("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", SearchLimits::depth(2));
+
+        assert_eq!(game.to_san(&result.best_move.unwrap()), "Rxd5");
+
+        let (_, result) = search("4k3/8/2p5/3p4/8/8/3R4/4K3 w - - 0 1", SearchLimits::depth(3));
+
+        assert_ne!(result.best_move.map(|movement| *movement.to()), Some(AbsolutePosition::new(3, 4)));
+    }
+
+    #[test]
+    fn test_principal_variation_is_playable(){
+        let (mut game, result) = search(STARTING_FEN, SearchLimits::depth(4));
+
+        assert_eq!(result.depth, 4);
+        assert_eq!(result.principal_variation.first().copied(), result.best_move);
+
+        for movement in result.principal_variation{
+            assert!(game.move_piece(movement).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_node_and_time_limits_stop_the_search(){
+        let (_, result) = search(STARTING_FEN, SearchLimits::nodes(2000));
+
+        assert!(result.nodes <= 2000);
+        assert!(result.best_move.is_some());
+
+        let (_, result) = search(STARTING_FEN, SearchLimits::time(Duration::from_millis(50)));
+
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+    }
+
+    #[test]
+    fn test_scores_are_displayed_in_pawns_or_moves_to_mate(){
+        assert_eq!(Score::from_search(35).to_string(), "+0.35");
+        assert_eq!(Score::from_search(-120).to_string(), "-1.20");
+        assert_eq!(Score::from_search(MATE - 1), Score::Mate(1));
+        assert_eq!(Score::from_search(MATE - 3), Score::Mate(2));
+        assert_eq!(Score::from_search(-MATE + 2), Score::Mate(-1));
+        assert_eq!(Score::Mate(-1).to_string(), "#-1");
+    }
+}
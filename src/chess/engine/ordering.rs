@@ -0,0 +1,157 @@
+//! Move ordering, searching the likely best moves first makes alpha-beta cut off sooner
+//!
+//! Moves are tried in this order: the move stored in the transposition table, captures by
+//! most valuable victim and least valuable attacker (MVV-LVA), the two killer moves of the ply,
+//! then the quiet moves by history score.
+
+use crate::chess::{bitboard::square_index, color::Color, game::Game, movement::Movement, piece::PieceType};
+
+use super::MAX_PLY;
+
+
+const TABLE_MOVE: i32 = 1_000_000;
+const CAPTURE: i32 = 100_000;
+const PROMOTION: i32 = 90_000;
+const FIRST_KILLER: i32 = 80_000;
+const SECOND_KILLER: i32 = 70_000;
+
+/// What earlier parts of the search learned about quiet moves
+pub struct MoveOrdering{
+    killers: Vec<[Option<Movement>; 2]>,
+    history: Vec<i32>,
+}
+
+impl MoveOrdering{
+    pub fn new() -> Self{
+        MoveOrdering{
+            killers: vec![[None; 2]; MAX_PLY],
+            history: vec![0; 2 * 64 * 64],
+        }
+    }
+
+    fn history_index(color: &Color, movement: &Movement) -> usize{
+        (*color as usize * 64 + square_index(movement.from())) * 64 + square_index(movement.to())
+    }
+
+    /// Remembers a quiet move that caused a beta cutoff at `ply`
+    pub fn record_cutoff(&mut self, color: &Color, movement: &Movement, ply: usize, depth: u32){
+        if self.killers[ply][0] != Some(*movement){
+            self.killers[ply][1] = self.killers[ply][0];
+            self.killers[ply][0] = Some(*movement);
+        }
+
+        let index = MoveOrdering::history_index(color, movement);
+        self.history[index] = (self.history[index] + (depth * depth) as i32).min(CAPTURE / 2);
+    }
+
+    /// Sorts `movements` so the most promising ones come first
+    pub fn sort(&self, game: &Game, movements: &mut [Movement], table_move: Option<Movement>, ply: usize){
+        movements.sort_by_cached_key(|movement| -self.score(game, movement, table_move, ply));
+    }
+
+    fn score(&self, game: &Game, movement: &Movement, table_move: Option<Movement>, ply: usize) -> i32{
+        if table_move == Some(*movement){
+            return TABLE_MOVE;
+        }
+
+        if let Some(victim) = game.captured_piece(movement){
+            return CAPTURE + mvv_lva(game, movement, victim);
+        }
+
+        if let Some(promotion) = movement.promotion(){
+            return PROMOTION + piece_order(promotion);
+        }
+
+        if ply < MAX_PLY{
+            if self.killers[ply][0] == Some(*movement){
+                return FIRST_KILLER;
+            }
+
+            if self.killers[ply][1] == Some(*movement){
+                return SECOND_KILLER;
+            }
+        }
+
+        self.history[MoveOrdering::history_index(game.turn(), movement)]
+    }
+}
+
+impl Default for MoveOrdering{
+    fn default() -> Self{
+        MoveOrdering::new()
+    }
+}
+
+/// A rank of the pieces by value, the king last since it can never be captured
+fn piece_order(piece_type: PieceType) -> i32{
+    match piece_type{
+        PieceType::Pawn => 1,
+        PieceType::Knight => 2,
+        PieceType::Bishop => 3,
+        PieceType::Rook => 4,
+        PieceType::Queen => 5,
+        PieceType::King => 6,
+    }
+}
+
+/// Scores a capture by most valuable victim first, then least valuable attacker
+pub fn mvv_lva(game: &Game, movement: &Movement, victim: PieceType) -> i32{
+    let attacker = game.position().piece_at(movement.from()).map_or(PieceType::Pawn, |(_, piece_type)| piece_type);
+
+    piece_order(victim) * 10 - piece_order(attacker)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{game::chess960::Chess960Game, movement::absolute_position::AbsolutePosition};
+
+    use super::*;
+
+    fn movement(from: &str, to: &str) -> Movement{
+        let square = |name: &str| {
+            let bytes = name.as_bytes();
+            AbsolutePosition::new((bytes[0] - b'a') as usize, (bytes[1] - b'1') as usize)
+        };
+
+        Movement::new(square(from), square(to))
+    }
+
+    #[test]
+    fn test_captures_are_ordered_by_victim_then_attacker(){
+        let game = Game::from_fen("4k3/4r3/2q5/1P1N4/8/8/8/6K1 w - - 0 1").unwrap();
+        let ordering = MoveOrdering::new();
+        let mut movements = game.legal_moves();
+
+        ordering.sort(&game, &mut movements, None, 0);
+
+        assert_eq!(movements[0], movement("b5", "c6"));
+        assert_eq!(movements[1], movement("d5", "e7"));
+        assert!(game.captured_piece(&movements[2]).is_none());
+    }
+
+    #[test]
+    fn test_chess960_castle_is_ordered_as_a_quiet_move(){
+        let game = Chess960Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let castle = movement("e1", "h1");
+
+        assert!(game.legal_moves().contains(&castle));
+        assert!(MoveOrdering::new().score(&game, &castle, None, 0) < CAPTURE);
+    }
+
+    #[test]
+    fn test_killers_come_before_other_quiet_moves(){
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let mut ordering = MoveOrdering::new();
+        let killer = movement("a1", "a7");
+        let mut movements = game.legal_moves();
+
+        ordering.record_cutoff(game.turn(), &killer, 3, 4);
+
+        ordering.sort(&game, &mut movements, None, 3);
+        assert_eq!(movements[0], killer);
+
+        ordering.sort(&game, &mut movements, Some(movement("e1", "f2")), 3);
+        assert_eq!(movements[0], movement("e1", "f2"));
+        assert_eq!(movements[1], killer);
+    }
+}
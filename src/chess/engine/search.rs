@@ -0,0 +1,246 @@
+use std::{sync::atomic::{AtomicBool, Ordering}, time::Instant};
+
+use crate::chess::{game::Game, movement::Movement, piece::PieceType};
+
+use super::{evaluation::evaluate, ordering::MoveOrdering, transposition::{Bound, Entry, TranspositionTable}, SearchLimits, MATE, MAX_PLY};
+
+
+/// How many nodes are searched between two looks at the clock and the stop flag
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// One search, from the first iteration to the last, sharing the engine's tables
+pub(super) struct Search<'a>{
+    table: &'a mut TranspositionTable,
+    ordering: &'a mut MoveOrdering,
+    limits: SearchLimits,
+    stop: &'a AtomicBool,
+    start: Instant,
+    pub(super) nodes: u64,
+    pub(super) stopped: bool,
+}
+
+impl<'a> Search<'a>{
+    pub(super) fn new(table: &'a mut TranspositionTable, ordering: &'a mut MoveOrdering, limits: SearchLimits, stop: &'a AtomicBool) -> Self{
+        Search{
+            table,
+            ordering,
+            limits,
+            stop,
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+        }
+    }
+
+    /// Whether a limit has been reached or the search was told to stop, once it has the search unwinds without a result
+    fn should_stop(&mut self) -> bool{
+        if self.stopped{
+            return true;
+        }
+
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes){
+            self.stopped = true;
+        }
+
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL){
+            let is_out_of_time = self.limits.time.is_some_and(|time| self.start.elapsed() >= time);
+
+            if is_out_of_time || self.stop.load(Ordering::Relaxed){
+                self.stopped = true;
+            }
+        }
+
+        self.stopped
+    }
+
+    /// Whether the search spent more than half its time, another iteration would not finish
+    pub(super) fn is_out_of_time(&self) -> bool{
+        self.limits.time.is_some_and(|time| self.start.elapsed() * 2 >= time)
+    }
+
+    /// Searches `game` to `depth` plies and returns its score for the player to move
+    ///
+    /// ### Arguments
+    ///
+    /// * `alpha` - The score the player to move is already sure to get
+    /// * `beta` - The score the opponent is already sure to hold the player to
+    /// * `ply` - The distance from the root, used to prefer the shortest mate
+    /// * `pv` - Filled with the best line found from this position
+    pub(super) fn negamax(&mut self, game: &mut Game, depth: u32, mut alpha: i32, beta: i32, ply: usize, pv: &mut Vec<Movement>) -> i32{
+        pv.clear();
+
+        if ply > 0 && is_draw(game){
+            return 0;
+        }
+
+        let in_check = game.position().in_check(game.turn());
+        let depth = match in_check{
+            true => depth + 1,
+            false => depth,
+        };
+
+        if depth == 0 || ply >= MAX_PLY - 1{
+            return self.quiescence(game, alpha, beta, ply);
+        }
+
+        self.nodes += 1;
+
+        if self.should_stop(){
+            return 0;
+        }
+
+        let key = game.zobrist_hash();
+        let mut table_move = None;
+
+        if let Some(entry) = self.table.probe(key){
+            table_move = entry.best_move;
+
+            if ply > 0 && entry.depth >= depth{
+                let score = score_from_table(entry.score, ply);
+
+                match entry.bound{
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => (),
+                }
+            }
+        }
+
+        let mut movements = game.generate_legal_plays();
+
+        if movements.is_empty(){
+            return match in_check{
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        self.ordering.sort(game, &mut movements, table_move, ply);
+
+        let original_alpha = alpha;
+        let mut best_score = -MATE;
+        let mut best_move = None;
+        let mut line = Vec::new();
+
+        for movement in movements{
+            let is_quiet = game.captured_piece(&movement).is_none() && movement.promotion().is_none();
+
+            game.make_move(movement).expect("A generated move can be made");
+            let score = -self.negamax(game, depth - 1, -beta, -alpha, ply + 1, &mut line);
+            game.unmake_move();
+
+            if self.stopped{
+                return 0;
+            }
+
+            if score > best_score{
+                best_score = score;
+                best_move = Some(movement);
+
+                if score > alpha{
+                    alpha = score;
+                    pv.clear();
+                    pv.push(movement);
+                    pv.extend_from_slice(&line);
+                }
+            }
+
+            if alpha >= beta{
+                if is_quiet{
+                    self.ordering.record_cutoff(game.turn(), &movement, ply, depth);
+                }
+
+                break;
+            }
+        }
+
+        let bound = match best_score{
+            score if score <= original_alpha => Bound::Upper,
+            score if score >= beta => Bound::Lower,
+            _ => Bound::Exact,
+        };
+
+        self.table.store(Entry{key, depth, score: score_to_table(best_score, ply), bound, best_move});
+
+        best_score
+    }
+
+    /// Searches captures and queen promotions until the position is quiet, so a search never
+    /// stops right in the middle of an exchange
+    fn quiescence(&mut self, game: &mut Game, mut alpha: i32, beta: i32, ply: usize) -> i32{
+        self.nodes += 1;
+
+        if self.should_stop(){
+            return 0;
+        }
+
+        let movements = game.generate_legal_plays();
+
+        if movements.is_empty(){
+            return match game.position().in_check(game.turn()){
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        let stand_pat = evaluate(game);
+
+        if stand_pat >= beta || ply >= MAX_PLY - 1{
+            return stand_pat;
+        }
+
+        alpha = alpha.max(stand_pat);
+
+        let mut captures : Vec<Movement> = movements.into_iter()
+            .filter(|movement| game.captured_piece(movement).is_some() || movement.promotion() == Some(PieceType::Queen))
+            .collect();
+
+        self.ordering.sort(game, &mut captures, None, ply);
+
+        let mut best_score = stand_pat;
+
+        for movement in captures{
+            game.make_move(movement).expect("A generated move can be made");
+            let score = -self.quiescence(game, -beta, -alpha, ply + 1);
+            game.unmake_move();
+
+            if self.stopped{
+                return 0;
+            }
+
+            if score > best_score{
+                best_score = score;
+                alpha = alpha.max(score);
+            }
+
+            if alpha >= beta{
+                break;
+            }
+        }
+
+        best_score
+    }
+}
+
+/// Draws the search recognises on its own: the fifty move rule, a repetition and dead positions
+fn is_draw(game: &Game) -> bool{
+    game.halfmove_clock() >= 100 || game.is_insufficient_material() || game.repetitions() >= 2
+}
+
+/// Mate scores count plies from the root, the table stores them counted from the position instead
+fn score_to_table(score: i32, ply: usize) -> i32{
+    match score{
+        score if score >= MATE - MAX_PLY as i32 => score + ply as i32,
+        score if score <= -MATE + MAX_PLY as i32 => score - ply as i32,
+        score => score,
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32{
+    match score{
+        score if score >= MATE - MAX_PLY as i32 => score - ply as i32,
+        score if score <= -MATE + MAX_PLY as i32 => score + ply as i32,
+        score => score,
+    }
+}
@@ -0,0 +1,110 @@
+use crate::chess::movement::Movement;
+
+
+/// How the stored score relates to the real score of the position
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound{
+    /// The score is exact, it fell between alpha and beta
+    Exact,
+    /// The search failed high, the real score is at least the stored one
+    Lower,
+    /// The search failed low, the real score is at most the stored one
+    Upper,
+}
+
+/// What a search found out about a position
+#[derive(Clone, Copy, Debug)]
+pub struct Entry{
+    pub key: u64,
+    pub depth: u32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Movement>,
+}
+
+/// A fixed size table of search results indexed by Zobrist hash
+///
+/// Each hash maps to a single slot, a new entry replaces the old one unless the old one
+/// describes the same position searched deeper.
+pub struct TranspositionTable{
+    entries: Vec<Option<Entry>>,
+}
+
+impl TranspositionTable{
+    /// Creates a table with room for `size` entries, rounded down to a power of two
+    pub fn new(size: usize) -> Self{
+        let size = match size.max(1).checked_next_power_of_two(){
+            Some(power) if power == size.max(1) => power,
+            Some(power) => power / 2,
+            None => 1 << (usize::BITS - 1),
+        };
+
+        TranspositionTable{
+            entries: vec![None; size],
+        }
+    }
+
+    fn index(&self, key: u64) -> usize{
+        key as usize & (self.entries.len() - 1)
+    }
+
+    /// Returns the entry of the position with hash `key`, if it is still in the table
+    pub fn probe(&self, key: u64) -> Option<&Entry>{
+        self.entries[self.index(key)].as_ref().filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&mut self, entry: Entry){
+        let index = self.index(entry.key);
+
+        if let Some(old) = &self.entries[index]{
+            if old.key == entry.key && old.depth > entry.depth{
+                return;
+            }
+        }
+
+        self.entries[index] = Some(entry);
+    }
+
+    /// Forgets every stored position
+    pub fn clear(&mut self){
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+    }
+
+    pub fn size(&self) -> usize{
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::movement::absolute_position::AbsolutePosition;
+
+    use super::*;
+
+    fn entry(key: u64, depth: u32) -> Entry{
+        Entry{key, depth, score: 10, bound: Bound::Exact, best_move: Some(Movement::new(AbsolutePosition::new(4, 1), AbsolutePosition::new(4, 3)))}
+    }
+
+    #[test]
+    fn test_size_is_a_power_of_two(){
+        assert_eq!(TranspositionTable::new(1000).size(), 512);
+        assert_eq!(TranspositionTable::new(1024).size(), 1024);
+        assert_eq!(TranspositionTable::new(0).size(), 1);
+    }
+
+    #[test]
+    fn test_deeper_entries_are_kept(){
+        let mut table = TranspositionTable::new(16);
+
+        table.store(entry(3, 5));
+        table.store(entry(3, 2));
+
+        assert_eq!(table.probe(3).map(|entry| entry.depth), Some(5));
+        assert!(table.probe(19).is_none());
+
+        table.store(entry(19, 1));
+
+        assert!(table.probe(3).is_none());
+        assert_eq!(table.probe(19).map(|entry| entry.depth), Some(1));
+    }
+}
@@ -0,0 +1,413 @@
+//! The Universal Chess Interface, the text protocol chess GUIs and tournament managers use to
+//! talk to engines
+//!
+//! A `UciSession` reads one command per line and writes its answers to an output. Searches run on
+//! a thread of their own, so `stop`, `isready` and `quit` are answered while the engine thinks.
+
+use std::{io::{BufRead, Write}, mem::size_of, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, thread::{self, JoinHandle}, time::Duration};
+
+use crate::chess::{color::Color, game::{chess960::Chess960Game, fen::STARTING_FEN, Game}, movement::Movement};
+
+use super::{transposition::Entry, Engine, Score, SearchLimits, SearchResult};
+
+
+/// The size of the transposition table before any `setoption name Hash`, in megabytes
+pub const DEFAULT_HASH_MEGABYTES: usize = 4;
+
+const MAX_HASH_MEGABYTES: usize = 1024;
+
+/// The time kept aside on every move for the GUI to receive it
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// The number of moves the remaining time is shared between when the GUI does not say
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// Reads commands from `input` until `quit` or the end of the input, answering on `output`
+///
+/// ### Arguments
+///
+/// * `input` - Where the commands come from, usually stdin
+/// * `output` - Where the answers go, usually stdout
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W){
+    let mut session = UciSession::new(output);
+
+    for line in input.lines(){
+        let line = match line{
+            Ok(line) => line,
+            Err(_) => break,
+        };
+
+        if !session.handle(&line){
+            break;
+        }
+    }
+
+    session.stop();
+}
+
+/// Returns the legal movement of `game` written as `text` in long algebraic notation
+fn legal_movement(game: &Game, text: &str) -> Option<Movement>{
+    Movement::from_uci(text).ok().filter(|movement| game.legal_moves().contains(movement))
+}
+
+/// The state of the conversation with a GUI
+pub struct UciSession<W: Write + Send + 'static>{
+    output: Arc<Mutex<W>>,
+    engine: Arc<Mutex<Engine>>,
+    game: Game,
+    /// Whether `UCI_Chess960` is on, castles are then sent as the king taking its own rook
+    chess960: bool,
+    stop: Arc<AtomicBool>,
+    search: Option<JoinHandle<()>>,
+}
+
+impl<W: Write + Send + 'static> UciSession<W>{
+    pub fn new(output: W) -> Self{
+        UciSession{
+            output: Arc::new(Mutex::new(output)),
+            engine: Arc::new(Mutex::new(Engine::with_table_size(table_size(DEFAULT_HASH_MEGABYTES)))),
+            game: Game::from_fen(STARTING_FEN).expect("The starting position is valid"),
+            chess960: false,
+            stop: Arc::new(AtomicBool::new(false)),
+            search: None,
+        }
+    }
+
+    /// Handles one command, unknown commands are ignored as the protocol asks
+    ///
+    /// ### Returns
+    ///
+    /// Returns false once the GUI sent `quit`
+    pub fn handle(&mut self, line: &str) -> bool{
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.first().copied(){
+            Some("uci") => {
+                self.send(&format!("id name better_chess {}", env!("CARGO_PKG_VERSION")));
+                self.send("id author the better_chess developers");
+                self.send(&format!("option name Hash type spin default {} min 1 max {}", DEFAULT_HASH_MEGABYTES, MAX_HASH_MEGABYTES));
+                self.send("option name Clear Hash type button");
+                self.send("option name UCI_Chess960 type check default false");
+                self.send("uciok");
+            },
+            Some("isready") => self.send("readyok"),
+            Some("ucinewgame") => {
+                self.stop();
+                self.engine.lock().expect("The search thread does not panic").clear();
+                self.game = Game::from_fen(STARTING_FEN).expect("The starting position is valid");
+            },
+            Some("position") => {
+                self.stop();
+
+                if let Err(error) = self.set_position(&tokens[1..]){
+                    self.send(&format!("info string {}", error));
+                }
+            },
+            Some("go") => {
+                self.stop();
+                self.go(&tokens[1..]);
+            },
+            Some("stop") => self.stop(),
+            Some("setoption") => {
+                self.stop();
+                self.set_option(&tokens[1..]);
+            },
+            Some("quit") => return false,
+            _ => (),
+        }
+
+        true
+    }
+
+    /// Stops the running search, if any, and waits for its `bestmove`
+    pub fn stop(&mut self){
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait();
+    }
+
+    /// Waits for the running search, if any, to finish on its own
+    pub fn wait(&mut self){
+        if let Some(search) = self.search.take(){
+            search.join().expect("The search thread does not panic");
+        }
+    }
+
+    /// Stops the running search and returns the output
+    pub fn into_output(mut self) -> W{
+        self.stop();
+
+        let output = Arc::try_unwrap(self.output).ok().expect("The search thread has finished");
+
+        output.into_inner().expect("The search thread does not panic")
+    }
+
+    fn send(&self, line: &str){
+        send(&self.output, line);
+    }
+
+    /// Sets up the game from `startpos` or `fen <fen>`, then plays the movements after `moves`
+    fn set_position(&mut self, tokens: &[&str]) -> Result<(), String>{
+        let moves_index = tokens.iter().position(|token| *token == "moves").unwrap_or(tokens.len());
+
+        let fen = match tokens.first().copied(){
+            Some("startpos") => STARTING_FEN.to_string(),
+            Some("fen") => tokens[1..moves_index].join(" "),
+            _ => return Err(String::from("position needs startpos or fen")),
+        };
+
+        // The position only replaces the current one once every move is played
+        let mut game = match self.chess960{
+            true => Chess960Game::from_fen(&fen),
+            false => Game::from_fen(&fen),
+        }.map_err(|error| error.to_string())?;
+
+        for text in tokens.iter().skip(moves_index + 1){
+            let movement = legal_movement(&game, text).ok_or(format!("illegal move {}", text))?;
+
+            game.move_piece(movement).map_err(|error| error.to_string())?;
+        }
+
+        self.game = game;
+
+        Ok(())
+    }
+
+    fn set_option(&mut self, tokens: &[&str]){
+        let value_index = tokens.iter().position(|token| *token == "value").unwrap_or(tokens.len());
+        let name = tokens.get(1..value_index).unwrap_or_default().join(" ");
+        let value = tokens.get(value_index + 1..).unwrap_or_default().join(" ");
+        let mut engine = self.engine.lock().expect("The search thread does not panic");
+
+        match name.to_lowercase().as_str(){
+            "hash" => match value.parse::<usize>(){
+                Ok(megabytes) => *engine = Engine::with_table_size(table_size(megabytes.clamp(1, MAX_HASH_MEGABYTES))),
+                Err(_) => self.send(&format!("info string invalid Hash value {}", value)),
+            },
+            "clear hash" => engine.clear(),
+            "uci_chess960" => match value.to_lowercase().parse::<bool>(){
+                Ok(chess960) => self.chess960 = chess960,
+                Err(_) => self.send(&format!("info string invalid UCI_Chess960 value {}", value)),
+            },
+            _ => self.send(&format!("info string unknown option {}", name)),
+        }
+    }
+
+    /// Starts searching the current position on another thread
+    fn go(&mut self, tokens: &[&str]){
+        let (limits, infinite) = parse_go(tokens, self.game.turn());
+        let output = Arc::clone(&self.output);
+        let engine = Arc::clone(&self.engine);
+        let stop = Arc::clone(&self.stop);
+        let game = self.game.clone();
+
+        stop.store(false, Ordering::Relaxed);
+
+        self.search = Some(thread::spawn(move || {
+            let mut engine = engine.lock().expect("The search thread does not panic");
+            let result = engine.search_with(&game, limits, &stop, |result| send(&output, &info(result)));
+
+            // An infinite search only answers once it is told to stop, even if it has nothing left to search
+            while infinite && !stop.load(Ordering::Relaxed){
+                thread::sleep(Duration::from_millis(5));
+            }
+
+            match result.best_move{
+                Some(movement) => send(&output, &format!("bestmove {}", movement)),
+                None => send(&output, "bestmove 0000"),
+            }
+        }));
+    }
+}
+
+fn send<W: Write>(output: &Mutex<W>, line: &str){
+    let mut output = output.lock().expect("The output is not poisoned");
+
+    // A GUI that went away cannot be told anything, the session ends with the input anyway
+    let _ = writeln!(output, "{}", line).and_then(|_| output.flush());
+}
+
+/// Returns the number of transposition table entries that fit in `megabytes`
+fn table_size(megabytes: usize) -> usize{
+    megabytes * 1024 * 1024 / size_of::<Option<Entry>>()
+}
+
+/// Reads the arguments of `go` into search limits for the player of `turn`
+///
+/// ### Returns
+///
+/// Returns the limits and whether the search is infinite, which it also is without any limit
+fn parse_go(tokens: &[&str], turn: &Color) -> (SearchLimits, bool){
+    let value = |name: &str| -> Option<u64>{
+        let index = tokens.iter().position(|token| *token == name)?;
+
+        tokens.get(index + 1)?.parse().ok()
+    };
+
+    let mut limits = SearchLimits{
+        depth: value("depth").map(|depth| depth as u32),
+        nodes: value("nodes"),
+        time: value("movetime").map(Duration::from_millis),
+    };
+
+    let (remaining, increment) = match turn{
+        Color::White => (value("wtime"), value("winc")),
+        Color::Black => (value("btime"), value("binc")),
+    };
+
+    if let (None, Some(remaining)) = (limits.time, remaining){
+        let remaining = Duration::from_millis(remaining);
+        let increment = Duration::from_millis(increment.unwrap_or(0));
+        let moves_to_go = value("movestogo").map_or(DEFAULT_MOVES_TO_GO, |moves| moves.max(1) as u32);
+
+        let time = (remaining / moves_to_go + increment * 3 / 4).min(remaining.saturating_sub(MOVE_OVERHEAD));
+
+        limits.time = Some(time.max(Duration::from_millis(1)));
+    }
+
+    let infinite = tokens.contains(&"infinite") || limits == SearchLimits::default();
+
+    match infinite{
+        true => (SearchLimits::default(), true),
+        false => (limits, false),
+    }
+}
+
+/// Writes the `info` line of a finished iteration
+fn info(result: &SearchResult) -> String{
+    let score = match result.score{
+        Score::Centipawns(centipawns) => format!("cp {}", centipawns),
+        Score::Mate(moves) => format!("mate {}", moves),
+    };
+
+    let milliseconds = result.time.as_millis() as u64;
+    let nodes_per_second = result.nodes * 1000 / milliseconds.max(1);
+    let pv: Vec<String> = result.principal_variation.iter().map(Movement::to_uci).collect();
+
+    format!("info depth {} score {} nodes {} nps {} time {} pv {}", result.depth, score, result.nodes, nodes_per_second, milliseconds, pv.join(" "))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn session(commands: &[&str]) -> String{
+        let mut session = UciSession::new(Vec::new());
+
+        for command in commands{
+            session.handle(command);
+        }
+
+        session.wait();
+
+        String::from_utf8(session.into_output()).unwrap()
+    }
+
+    #[test]
+    fn test_handshake(){
+        let output = session(&["uci", "isready", "debug on"]);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].starts_with("id name better_chess"));
+        assert!(lines.contains(&"option name Hash type spin default 4 min 1 max 1024"));
+        assert_eq!(lines[lines.len() - 2..], ["uciok", "readyok"]);
+    }
+
+    #[test]
+    fn test_go_depth_reports_each_iteration(){
+        let output = session(&["position startpos moves e2e4 e7e5", "go depth 3"]);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("info depth 1 score cp "));
+        assert!(lines[2].starts_with("info depth 3 "));
+        assert!(lines[2].contains(" pv "));
+        assert!(lines[3].starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_position_with_moves_and_promotion(){
+        let output = session(&["position fen 8/4P1k1/8/8/8/8/8/4K3 w - - 0 1 moves e7e8q g7h7", "go depth 1"]);
+
+        assert!(!output.contains("info string"));
+        assert!(output.lines().last().unwrap().starts_with("bestmove "));
+
+        let output = session(&["position startpos moves e2e5", "go depth 1"]);
+
+        assert!(output.starts_with("info string illegal move e2e5"));
+    }
+
+    #[test]
+    fn test_illegal_last_move_keeps_the_previous_position(){
+        let mut session = UciSession::new(Vec::new());
+
+        session.handle("position startpos moves e2e4");
+
+        let fen = session.game.to_fen();
+
+        session.handle("position startpos moves d2d4 d7d5 e1e3");
+
+        assert_eq!(session.game.to_fen(), fen);
+        assert!(String::from_utf8(session.into_output()).unwrap().starts_with("info string illegal move e1e3"));
+    }
+
+    #[test]
+    fn test_mate_scores_and_game_over(){
+        let output = session(&["position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "go depth 3"]);
+
+        assert!(output.contains("score mate 1"));
+        assert!(output.ends_with("bestmove a1a8\n"));
+
+        let output = session(&["position fen 7k/8/8/8/8/8/5PPP/r5K1 w - - 0 1", "go depth 2"]);
+
+        assert_eq!(output, "bestmove 0000\n");
+    }
+
+    #[test]
+    fn test_infinite_search_waits_for_stop(){
+        let mut session = UciSession::new(Vec::new());
+
+        session.handle("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        session.handle("go infinite");
+        thread::sleep(Duration::from_millis(50));
+        session.handle("isready");
+        session.handle("stop");
+
+        let output = String::from_utf8(session.into_output()).unwrap();
+
+        assert!(output.contains("readyok\n"));
+        assert!(output.ends_with("bestmove a1a8\n"));
+    }
+
+    #[test]
+    fn test_clock_limits(){
+        let (limits, infinite) = parse_go(&["wtime", "60000", "btime", "1000", "winc", "2000"], &Color::White);
+
+        assert!(!infinite);
+        assert_eq!(limits.time, Some(Duration::from_millis(2000 + 1500)));
+
+        let (limits, _) = parse_go(&["wtime", "60000", "btime", "1000", "movestogo", "1"], &Color::Black);
+
+        assert_eq!(limits.time, Some(Duration::from_millis(950)));
+        assert_eq!(parse_go(&["movetime", "300"], &Color::White).0.time, Some(Duration::from_millis(300)));
+        assert_eq!(parse_go(&[], &Color::White), (SearchLimits::default(), true));
+    }
+
+    #[test]
+    fn test_set_option(){
+        let output = session(&["setoption name Hash value 16", "setoption name Clear Hash", "setoption name Threads value 2"]);
+
+        assert_eq!(output, "info string unknown option Threads\n");
+    }
+
+    #[test]
+    fn test_chess960_castles_take_the_own_rook(){
+        let output = session(&["position fen 4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1 moves e1h1"]);
+
+        assert_eq!(output, "info string illegal move e1h1\n");
+
+        let output = session(&["setoption name UCI_Chess960 value true", "position fen 4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1 moves e1h1", "go depth 1"]);
+
+        assert!(!output.contains("info string"));
+        assert!(output.contains("bestmove e8"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Display;
+
+use super::{game::fen::FenError, movement::{absolute_position::AbsolutePosition, Movement}};
+
+/// Everything that can go wrong when reading positions and notation or playing a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChessError{
+    /// The movement starts on an empty tile
+    NoPieceOnSquare(AbsolutePosition),
+    /// The piece on the starting tile belongs to the player who is not on move
+    NotYourTurn,
+    /// The piece cannot move that way, or nothing matches the notation
+    IllegalMove,
+    /// The move would leave the own king attacked
+    LeavesKingInCheck,
+    /// More than one legal move matches the notation
+    AmbiguousMove(Vec<Movement>),
+    /// The promotion is missing a valid piece, or the move does not reach the last rank
+    InvalidPromotion,
+    /// A pawn waits for `promote` before anything else can be played
+    PromotionPending,
+    /// `promote` was called without a pawn on the last rank
+    NoPendingPromotion,
+    /// The game ended, on the board or off it
+    GameOver,
+    /// The player to move has no draw to claim
+    NoDrawToClaim,
+    /// The opponent of the player to move has no draw offer waiting for an answer
+    NoDrawOffer,
+    /// The ply is further than the played and undone moves
+    InvalidPly(usize),
+    /// A relative position leads off the board
+    OutOfBoard{file: i8, rank: i8},
+    /// A FEN string could not be read
+    Fen(FenError),
+    /// A square, movement or notation string could not be read
+    ParseError(String),
+    /// Chess960 only has start positions 0 to 959
+    InvalidStartPosition(u32),
+    /// The stages of a time control do not cover the whole game
+    InvalidTimeControl,
+    /// The engine only plays at levels 1 to 6
+    InvalidLevel(u8),
+}
+
+impl Display for ChessError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            ChessError::NoPieceOnSquare(position) => write!(f, "No piece on {}!", position),
+            ChessError::NotYourTurn => write!(f, "Cannot move opponent's piece!"),
+            ChessError::IllegalMove => write!(f, "Invalid move!"),
+            ChessError::LeavesKingInCheck => write!(f, "Cannot move into check!"),
+            ChessError::AmbiguousMove(candidates) => {
+                let candidates : Vec<String> = candidates.iter().map(|movement| format!("{}{}", movement.from(), movement.to())).collect();
+                write!(f, "Ambiguous move, could be {}!", candidates.join(", "))
+            },
+            ChessError::InvalidPromotion => write!(f, "Invalid promotion!"),
+            ChessError::PromotionPending => write!(f, "Cannot move piece while promoting!"),
+            ChessError::NoPendingPromotion => write!(f, "There is no pawn to promote!"),
+            ChessError::GameOver => write!(f, "The game is over!"),
+            ChessError::NoDrawToClaim => write!(f, "There is no draw to claim!"),
+            ChessError::NoDrawOffer => write!(f, "The opponent offered no draw!"),
+            ChessError::InvalidPly(ply) => write!(f, "Invalid ply: {}!", ply),
+            ChessError::OutOfBoard{file, rank} => write!(f, "Invalid position: {}{}", file, rank),
+            ChessError::Fen(error) => write!(f, "{}", error),
+            ChessError::ParseError(reason) => write!(f, "{}", reason),
+            ChessError::InvalidStartPosition(index) => write!(f, "Invalid Chess960 start position: {}!", index),
+            ChessError::InvalidTimeControl => write!(f, "Invalid time control, only the last stage may last until the end of the game!"),
+            ChessError::InvalidLevel(level) => write!(f, "Invalid level {}, expected 1 to 6!", level),
+        }
+    }
+}
+
+impl std::error::Error for ChessError{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self{
+            ChessError::Fen(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FenError> for ChessError{
+    fn from(error: FenError) -> Self {
+        ChessError::Fen(error)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_ambiguous_move_lists_candidates(){
+        let error = ChessError::AmbiguousMove(vec![
+            Movement::new(AbsolutePosition::new(1, 0), AbsolutePosition::new(3, 1)),
+            Movement::new(AbsolutePosition::new(5, 2), AbsolutePosition::new(3, 1)),
+        ]);
+
+        assert_eq!(error.to_string(), "Ambiguous move, could be b1d2, f3d2!");
+    }
+
+    #[test]
+    fn test_fen_error_is_the_source(){
+        let error = ChessError::from(FenError::SideToMove(String::from("x")));
+
+        assert_eq!(error.source().map(|source| source.to_string()), Some(String::from("Invalid side to move: x")));
+    }
+}
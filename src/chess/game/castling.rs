@@ -0,0 +1,250 @@
+use std::fmt::Display;
+
+use crate::chess::{color::Color, movement::absolute_position::AbsolutePosition};
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CastleSide{
+    KingSide,
+    QueenSide,
+}
+
+impl CastleSide{
+    /// The file the king lands on when castling to this side
+    pub fn king_destination_file(&self) -> usize{
+        match self{
+            CastleSide::KingSide => 6,
+            CastleSide::QueenSide => 2,
+        }
+    }
+
+    /// The file the rook lands on when castling to this side
+    pub fn rook_destination_file(&self) -> usize{
+        match self{
+            CastleSide::KingSide => 5,
+            CastleSide::QueenSide => 3,
+        }
+    }
+
+    /// The file the rook starts on in a classic game
+    pub fn rook_file(&self) -> usize{
+        match self{
+            CastleSide::KingSide => 7,
+            CastleSide::QueenSide => 0,
+        }
+    }
+}
+
+/// A castle as it is played on the board, from the tiles the king and the rook start on
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Castle{
+    pub side: CastleSide,
+    /// The first rank of the player castling
+    pub rank: usize,
+    pub king_file: usize,
+    pub rook_file: usize,
+}
+
+impl Castle{
+    pub fn king_from(&self) -> AbsolutePosition{
+        AbsolutePosition::new(self.king_file, self.rank)
+    }
+
+    pub fn king_to(&self) -> AbsolutePosition{
+        AbsolutePosition::new(self.side.king_destination_file(), self.rank)
+    }
+
+    pub fn rook_from(&self) -> AbsolutePosition{
+        AbsolutePosition::new(self.rook_file, self.rank)
+    }
+
+    pub fn rook_to(&self) -> AbsolutePosition{
+        AbsolutePosition::new(self.side.rook_destination_file(), self.rank)
+    }
+}
+
+/// Which castles are still available to each player and the file of the rook each one is played with
+///
+/// In a classic game the rooks start on the a and h files and the rights are written `KQkq` in FEN.
+/// In Chess960 they may start on any file, a right whose rook is elsewhere is written with the
+/// letter of its file, like `HAha` in Shredder-FEN.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CastlingRights{
+    white_king_side: Option<usize>,
+    white_queen_side: Option<usize>,
+    black_king_side: Option<usize>,
+    black_queen_side: Option<usize>,
+}
+
+impl CastlingRights{
+    pub fn all() -> Self{
+        CastlingRights{
+            white_king_side: Some(CastleSide::KingSide.rook_file()),
+            white_queen_side: Some(CastleSide::QueenSide.rook_file()),
+            black_king_side: Some(CastleSide::KingSide.rook_file()),
+            black_queen_side: Some(CastleSide::QueenSide.rook_file()),
+        }
+    }
+
+    pub fn none() -> Self{
+        CastlingRights{
+            white_king_side: None,
+            white_queen_side: None,
+            black_king_side: None,
+            black_queen_side: None,
+        }
+    }
+
+    pub fn has(&self, color: &Color, side: &CastleSide) -> bool{
+        self.rook_file(color, side).is_some()
+    }
+
+    /// Returns the file of the rook the castle is played with, `None` without the right
+    pub fn rook_file(&self, color: &Color, side: &CastleSide) -> Option<usize>{
+        match (color, side){
+            (Color::White, CastleSide::KingSide) => self.white_king_side,
+            (Color::White, CastleSide::QueenSide) => self.white_queen_side,
+            (Color::Black, CastleSide::KingSide) => self.black_king_side,
+            (Color::Black, CastleSide::QueenSide) => self.black_queen_side,
+        }
+    }
+
+    /// Grants or removes a castle with the rook on its classic file
+    pub fn set(&mut self, color: &Color, side: &CastleSide, allowed: bool){
+        let rook_file = match allowed{
+            true => Some(side.rook_file()),
+            false => None,
+        };
+
+        self.set_rook_file(color, side, rook_file);
+    }
+
+    /// Grants a castle with the rook on `rook_file`, or removes it with `None`
+    pub fn set_rook_file(&mut self, color: &Color, side: &CastleSide, rook_file: Option<usize>){
+        match (color, side){
+            (Color::White, CastleSide::KingSide) => self.white_king_side = rook_file,
+            (Color::White, CastleSide::QueenSide) => self.white_queen_side = rook_file,
+            (Color::Black, CastleSide::KingSide) => self.black_king_side = rook_file,
+            (Color::Black, CastleSide::QueenSide) => self.black_queen_side = rook_file,
+        }
+    }
+
+    /// Removes both castles of a player, as happens when their king moves
+    pub fn remove_all(&mut self, color: &Color){
+        self.set(color, &CastleSide::KingSide, false);
+        self.set(color, &CastleSide::QueenSide, false);
+    }
+
+    /// Removes the castle that depends on the rook starting on `position`, as happens
+    /// when anything moves from or onto that square
+    pub fn remove_rook_square(&mut self, position: &AbsolutePosition){
+        let color = match position.rank{
+            0 => Color::White,
+            7 => Color::Black,
+            _ => return,
+        };
+
+        for side in [CastleSide::KingSide, CastleSide::QueenSide]{
+            if self.rook_file(&color, &side) == Some(position.file){
+                self.set(&color, &side, false);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        *self == CastlingRights::none()
+    }
+
+    /// Whether every right is played with a rook on its classic file
+    pub fn is_classic(&self) -> bool{
+        [Color::White, Color::Black].iter()
+            .flat_map(|color| [CastleSide::KingSide, CastleSide::QueenSide].map(|side| (*color, side)))
+            .all(|(color, side)| self.rook_file(&color, &side).is_none_or(|file| file == side.rook_file()))
+    }
+}
+
+/// Writes `KQkq` for rooks on their classic files and the letter of the file otherwise
+impl Display for CastlingRights{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty(){
+            return write!(f, "-");
+        }
+
+        let rights = [
+            (Color::White, CastleSide::KingSide, 'K'),
+            (Color::White, CastleSide::QueenSide, 'Q'),
+            (Color::Black, CastleSide::KingSide, 'k'),
+            (Color::Black, CastleSide::QueenSide, 'q'),
+        ];
+
+        for (color, side, letter) in rights{
+            match self.rook_file(&color, &side){
+                Some(file) if file == side.rook_file() => write!(f, "{}", letter)?,
+                Some(file) => write!(f, "{}", file_letter(&color, file))?,
+                None => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The letter of `file` in Shredder-FEN, uppercase for white
+pub(super) fn file_letter(color: &Color, file: usize) -> char{
+    let letter = (b'a' + file as u8) as char;
+
+    match color{
+        Color::White => letter.to_ascii_uppercase(),
+        Color::Black => letter,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_king_move_removes_both_sides(){
+        let mut rights = CastlingRights::all();
+
+        rights.remove_all(&Color::White);
+
+        assert!(!rights.has(&Color::White, &CastleSide::KingSide));
+        assert!(!rights.has(&Color::White, &CastleSide::QueenSide));
+        assert!(rights.has(&Color::Black, &CastleSide::KingSide));
+        assert_eq!(rights.to_string(), "kq");
+    }
+
+    #[test]
+    fn test_rook_square_removes_one_side(){
+        let mut rights = CastlingRights::all();
+
+        rights.remove_rook_square(&AbsolutePosition::new(0, 7));
+        rights.remove_rook_square(&AbsolutePosition::new(3, 0));
+
+        assert_eq!(rights.to_string(), "KQk");
+    }
+
+    #[test]
+    fn test_no_rights_are_written_as_dash(){
+        assert_eq!(CastlingRights::none().to_string(), "-");
+    }
+
+    #[test]
+    fn test_rights_keep_the_rook_file(){
+        let mut rights = CastlingRights::none();
+
+        rights.set_rook_file(&Color::White, &CastleSide::KingSide, Some(6));
+        rights.set_rook_file(&Color::White, &CastleSide::QueenSide, Some(0));
+        rights.set_rook_file(&Color::Black, &CastleSide::QueenSide, Some(1));
+
+        assert_eq!(rights.rook_file(&Color::White, &CastleSide::KingSide), Some(6));
+        assert!(!rights.is_classic());
+        assert_eq!(rights.to_string(), "GQb");
+
+        rights.remove_rook_square(&AbsolutePosition::new(6, 0));
+        rights.remove_rook_square(&AbsolutePosition::new(7, 7));
+
+        assert_eq!(rights.to_string(), "Qb");
+    }
+}
@@ -0,0 +1,232 @@
+use rand::Rng;
+
+use crate::chess::{bitboard::position::Position, color::Color, error::ChessError, movement::absolute_position::AbsolutePosition, piece::PieceType};
+
+use super::{castling::{CastleSide, CastlingRights}, fen::FenError, Game};
+
+/// The files of the two knights among the five tiles left once the bishops and the queen are placed
+const KNIGHTS: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+/// Creates games of Fischer Random Chess, where the pieces behind the pawns are shuffled
+///
+/// Each start position has bishops on opposite colors and the king between the rooks, the
+/// castles then put the king and rook on the same tiles as in a classic game.
+pub struct Chess960Game{}
+
+impl Chess960Game{
+    /// The number of Chess960 start positions
+    pub const START_POSITIONS: u32 = 960;
+
+    /// The index of the classic start position among the Chess960 ones
+    pub const CLASSIC_INDEX: u32 = 518;
+
+    /// Creates a game from one of the 960 start positions
+    ///
+    /// ### Arguments
+    ///
+    /// * `index` - The number of the start position from 0 to 959, in the standard Scharnagl numbering
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing the game, or `ChessError::InvalidStartPosition` for an index past 959
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use better_chess::{Chess960Game, STARTING_FEN};
+    ///
+    /// let game = Chess960Game::new(0).unwrap();
+    ///
+    /// assert_eq!(game.to_fen(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1");
+    /// assert_eq!(game.to_shredder_fen(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1");
+    /// assert_eq!(Chess960Game::new(518).unwrap().to_fen(), STARTING_FEN);
+    /// ```
+    pub fn new(index: u32) -> Result<Game, ChessError>{
+        let back_rank = Chess960Game::back_rank(index).ok_or(ChessError::InvalidStartPosition(index))?;
+        let mut position = Position::empty();
+
+        for (file, piece_type) in back_rank.iter().enumerate(){
+            position.set_piece(&AbsolutePosition::new(file, 0), Color::White, *piece_type);
+            position.set_piece(&AbsolutePosition::new(file, 1), Color::White, PieceType::Pawn);
+            position.set_piece(&AbsolutePosition::new(file, 6), Color::Black, PieceType::Pawn);
+            position.set_piece(&AbsolutePosition::new(file, 7), Color::Black, *piece_type);
+        }
+
+        let king = back_rank.iter().position(|piece_type| *piece_type == PieceType::King).expect("Every start position has a king");
+        let rooks : Vec<usize> = (0..8).filter(|file| back_rank[*file] == PieceType::Rook).collect();
+        let mut castling_rights = CastlingRights::none();
+
+        for color in [Color::White, Color::Black]{
+            castling_rights.set_rook_file(&color, &CastleSide::QueenSide, rooks.iter().copied().find(|file| *file < king));
+            castling_rights.set_rook_file(&color, &CastleSide::KingSide, rooks.iter().copied().find(|file| *file > king));
+        }
+
+        let mut game = Game::from_position(position, Color::White);
+
+        game.chess960 = true;
+        game.castling_rights = castling_rights;
+        game.zobrist = game.position.zobrist_hash() ^ game.zobrist_state_keys();
+        game.initial_fen = game.to_fen();
+
+        Ok(game)
+    }
+
+    /// Creates a game from a start position picked at random
+    pub fn random() -> Game{
+        let index = rand::thread_rng().gen_range(0..Chess960Game::START_POSITIONS);
+
+        Chess960Game::new(index).expect("The index is below the number of start positions")
+    }
+
+    /// Creates a Chess960 game from a position in FEN, X-FEN or Shredder-FEN
+    ///
+    /// Unlike `Game::from_fen`, castles are played by moving the king onto its rook even when the
+    /// king and rooks stand on their classic files.
+    pub fn from_fen(fen: &str) -> Result<Game, FenError>{
+        Game::from_fen_with_rules(fen, true)
+    }
+
+    /// Returns the pieces of the first rank of a start position from the a-file to the h-file, `None` for an index past 959
+    pub fn back_rank(index: u32) -> Option<[PieceType; 8]>{
+        if index >= Chess960Game::START_POSITIONS{
+            return None;
+        }
+
+        let mut back_rank = [None; 8];
+        let index = index as usize;
+
+        back_rank[2 * (index % 4) + 1] = Some(PieceType::Bishop);
+        back_rank[2 * (index / 4 % 4)] = Some(PieceType::Bishop);
+
+        let index = index / 16;
+        let (knights, queen) = (index / 6, index % 6);
+
+        place_on_empty(&mut back_rank, queen, PieceType::Queen);
+
+        // The second knight goes first so the first one still counts the same empty tiles
+        place_on_empty(&mut back_rank, KNIGHTS[knights].1, PieceType::Knight);
+        place_on_empty(&mut back_rank, KNIGHTS[knights].0, PieceType::Knight);
+
+        for piece_type in [PieceType::Rook, PieceType::King, PieceType::Rook]{
+            place_on_empty(&mut back_rank, 0, piece_type);
+        }
+
+        Some(back_rank.map(|piece_type| piece_type.expect("Every tile of the first rank is filled")))
+    }
+}
+
+/// Puts `piece_type` on the empty tile that comes `nth` from the a-file
+fn place_on_empty(back_rank: &mut [Option<PieceType>; 8], nth: usize, piece_type: PieceType){
+    let file = (0..8).filter(|file| back_rank[*file].is_none()).nth(nth).expect("There are enough empty tiles left");
+
+    back_rank[file] = Some(piece_type);
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{game::fen::STARTING_FEN, movement::Movement};
+
+    use super::*;
+
+    fn back_rank_letters(index: u32) -> String{
+        Chess960Game::back_rank(index).unwrap().iter().map(|piece_type| piece_type.prefix()).collect()
+    }
+
+    #[test]
+    fn test_start_positions_follow_the_scharnagl_numbering(){
+        assert_eq!(back_rank_letters(0), "BBQNNRKR");
+        assert_eq!(back_rank_letters(518), "RNBQKBNR");
+        assert_eq!(back_rank_letters(959), "RKRNNQBB");
+        assert_eq!(Chess960Game::back_rank(960), None);
+        assert_eq!(Chess960Game::new(960).err(), Some(ChessError::InvalidStartPosition(960)));
+    }
+
+    #[test]
+    fn test_every_start_position_is_valid(){
+        let mut back_ranks = std::collections::HashSet::new();
+
+        for index in 0..Chess960Game::START_POSITIONS{
+            let back_rank = Chess960Game::back_rank(index).unwrap();
+            let files = |piece_type: PieceType| (0..8).filter(move |file| back_rank[*file] == piece_type);
+
+            let bishops : Vec<usize> = files(PieceType::Bishop).collect();
+            let rooks : Vec<usize> = files(PieceType::Rook).collect();
+            let king = files(PieceType::King).next().unwrap();
+
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            assert!(rooks[0] < king && king < rooks[1]);
+            assert!(back_ranks.insert(back_rank.map(|piece_type| piece_type.prefix())));
+        }
+    }
+
+    #[test]
+    fn test_classic_index_plays_like_a_classic_game(){
+        let game = Chess960Game::new(Chess960Game::CLASSIC_INDEX).unwrap();
+
+        assert_eq!(game.to_fen(), STARTING_FEN);
+        assert!(game.is_chess960());
+        assert_eq!(game.perft(3), 8902);
+    }
+
+    #[test]
+    fn test_castle_with_the_king_onto_its_rook(){
+        // The king on b1 castles queen side with the rook on a1, ending on c1 with the rook on d1
+        let mut game = Chess960Game::from_fen("1r2k2r/8/8/8/8/8/1P6/RK5R w HAhb - 0 1").unwrap();
+        let square = |name: &str| name.parse::<AbsolutePosition>().unwrap();
+
+        assert_eq!(game.castling_rights().to_string(), "KQkb");
+        assert!(game.legal_moves().contains(&Movement::new(square("b1"), square("a1"))));
+        assert!(game.legal_moves().contains(&Movement::new(square("b1"), square("h1"))));
+        assert_eq!(game.to_san(&Movement::new(square("b1"), square("a1"))), "O-O-O");
+
+        game.move_piece(Movement::new(square("b1"), square("a1"))).unwrap();
+
+        assert_eq!(game.position().piece_at(&square("c1")), Some((Color::White, PieceType::King)));
+        assert_eq!(game.position().piece_at(&square("d1")), Some((Color::White, PieceType::Rook)));
+        assert_eq!(game.position().piece_at(&square("a1")), None);
+
+        game.move_piece(game.parse_san("O-O").unwrap()).unwrap();
+
+        assert_eq!(game.position().piece_at(&square("g8")), Some((Color::Black, PieceType::King)));
+        assert_eq!(game.position().piece_at(&square("f8")), Some((Color::Black, PieceType::Rook)));
+        assert_eq!(game.to_shredder_fen(), "1r3rk1/8/8/8/8/8/1P6/2KR3R w - - 2 2");
+
+        game.undo();
+        game.undo();
+
+        assert_eq!(game.to_shredder_fen(), "1r2k2r/8/8/8/8/8/1P6/RK5R w HAhb - 0 1");
+    }
+
+    #[test]
+    fn test_castle_where_the_king_does_not_move(){
+        let mut game = Chess960Game::from_fen("4k3/8/8/8/8/8/8/5RKR w H - 0 1").unwrap();
+        let castle = Movement::new(AbsolutePosition::new(6, 0), AbsolutePosition::new(7, 0));
+
+        assert_eq!(game.castling_rights().to_string(), "K");
+        assert!(!game.legal_moves().contains(&castle));
+
+        // Without the rook on f1 the king stays on g1 and the rook jumps over it
+        let mut open = Chess960Game::from_fen("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap();
+
+        open.move_piece(castle).unwrap();
+
+        assert_eq!(open.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+        assert!(game.move_piece(castle).is_err());
+    }
+
+    #[test]
+    fn test_castling_checks_the_tile_the_rook_was_shielding(){
+        // Castling queen side leaves the king on c1 with the black rook on a1 attacking it through b1
+        let game = Chess960Game::from_fen("4k3/8/8/8/8/8/8/rRK4R w B - 0 1").unwrap();
+
+        assert!(!game.legal_moves().contains(&Movement::new(AbsolutePosition::new(2, 0), AbsolutePosition::new(1, 0))));
+    }
+
+    #[test]
+    fn test_random_games_start_from_a_valid_position(){
+        let game = Chess960Game::random();
+
+        assert!(game.is_chess960());
+        assert!(game.castling_rights().has(&Color::White, &CastleSide::KingSide));
+    }
+}
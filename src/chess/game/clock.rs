@@ -0,0 +1,719 @@
+use std::{fmt::Debug, str::FromStr, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use crate::chess::{color::Color, error::ChessError};
+
+use super::{draw::DrawReason, Game, GameState};
+
+
+/// Tells the clock how much time went by, so games can be timed by the wall clock or by hand
+pub trait TimeSource: Send + Sync{
+    /// Returns the time elapsed since some fixed point, which must never go backwards
+    fn now(&self) -> Duration;
+}
+
+/// Measures time with the monotonic system clock
+#[derive(Clone, Copy, Debug)]
+pub struct SystemTimeSource{
+    start: Instant,
+}
+
+impl SystemTimeSource{
+    pub fn new() -> Self{
+        SystemTimeSource{
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemTimeSource{
+    fn default() -> Self {
+        SystemTimeSource::new()
+    }
+}
+
+impl TimeSource for SystemTimeSource{
+    fn now(&self) -> Duration{
+        self.start.elapsed()
+    }
+}
+
+/// A time source that only moves when told to, its clones share the same time
+///
+/// ## Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+/// use better_chess::{Clock, Color, ManualTimeSource, TimeControl};
+///
+/// let time = ManualTimeSource::new();
+/// let mut clock = Clock::with_time_source(TimeControl::fischer(Duration::from_secs(60), Duration::from_secs(2)), Arc::new(time.clone()));
+///
+/// clock.start(Color::White);
+/// time.advance(Duration::from_secs(10));
+/// clock.press();
+///
+/// assert_eq!(clock.remaining(&Color::White), Duration::from_secs(52));
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct ManualTimeSource{
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualTimeSource{
+    pub fn new() -> Self{
+        ManualTimeSource::default()
+    }
+
+    /// Moves the time forward by `duration`
+    pub fn advance(&self, duration: Duration){
+        *self.now.lock().expect("The time is never poisoned") += duration;
+    }
+}
+
+impl TimeSource for ManualTimeSource{
+    fn now(&self) -> Duration{
+        *self.now.lock().expect("The time is never poisoned")
+    }
+}
+
+/// What a player gets back for each move
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Increment{
+    /// Nothing, the time only runs down
+    None,
+    /// The duration is added after each move, whatever the move took
+    Fischer(Duration),
+    /// The time spent on the move is given back after it, up to the duration
+    Bronstein(Duration),
+    /// The clock waits for the duration before it starts running down on each move
+    Delay(Duration),
+}
+
+/// A part of a time control, the time is given when the stage starts
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stage{
+    /// The number of moves to play in the stage, `None` for the rest of the game
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub increment: Increment,
+}
+
+impl Stage{
+    /// Creates a stage that lasts until the end of the game
+    pub fn sudden_death(time: Duration, increment: Increment) -> Self{
+        Stage{
+            moves: None,
+            time,
+            increment,
+        }
+    }
+
+    /// Creates a stage that ends after each player played `moves` moves
+    pub fn moves(moves: u32, time: Duration, increment: Increment) -> Self{
+        Stage{
+            moves: Some(moves),
+            time,
+            increment,
+        }
+    }
+}
+
+/// The time each player has for the game, in one or several stages
+///
+/// A control such as `40/90+30` is two stages: 90 minutes for the first 40 moves, then
+/// 30 minutes for the rest of the game, both with a 30 seconds Fischer increment.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TimeControl{
+    stages: Vec<Stage>,
+}
+
+impl TimeControl{
+    /// Creates a time control from its stages
+    ///
+    /// ### Arguments
+    ///
+    /// * `stages` - Every stage but the last one has a number of moves, the last one lasts until the end of the game
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing the time control, or `ChessError::InvalidTimeControl` if the stages do not cover the whole game
+    pub fn new(stages: Vec<Stage>) -> Result<Self, ChessError>{
+        let (last, others) = stages.split_last().ok_or(ChessError::InvalidTimeControl)?;
+
+        if last.moves.is_some() || others.iter().any(|stage| !matches!(stage.moves, Some(moves) if moves > 0)){
+            return Err(ChessError::InvalidTimeControl);
+        }
+
+        Ok(TimeControl{stages})
+    }
+
+    /// All the moves in `time`
+    pub fn sudden_death(time: Duration) -> Self{
+        TimeControl{stages: vec![Stage::sudden_death(time, Increment::None)]}
+    }
+
+    /// All the moves in `time`, with `increment` added after each move
+    pub fn fischer(time: Duration, increment: Duration) -> Self{
+        TimeControl{stages: vec![Stage::sudden_death(time, Increment::Fischer(increment))]}
+    }
+
+    /// All the moves in `time`, with the time of each move given back up to `delay`
+    pub fn bronstein(time: Duration, delay: Duration) -> Self{
+        TimeControl{stages: vec![Stage::sudden_death(time, Increment::Bronstein(delay))]}
+    }
+
+    /// All the moves in `time`, the clock waiting for `delay` on each move before running
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self{
+        TimeControl{stages: vec![Stage::sudden_death(time, Increment::Delay(delay))]}
+    }
+
+    pub fn stages(&self) -> &[Stage]{
+        &self.stages
+    }
+}
+
+/// Reads a time control in the syntax of the PGN `TimeControl` tag, in seconds
+///
+/// Stages are separated by `:` and written `moves/time`, or `time` for the last one, each
+/// followed by an optional `+increment`. `40/5400+30:1800+30` is the `40/90+30` control.
+impl FromStr for TimeControl{
+    type Err = ChessError;
+
+    fn from_str(string: &str) -> Result<Self, ChessError>{
+        let seconds = |text: &str| text.parse::<u64>()
+            .map(Duration::from_secs)
+            .map_err(|_| ChessError::ParseError(format!("Invalid time control {:?}, expected seconds instead of {:?}", string, text)));
+
+        let mut stages = Vec::new();
+
+        for stage in string.trim().split(':'){
+            let (moves, stage) = match stage.split_once('/'){
+                Some((moves, stage)) => (Some(moves.parse::<u32>().map_err(|_| ChessError::ParseError(format!("Invalid number of moves {:?}", moves)))?), stage),
+                None => (None, stage),
+            };
+
+            let (time, increment) = match stage.split_once('+'){
+                Some((time, increment)) => (seconds(time)?, Increment::Fischer(seconds(increment)?)),
+                None => (seconds(stage)?, Increment::None),
+            };
+
+            stages.push(Stage{moves, time, increment});
+        }
+
+        TimeControl::new(stages)
+    }
+}
+
+/// The time of one player
+#[derive(Clone, Copy, Debug)]
+struct PlayerTime{
+    remaining: Duration,
+    stage: usize,
+    moves: u32,
+}
+
+/// A chess clock, each player's time runs down while they are on move
+///
+/// `Game::set_clock` attaches a clock to a game, which then presses it on every move and ends
+/// the game once a flag falls. Taking moves back does not give the time back.
+#[derive(Clone)]
+pub struct Clock{
+    control: TimeControl,
+    source: Arc<dyn TimeSource>,
+    white: PlayerTime,
+    black: PlayerTime,
+    /// The player whose time runs and the time their move started
+    running: Option<(Color, Duration)>,
+    flagged: Option<Color>,
+}
+
+impl Debug for Clock{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clock")
+            .field("control", &self.control)
+            .field("white", &self.remaining(&Color::White))
+            .field("black", &self.remaining(&Color::Black))
+            .field("running", &self.running())
+            .finish()
+    }
+}
+
+impl Clock{
+    /// Creates a stopped clock running on the system time
+    pub fn new(control: TimeControl) -> Self{
+        Clock::with_time_source(control, Arc::new(SystemTimeSource::new()))
+    }
+
+    /// Creates a stopped clock that reads the time from `source`
+    pub fn with_time_source(control: TimeControl, source: Arc<dyn TimeSource>) -> Self{
+        let player = PlayerTime{
+            remaining: control.stages[0].time,
+            stage: 0,
+            moves: 0,
+        };
+
+        Clock{
+            control,
+            source,
+            white: player,
+            black: player,
+            running: None,
+            flagged: None,
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl{
+        &self.control
+    }
+
+    /// Returns the player whose time is running, if any
+    pub fn running(&self) -> Option<Color>{
+        self.running.map(|(color, _)| color)
+    }
+
+    /// Returns the time `color` has left, counting the move in progress
+    pub fn remaining(&self, color: &Color) -> Duration{
+        let player = self.player(color);
+
+        match self.running{
+            Some((running, started)) if &running == color => player.remaining.saturating_sub(self.charged(player, self.source.now() - started)),
+            _ => player.remaining,
+        }
+    }
+
+    /// Returns the player who ran out of time, if any
+    pub fn flagged(&self) -> Option<Color>{
+        self.flagged.or_else(|| self.running().filter(|color| self.remaining(color).is_zero()))
+    }
+
+    /// Runs the time of `color`, stopping the other player's time without ending their move
+    pub fn start(&mut self, color: Color){
+        self.stop();
+        self.running = Some((color, self.source.now()));
+    }
+
+    /// Stops the running time without ending the move
+    pub fn stop(&mut self){
+        if let Some((color, started)) = self.running.take(){
+            self.charge(&color, self.source.now() - started);
+        }
+    }
+
+    /// Ends the move of the running player and runs the time of the other one
+    ///
+    /// The move is only counted when it was played in time, otherwise the player is flagged
+    /// and the clock stops.
+    pub fn press(&mut self){
+        let (color, started) = match self.running.take(){
+            Some(running) => running,
+            None => return,
+        };
+
+        let elapsed = self.source.now() - started;
+
+        if !self.charge(&color, elapsed){
+            return;
+        }
+
+        let index = self.player(&color).stage;
+        let stage = self.control.stages[index];
+        let next_stage = self.control.stages.get(index + 1).copied();
+        let player = self.player_mut(&color);
+
+        player.remaining += match stage.increment{
+            Increment::Fischer(increment) => increment,
+            Increment::Bronstein(delay) => elapsed.min(delay),
+            Increment::None | Increment::Delay(_) => Duration::ZERO,
+        };
+
+        player.moves += 1;
+
+        if let Some(next_stage) = next_stage.filter(|_| stage.moves == Some(player.moves)){
+            player.stage += 1;
+            player.moves = 0;
+            player.remaining += next_stage.time;
+        }
+
+        self.running = Some((color.opposite(), self.source.now()));
+    }
+
+    /// Takes the time of a move from `color`, flagging them if it is more than they had
+    ///
+    /// ### Returns
+    ///
+    /// Returns whether the player still has time left
+    fn charge(&mut self, color: &Color, elapsed: Duration) -> bool{
+        let charged = self.charged(self.player(color), elapsed);
+        let player = self.player_mut(color);
+
+        match player.remaining.checked_sub(charged){
+            Some(remaining) if !remaining.is_zero() => {
+                player.remaining = remaining;
+                true
+            },
+            _ => {
+                player.remaining = Duration::ZERO;
+                self.flagged = Some(*color);
+                false
+            },
+        }
+    }
+
+    /// Returns the part of a move of `elapsed` that runs the player's time down
+    fn charged(&self, player: &PlayerTime, elapsed: Duration) -> Duration{
+        match self.control.stages[player.stage].increment{
+            Increment::Delay(delay) => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+
+    fn player(&self, color: &Color) -> &PlayerTime{
+        match color{
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+
+    fn player_mut(&mut self, color: &Color) -> &mut PlayerTime{
+        match color{
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+}
+
+impl Game{
+    /// Attaches a clock to the game and runs the time of the player to move
+    ///
+    /// From then on `move_piece` presses the clock after every move, and the game ends once
+    /// the player to move runs out of time, which `check_flag` tells between moves.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, time::Duration};
+    /// use better_chess::{ClassicGame, Clock, Color, GameState, ManualTimeSource, TimeControl};
+    ///
+    /// let time = ManualTimeSource::new();
+    /// let mut game = ClassicGame::new();
+    ///
+    /// game.set_clock(Clock::with_time_source(TimeControl::sudden_death(Duration::from_secs(60)), Arc::new(time.clone())));
+    /// time.advance(Duration::from_secs(61));
+    ///
+    /// assert_eq!(game.check_flag(), Some(Color::White));
+    /// assert_eq!(game.state(), &GameState::Timeout(Color::White));
+    /// ```
+    pub fn set_clock(&mut self, clock: Clock){
+        self.clock = Some(clock);
+        self.sync_clock();
+    }
+
+    /// Returns the clock attached with `set_clock`, if any
+    pub fn clock(&self) -> Option<&Clock>{
+        self.clock.as_ref()
+    }
+
+    /// Detaches the clock from the game
+    pub fn take_clock(&mut self) -> Option<Clock>{
+        self.clock.take()
+    }
+
+    /// Ends the game if the player to move ran out of time
+    ///
+    /// The player loses on time, unless the opponent does not have the pieces left to
+    /// checkmate, then the game is drawn.
+    ///
+    /// ### Returns
+    ///
+    /// Returns the player whose flag fell, `None` if the game goes on or was already over
+    pub fn check_flag(&mut self) -> Option<Color>{
+        if self.is_over(){
+            return None;
+        }
+
+        let color = self.clock.as_ref()?.flagged()?;
+
+        if let Some(clock) = &mut self.clock{
+            clock.stop();
+        }
+
+        self.pending_promotion = None;
+        self.state = match self.has_mating_material(&color.opposite()){
+            true => GameState::Timeout(color),
+            false => GameState::Draw(DrawReason::TimeoutVsInsufficientMaterial),
+        };
+
+        Some(color)
+    }
+
+    /// Runs the time of the player to move after the moves were taken back or played again
+    pub(super) fn sync_clock(&mut self){
+        let is_over = self.is_over();
+        let turn = self.turn;
+
+        if let Some(clock) = &mut self.clock{
+            match is_over{
+                true => clock.stop(),
+                false => clock.start(turn),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::{classic::ClassicGame, fen::STARTING_FEN};
+
+    use super::*;
+
+    fn seconds(seconds: u64) -> Duration{
+        Duration::from_secs(seconds)
+    }
+
+    fn started_clock(control: TimeControl) -> (Clock, ManualTimeSource){
+        let time = ManualTimeSource::new();
+        let mut clock = Clock::with_time_source(control, Arc::new(time.clone()));
+
+        clock.start(Color::White);
+
+        (clock, time)
+    }
+
+    /// Plays a move of `duration` for the running player
+    fn play(clock: &mut Clock, time: &ManualTimeSource, duration: u64){
+        time.advance(seconds(duration));
+        clock.press();
+    }
+
+    #[test]
+    fn test_sudden_death_runs_down_the_player_on_move(){
+        let (mut clock, time) = started_clock(TimeControl::sudden_death(seconds(60)));
+
+        time.advance(seconds(5));
+
+        assert_eq!(clock.remaining(&Color::White), seconds(55));
+        assert_eq!(clock.remaining(&Color::Black), seconds(60));
+
+        clock.press();
+        play(&mut clock, &time, 20);
+
+        assert_eq!(clock.running(), Some(Color::White));
+        assert_eq!(clock.remaining(&Color::White), seconds(55));
+        assert_eq!(clock.remaining(&Color::Black), seconds(40));
+        assert_eq!(clock.flagged(), None);
+    }
+
+    #[test]
+    fn test_fischer_adds_the_increment_after_each_move(){
+        let (mut clock, time) = started_clock(TimeControl::fischer(seconds(60), seconds(10)));
+
+        play(&mut clock, &time, 3);
+        play(&mut clock, &time, 30);
+
+        assert_eq!(clock.remaining(&Color::White), seconds(67));
+        assert_eq!(clock.remaining(&Color::Black), seconds(40));
+    }
+
+    #[test]
+    fn test_bronstein_gives_back_the_time_up_to_the_delay(){
+        let (mut clock, time) = started_clock(TimeControl::bronstein(seconds(60), seconds(10)));
+
+        play(&mut clock, &time, 3);
+        play(&mut clock, &time, 30);
+
+        assert_eq!(clock.remaining(&Color::White), seconds(60));
+        assert_eq!(clock.remaining(&Color::Black), seconds(40));
+
+        // The clock runs during the delay, so it can fall before the time is given back
+        let (mut clock, time) = started_clock(TimeControl::bronstein(seconds(5), seconds(10)));
+
+        play(&mut clock, &time, 6);
+
+        assert_eq!(clock.flagged(), Some(Color::White));
+    }
+
+    #[test]
+    fn test_simple_delay_waits_before_running(){
+        let (mut clock, time) = started_clock(TimeControl::simple_delay(seconds(5), seconds(10)));
+
+        time.advance(seconds(8));
+
+        assert_eq!(clock.remaining(&Color::White), seconds(5));
+
+        clock.press();
+        play(&mut clock, &time, 12);
+
+        assert_eq!(clock.remaining(&Color::White), seconds(5));
+        assert_eq!(clock.remaining(&Color::Black), seconds(3));
+        assert_eq!(clock.flagged(), None);
+
+        time.advance(seconds(15));
+
+        assert_eq!(clock.flagged(), Some(Color::White));
+    }
+
+    #[test]
+    fn test_multi_stage_controls_add_the_next_stage_after_its_moves(){
+        let control: TimeControl = "2/100+5:50".parse().unwrap();
+
+        assert_eq!(control, TimeControl::new(vec![
+            Stage::moves(2, seconds(100), Increment::Fischer(seconds(5))),
+            Stage::sudden_death(seconds(50), Increment::None),
+        ]).unwrap());
+
+        let (mut clock, time) = started_clock(control);
+
+        for _ in 0..4{
+            play(&mut clock, &time, 10);
+        }
+
+        assert_eq!(clock.remaining(&Color::White), seconds(100 - 20 + 10 + 50));
+        assert_eq!(clock.remaining(&Color::Black), seconds(100 - 20 + 10 + 50));
+
+        play(&mut clock, &time, 10);
+
+        assert_eq!(clock.remaining(&Color::White), seconds(130));
+    }
+
+    #[test]
+    fn test_flag_falls_when_the_time_runs_out(){
+        let (mut clock, time) = started_clock(TimeControl::sudden_death(seconds(10)));
+
+        time.advance(seconds(10));
+
+        assert_eq!(clock.flagged(), Some(Color::White));
+
+        clock.press();
+
+        assert_eq!(clock.running(), None);
+        assert_eq!(clock.remaining(&Color::White), Duration::ZERO);
+        assert_eq!(clock.flagged(), Some(Color::White));
+    }
+
+    #[test]
+    fn test_moves_press_the_clock_of_the_game(){
+        let time = ManualTimeSource::new();
+        let mut game = ClassicGame::new();
+
+        game.set_clock(Clock::with_time_source(TimeControl::fischer(seconds(60), seconds(1)), Arc::new(time.clone())));
+
+        for (san, duration) in [("e4", 5), ("e5", 10), ("Nf3", 20)]{
+            time.advance(seconds(duration));
+            game.move_piece(game.parse_san(san).unwrap()).unwrap();
+        }
+
+        let clock = game.clock().unwrap();
+
+        assert_eq!(clock.running(), Some(Color::Black));
+        assert_eq!(clock.remaining(&Color::White), seconds(60 - 25 + 2));
+        assert_eq!(clock.remaining(&Color::Black), seconds(60 - 10 + 1));
+
+        // Taking a move back runs the time of the player who moves again, without giving time back
+        time.advance(seconds(3));
+        game.undo();
+
+        assert_eq!(game.clock().unwrap().running(), Some(Color::White));
+        assert_eq!(game.clock().unwrap().remaining(&Color::Black), seconds(48));
+    }
+
+    #[test]
+    fn test_game_is_lost_on_time(){
+        let time = ManualTimeSource::new();
+        let mut game = ClassicGame::new();
+
+        game.set_clock(Clock::with_time_source(TimeControl::sudden_death(seconds(60)), Arc::new(time.clone())));
+        game.move_piece(game.parse_san("e4").unwrap()).unwrap();
+        time.advance(seconds(60));
+
+        assert_eq!(game.move_piece(game.parse_san("e5").unwrap()), Err(ChessError::GameOver));
+        assert_eq!(game.state(), &GameState::Timeout(Color::Black));
+        assert_eq!(game.check_flag(), None);
+        assert!(game.legal_moves().is_empty());
+    }
+
+    /// A time source one step later at every reading, as if each check of the clock took time
+    struct SteppingTimeSource{
+        now: Mutex<Duration>,
+        step: Duration,
+    }
+
+    impl TimeSource for SteppingTimeSource{
+        fn now(&self) -> Duration{
+            let mut now = self.now.lock().expect("The time is never poisoned");
+            *now += self.step;
+            *now
+        }
+    }
+
+    #[test]
+    fn test_flag_falling_during_a_move_ends_the_game(){
+        let time = SteppingTimeSource{now: Mutex::new(Duration::ZERO), step: seconds(40)};
+        let mut game = ClassicGame::new();
+
+        // White has time left when the move starts and none once the clock is pressed
+        game.set_clock(Clock::with_time_source(TimeControl::sudden_death(seconds(60)), Arc::new(time)));
+
+        assert_eq!(game.move_piece(game.parse_san("e4").unwrap()), Err(ChessError::GameOver));
+        assert_eq!(game.state(), &GameState::Timeout(Color::White));
+        assert_eq!(game.clock().unwrap().remaining(&Color::White), Duration::ZERO);
+        assert_eq!(game.clock().unwrap().running(), None);
+        assert!(game.is_over());
+
+        // The move came too late and is not played
+        assert_eq!(game.to_fen(), STARTING_FEN);
+        assert!(game.history().is_empty());
+        assert_eq!(game.turn(), &Color::White);
+    }
+
+    #[test]
+    fn test_flag_fall_against_insufficient_material_is_a_draw(){
+        let time = ManualTimeSource::new();
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4KN2 w - - 0 1").unwrap();
+
+        game.set_clock(Clock::with_time_source(TimeControl::sudden_death(seconds(10)), Arc::new(time.clone())));
+        time.advance(seconds(11));
+
+        assert_eq!(game.check_flag(), Some(Color::White));
+        assert_eq!(game.state(), &GameState::Draw(DrawReason::TimeoutVsInsufficientMaterial));
+
+        // A lone king with a knight still has no mate, but pawns may promote
+        let game = Game::from_fen("4k3/8/8/8/8/8/4P3/4KN2 w - - 0 1").unwrap();
+
+        assert!(game.has_mating_material(&Color::White));
+        assert!(!game.has_mating_material(&Color::Black));
+        assert!(Game::from_fen("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+        assert!(!Game::from_fen("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+    }
+
+    #[test]
+    fn test_flag_fall_against_a_lone_minor_piece(){
+        let flag = |fen: &str| -> GameState{
+            let time = ManualTimeSource::new();
+            let mut game = Game::from_fen(fen).unwrap();
+
+            game.set_clock(Clock::with_time_source(TimeControl::sudden_death(seconds(10)), Arc::new(time.clone())));
+            time.advance(seconds(11));
+            game.check_flag();
+
+            game.state().clone()
+        };
+
+        // The black rook may hem its own king in for a knight mate
+        assert_eq!(flag("7k/7r/8/8/8/8/8/4K1N1 b - - 0 1"), GameState::Timeout(Color::Black));
+        assert_eq!(flag("7k/7r/8/8/8/8/8/4KB2 b - - 0 1"), GameState::Draw(DrawReason::TimeoutVsInsufficientMaterial));
+        // A king and bishop against a bare king is drawn before any flag falls
+        assert_eq!(flag("7k/8/8/8/8/8/8/4KB2 b - - 0 1"), GameState::Draw(DrawReason::InsufficientMaterial));
+        assert_eq!(flag("7k/7q/8/8/8/8/8/4K1N1 b - - 0 1"), GameState::Draw(DrawReason::TimeoutVsInsufficientMaterial));
+    }
+
+    #[test]
+    fn test_rejects_invalid_time_controls(){
+        assert_eq!(TimeControl::new(Vec::new()), Err(ChessError::InvalidTimeControl));
+        assert_eq!(TimeControl::new(vec![Stage::moves(40, seconds(60), Increment::None)]), Err(ChessError::InvalidTimeControl));
+        assert_eq!(TimeControl::from_str("300+2"), Ok(TimeControl::fischer(seconds(300), seconds(2))));
+
+        for string in ["", "abc", "40/", "40/300", "300+", "-5", "0/60:60"]{
+            assert!(TimeControl::from_str(string).is_err(), "{}", string);
+        }
+    }
+}
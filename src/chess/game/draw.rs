@@ -0,0 +1,292 @@
+use std::fmt::Display;
+
+use crate::chess::{color::Color, error::ChessError, piece::PieceType};
+
+use super::{Game, GameState};
+
+
+/// Why a game ended in a draw, other than stalemate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DrawReason{
+    /// 50 moves by each player without a capture or pawn move, a player may claim it
+    FiftyMoveRule,
+    /// 75 moves by each player without a capture or pawn move, the game ends on its own
+    SeventyFiveMoveRule,
+    /// The same position occurred three times, a player may claim it
+    ThreefoldRepetition,
+    /// The same position occurred five times, the game ends on its own
+    FivefoldRepetition,
+    /// Neither player has the pieces left to checkmate
+    InsufficientMaterial,
+    /// A player ran out of time, but the opponent does not have the pieces left to checkmate
+    TimeoutVsInsufficientMaterial,
+    /// The players agreed to a draw
+    Agreement,
+}
+
+impl DrawReason{
+    /// Whether the draw has to be claimed by a player instead of ending the game on its own
+    pub fn is_claimable(&self) -> bool{
+        matches!(self, DrawReason::FiftyMoveRule | DrawReason::ThreefoldRepetition)
+    }
+}
+
+impl Display for DrawReason{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            DrawReason::FiftyMoveRule => write!(f, "the fifty-move rule"),
+            DrawReason::SeventyFiveMoveRule => write!(f, "the seventy-five-move rule"),
+            DrawReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+            DrawReason::FivefoldRepetition => write!(f, "fivefold repetition"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+            DrawReason::TimeoutVsInsufficientMaterial => write!(f, "timeout against insufficient material"),
+            DrawReason::Agreement => write!(f, "agreement"),
+        }
+    }
+}
+
+impl Game{
+    /// Returns the draw the player to move may claim, if any
+    ///
+    /// ### Returns
+    ///
+    /// * `Some(DrawReason::ThreefoldRepetition)` - If the current position occurred at least three times
+    /// * `Some(DrawReason::FiftyMoveRule)` - If no capture or pawn move happened in the last 50 moves of each player
+    /// * `None` - If there is no draw to claim, or the game is already over
+    pub fn claimable_draw(&self) -> Option<DrawReason>{
+        if !matches!(self.state, GameState::InProgress | GameState::Check(_)){
+            return None;
+        }
+
+        if self.repetitions() >= 3{
+            return Some(DrawReason::ThreefoldRepetition);
+        }
+
+        if self.halfmove_clock >= 100{
+            return Some(DrawReason::FiftyMoveRule);
+        }
+
+        None
+    }
+
+    /// Ends the game with the draw returned by `claimable_draw`
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing a reference to the new game state, otherwise the `ChessError` explaining why not
+    pub fn claim_draw(&mut self) -> Result<&GameState, ChessError>{
+        match self.claimable_draw(){
+            Some(reason) => {
+                self.state = GameState::Draw(reason);
+                Ok(&self.state)
+            },
+            None => Err(ChessError::NoDrawToClaim),
+        }
+    }
+
+    /// Returns how many times the current position occurred, counting the current one
+    ///
+    /// Positions are the same when their Zobrist hashes are, which covers the pieces, the player
+    /// to move, the castling rights and the possible en passant captures.
+    pub fn repetitions(&self) -> usize{
+        let earlier = self.history.iter().rev()
+            .take(self.halfmove_clock as usize)
+            .filter(|record| record.zobrist == self.zobrist)
+            .count();
+
+        earlier + 1
+    }
+
+    /// Whether neither player can ever checkmate, whatever is played
+    ///
+    /// That is the case with bare kings, a single minor piece, or bishops that all stand on the same colour.
+    pub fn is_insufficient_material(&self) -> bool{
+        !self.has_mating_material(&Color::White) && !self.has_mating_material(&Color::Black)
+    }
+
+    /// Whether `color` could still checkmate with some series of legal moves, which decides a loss on time
+    ///
+    /// Pawns, rooks, queens or two minor pieces can always mate. A lone knight needs an opposing piece
+    /// other than a queen to hem the king in, and bishops that all stand on one colour need a pawn,
+    /// a knight or a bishop of the other colour somewhere on the board.
+    pub fn has_mating_material(&self, color: &Color) -> bool{
+        let pieces = self.position.pieces_of(color);
+        let heavy = self.position.pieces_of_type(PieceType::Queen) | self.position.pieces_of_type(PieceType::Rook) | self.position.pieces_of_type(PieceType::Pawn);
+
+        if !(pieces & heavy).is_empty(){
+            return true;
+        }
+
+        let knights = self.position.pieces_of_type(PieceType::Knight);
+        let bishops = self.position.pieces_of_type(PieceType::Bishop);
+        let minors = pieces & (knights | bishops);
+
+        if minors.count() > 1{
+            return true;
+        }
+
+        if !(minors & knights).is_empty(){
+            let blockers = self.position.pieces_of(&color.opposite()) & !(self.position.pieces_of_type(PieceType::King) | self.position.pieces_of_type(PieceType::Queen));
+
+            return !blockers.is_empty();
+        }
+
+        if !(minors & bishops).is_empty(){
+            let bishop_colors : Vec<usize> = bishops.into_iter()
+                .map(|square| (square.file + square.rank) % 2)
+                .collect();
+
+            return !knights.is_empty() || !self.position.pieces_of_type(PieceType::Pawn).is_empty() || bishop_colors.windows(2).any(|colors| colors[0] != colors[1]);
+        }
+
+        false
+    }
+
+    /// Returns the draw that ends the game on its own in the current position, if any
+    pub(super) fn automatic_draw(&self) -> Option<DrawReason>{
+        if self.is_insufficient_material(){
+            return Some(DrawReason::InsufficientMaterial);
+        }
+
+        if self.halfmove_clock >= 150{
+            return Some(DrawReason::SeventyFiveMoveRule);
+        }
+
+        if self.repetitions() >= 5{
+            return Some(DrawReason::FivefoldRepetition);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{color::Color, game::classic::ClassicGame};
+
+    use super::*;
+
+    fn play(game: &mut Game, moves: &[&str]){
+        for san in moves{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_insufficient_material(){
+        for fen in ["8/8/4k3/8/8/3K4/8/8 w - - 0 1", "8/8/4k3/8/8/3K4/8/6B1 w - - 0 1", "8/8/4k3/8/8/3K4/8/6n1 w - - 0 1", "2b5/8/4k3/8/8/3K4/8/5B2 w - - 0 1"]{
+            let game = Game::from_fen(fen).unwrap();
+
+            assert!(game.is_insufficient_material(), "{} should be a dead position", fen);
+            assert_eq!(game.state(), &GameState::Draw(DrawReason::InsufficientMaterial));
+        }
+
+        for fen in ["8/8/4k3/8/8/3K4/8/6bB w - - 0 1", "8/8/4k3/8/8/3K4/8/5nN1 w - - 0 1", "8/8/4k3/8/8/3K4/P7/8 w - - 0 1", "8/8/4k3/8/8/3K4/8/6BN w - - 0 1"]{
+            let game = Game::from_fen(fen).unwrap();
+
+            assert!(!game.is_insufficient_material(), "{} should not be a dead position", fen);
+        }
+    }
+
+    #[test]
+    fn test_a_lone_minor_piece_mates_only_with_blocking_material(){
+        // Kh8 Rh7 against Kf8 and a knight mating from g6
+        assert!(Game::from_fen("7k/7r/8/8/8/8/8/4K1N1 w - - 0 1").unwrap().has_mating_material(&Color::White));
+        assert!(!Game::from_fen("7k/8/8/8/8/8/8/4KB2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+        assert!(Game::from_fen("7k/6n1/8/8/8/8/8/4KB2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+        assert!(Game::from_fen("7k/8/7p/8/8/8/8/4KB2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+
+        // The queen always takes the checking knight or bishop, or steps in front of it
+        assert!(!Game::from_fen("7k/7q/8/8/8/8/8/4K1N1 w - - 0 1").unwrap().has_mating_material(&Color::White));
+        assert!(!Game::from_fen("7k/7q/8/8/8/8/8/4KB2 w - - 0 1").unwrap().has_mating_material(&Color::White));
+    }
+
+    #[test]
+    fn test_capturing_the_last_piece_draws(){
+        let mut game = Game::from_fen("8/8/4k3/8/8/3K4/2r5/8 w - - 0 1").unwrap();
+
+        assert_eq!(game.state(), &GameState::InProgress);
+
+        play(&mut game, &["Kxc2"]);
+
+        assert_eq!(game.state(), &GameState::Draw(DrawReason::InsufficientMaterial));
+        assert!(game.move_piece(game.parse_san("Kd5").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_threefold_repetition_can_be_claimed(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"]);
+
+        assert_eq!(game.repetitions(), 2);
+        assert_eq!(game.claimable_draw(), None);
+        assert!(game.claim_draw().is_err());
+
+        play(&mut game, &["Ng8"]);
+
+        assert_eq!(game.repetitions(), 3);
+        assert_eq!(game.state(), &GameState::InProgress);
+        assert_eq!(game.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+        assert_eq!(game.claim_draw(), Ok(&GameState::Draw(DrawReason::ThreefoldRepetition)));
+        assert!(game.move_piece(game.parse_san("e4").unwrap()).is_err());
+
+        // The claim ends the game for good, taking back a move would undo it
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.go_to_ply(0), Err(ChessError::GameOver));
+        assert_eq!(game.state(), &GameState::Draw(DrawReason::ThreefoldRepetition));
+    }
+
+    #[test]
+    fn test_fivefold_repetition_ends_the_game(){
+        let mut game = ClassicGame::new();
+
+        for _ in 0..4{
+            play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+        }
+
+        assert_eq!(game.repetitions(), 5);
+        assert_eq!(game.state(), &GameState::Draw(DrawReason::FivefoldRepetition));
+
+        game.undo();
+
+        assert_eq!(game.state(), &GameState::InProgress);
+    }
+
+    #[test]
+    fn test_unusable_en_passant_does_not_break_repetition(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4", "Nf6", "Nf3", "Ng8", "Ng1", "Nf6", "Nf3", "Ng8", "Ng1"]);
+
+        assert_eq!(game.repetitions(), 3);
+    }
+
+    #[test]
+    fn test_fifty_and_seventy_five_move_rules(){
+        let mut game = Game::from_fen("8/8/4k3/8/8/3K4/8/R7 w - - 99 80").unwrap();
+
+        assert_eq!(game.claimable_draw(), None);
+
+        play(&mut game, &["Ra2"]);
+
+        assert_eq!(game.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+        assert_eq!(game.state(), &GameState::InProgress);
+
+        let mut game = Game::from_fen("8/8/4k3/8/8/3K4/8/R7 w - - 149 100").unwrap();
+
+        play(&mut game, &["Ra2"]);
+
+        assert_eq!(game.state(), &GameState::Draw(DrawReason::SeventyFiveMoveRule));
+    }
+
+    #[test]
+    fn test_checkmate_takes_precedence_over_the_seventy_five_move_rule(){
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 100").unwrap();
+
+        play(&mut game, &["Ra8#"]);
+
+        assert_eq!(game.state(), &GameState::Checkmate(Color::Black));
+    }
+}
@@ -0,0 +1,541 @@
+use std::fmt::Display;
+
+use crate::chess::{bitboard::position::Position, color::Color, movement::absolute_position::AbsolutePosition, piece::PieceType};
+
+use super::{castling::{self, CastleSide, CastlingRights}, Game};
+
+/// The position every classic game starts from
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The reason a FEN string could not be read, naming the field that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError{
+    /// A FEN needs four to six space separated fields
+    FieldCount(usize),
+    PiecePlacement(String),
+    SideToMove(String),
+    CastlingRights(String),
+    EnPassant(String),
+    HalfmoveClock(String),
+    FullmoveNumber(String),
+}
+
+impl Display for FenError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            FenError::FieldCount(count) => write!(f, "Expected 4 to 6 fields, found {}", count),
+            FenError::PiecePlacement(reason) => write!(f, "Invalid piece placement: {}", reason),
+            FenError::SideToMove(field) => write!(f, "Invalid side to move: {}", field),
+            FenError::CastlingRights(field) => write!(f, "Invalid castling rights: {}", field),
+            FenError::EnPassant(field) => write!(f, "Invalid en passant square: {}", field),
+            FenError::HalfmoveClock(field) => write!(f, "Invalid halfmove clock: {}", field),
+            FenError::FullmoveNumber(field) => write!(f, "Invalid fullmove number: {}", field),
+        }
+    }
+}
+
+impl std::error::Error for FenError{}
+
+impl Game{
+    /// Creates a game from a position in Forsyth–Edwards Notation
+    /// 
+    /// The castling rights may also be written in X-FEN or Shredder-FEN, with the file of the rook
+    /// like `HAha`, as long as the castling kings and rooks are on their classic files. Positions
+    /// that castle from other files are read by `Chess960Game::from_fen`.
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `fen` - The position, the halfmove clock and fullmove number may be left out
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns a Result containing the game, otherwise the field that could not be read
+    /// 
+    /// ## Examples
+    /// 
+    /// ```
+    /// use better_chess::{Color, Game};
+    /// 
+    /// let game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+    /// 
+    /// assert_eq!(game.turn(), &Color::Black);
+    /// ```
+    pub fn from_fen(fen: &str) -> Result<Game, FenError>{
+        Game::from_fen_with_rules(fen, false)
+    }
+
+    /// Creates a game from a position in FEN, following the Chess960 castling rules when `chess960` is set
+    pub(super) fn from_fen_with_rules(fen: &str, chess960: bool) -> Result<Game, FenError>{
+        let fields : Vec<&str> = fen.split_whitespace().collect();
+
+        if fields.len() < 4 || fields.len() > 6{
+            return Err(FenError::FieldCount(fields.len()));
+        }
+
+        let position = parse_placement(fields[0])?;
+
+        let turn = match fields[1]{
+            "w" => Color::White,
+            "b" => Color::Black,
+            field => return Err(FenError::SideToMove(field.to_string())),
+        };
+
+        // The player who just moved cannot have left their king in check
+        if position.in_check(&turn.opposite()){
+            return Err(FenError::SideToMove(format!("{}, {} is in check", fields[1], turn.opposite())));
+        }
+
+        let castling_rights = parse_castling_rights(fields[2], &position)?;
+
+        if !chess960 && is_chess960_setup(&position, &castling_rights){
+            return Err(FenError::CastlingRights(format!("{}, castling from these files needs the Chess960 rules", fields[2])));
+        }
+        let en_passant = parse_en_passant(fields[3], &turn, &position)?;
+
+        let halfmove_clock = match fields.get(4){
+            Some(field) => field.parse().map_err(|_| FenError::HalfmoveClock(field.to_string()))?,
+            None => 0,
+        };
+
+        let fullmove_number = match fields.get(5){
+            Some(field) => match field.parse(){
+                Ok(number) if number > 0 => number,
+                _ => return Err(FenError::FullmoveNumber(field.to_string())),
+            },
+            None => 1,
+        };
+
+        let mut game = Game::from_position(position, turn);
+
+        game.chess960 = chess960;
+        game.castling_rights = castling_rights;
+        game.en_passant = en_passant;
+        game.halfmove_clock = halfmove_clock;
+        game.fullmove_number = fullmove_number;
+        game.zobrist = game.position.zobrist_hash() ^ game.zobrist_state_keys();
+        game.update_state();
+        game.initial_fen = game.to_fen();
+
+        Ok(game)
+    }
+
+    /// Returns the current position in Forsyth–Edwards Notation
+    /// 
+    /// Castling rights are written in X-FEN: `KQkq` for the outermost rooks, which covers every
+    /// classic game, and the file of the rook when another rook stands further out.
+    pub fn to_fen(&self) -> String{
+        self.fen_with_castling(self.x_fen_castling())
+    }
+
+    /// Returns the current position in Shredder-FEN, which writes each castling right with the file of its rook, like `HAha`
+    pub fn to_shredder_fen(&self) -> String{
+        let mut castling = String::new();
+
+        for (color, side) in CASTLES{
+            if let Some(file) = self.castling_rights.rook_file(&color, &side){
+                castling.push(castling::file_letter(&color, file));
+            }
+        }
+
+        self.fen_with_castling(castling)
+    }
+
+    fn x_fen_castling(&self) -> String{
+        let mut castling = String::new();
+
+        for (color, side) in CASTLES{
+            let file = match self.castling_rights.rook_file(&color, &side){
+                Some(file) => file,
+                None => continue,
+            };
+
+            let letter = match outermost_rook(&self.position, &color, &side){
+                Some(outermost) if outermost == file => match side{
+                    CastleSide::KingSide => 'K',
+                    CastleSide::QueenSide => 'Q',
+                },
+                _ => castling::file_letter(&Color::White, file),
+            };
+
+            match color{
+                Color::White => castling.push(letter),
+                Color::Black => castling.push(letter.to_ascii_lowercase()),
+            }
+        }
+
+        castling
+    }
+
+    fn fen_with_castling(&self, castling: String) -> String{
+        let mut placement = String::new();
+
+        for rank in (0..8).rev(){
+            let mut empty = 0;
+
+            for file in 0..8{
+                match self.position.piece_at(&AbsolutePosition::new(file, rank)){
+                    Some((color, piece_type)) => {
+                        if empty > 0{
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+
+                        placement.push(piece_to_char(&color, piece_type));
+                    },
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0{
+                placement.push_str(&empty.to_string());
+            }
+
+            if rank > 0{
+                placement.push('/');
+            }
+        }
+
+        let turn = match self.turn{
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+
+        let en_passant = match self.en_passant{
+            Some(position) => position.to_string(),
+            None => String::from("-"),
+        };
+
+        let castling = match castling.is_empty(){
+            true => String::from("-"),
+            false => castling,
+        };
+
+        format!("{} {} {} {} {} {}", placement, turn, castling, en_passant, self.halfmove_clock, self.fullmove_number)
+    }
+}
+
+fn piece_to_char(color: &Color, piece_type: PieceType) -> char{
+    match color{
+        Color::White => piece_type.prefix(),
+        Color::Black => piece_type.prefix().to_ascii_lowercase(),
+    }
+}
+
+fn char_to_piece(letter: char) -> Option<(Color, PieceType)>{
+    let piece_type = PieceType::from_prefix(letter)?;
+
+    let color = match letter.is_ascii_uppercase(){
+        true => Color::White,
+        false => Color::Black,
+    };
+
+    Some((color, piece_type))
+}
+
+fn parse_placement(field: &str) -> Result<Position, FenError>{
+    let ranks : Vec<&str> = field.split('/').collect();
+
+    if ranks.len() != 8{
+        return Err(FenError::PiecePlacement(format!("expected 8 ranks, found {}", ranks.len())));
+    }
+
+    let mut position = Position::empty();
+    let mut kings = (0, 0);
+
+    for (i, row) in ranks.iter().enumerate(){
+        let rank = 7 - i;
+        let mut file = 0;
+
+        for letter in row.chars(){
+            if let Some(empty) = letter.to_digit(10){
+                if empty == 0{
+                    return Err(FenError::PiecePlacement(format!("rank {} counts 0 empty files", rank + 1)));
+                }
+
+                file += empty as usize;
+                continue;
+            }
+
+            let (color, piece_type) = match char_to_piece(letter){
+                Some(piece) => piece,
+                None => return Err(FenError::PiecePlacement(format!("unknown piece '{}'", letter))),
+            };
+
+            if file > 7{
+                return Err(FenError::PiecePlacement(format!("rank {} has more than 8 files", rank + 1)));
+            }
+
+            if piece_type == PieceType::King{
+                match color{
+                    Color::White => kings.0 += 1,
+                    Color::Black => kings.1 += 1,
+                }
+            }
+
+            if piece_type == PieceType::Pawn && (rank == 0 || rank == 7){
+                return Err(FenError::PiecePlacement(format!("pawn on rank {}", rank + 1)));
+            }
+
+            position.set_piece(&AbsolutePosition::new(file, rank), color, piece_type);
+            file += 1;
+        }
+
+        if file != 8{
+            return Err(FenError::PiecePlacement(format!("rank {} has {} files", rank + 1, file)));
+        }
+    }
+
+    if kings != (1, 1){
+        return Err(FenError::PiecePlacement(String::from("each side needs exactly one king")));
+    }
+
+    Ok(position)
+}
+
+/// The castles in the order FEN writes them
+const CASTLES: [(Color, CastleSide); 4] = [
+    (Color::White, CastleSide::KingSide),
+    (Color::White, CastleSide::QueenSide),
+    (Color::Black, CastleSide::KingSide),
+    (Color::Black, CastleSide::QueenSide),
+];
+
+fn back_rank(color: &Color) -> usize{
+    match color{
+        Color::White => 0,
+        Color::Black => 7,
+    }
+}
+
+/// Returns the king of `color` if it stands on its first rank
+fn king_on_back_rank(position: &Position, color: &Color) -> Option<AbsolutePosition>{
+    position.king(color).filter(|king| king.rank == back_rank(color))
+}
+
+/// Returns the file of the rook of `color` furthest from its king on `side`, on their first rank
+fn outermost_rook(position: &Position, color: &Color, side: &CastleSide) -> Option<usize>{
+    let king = king_on_back_rank(position, color)?;
+    let rooks = position.pieces(color, PieceType::Rook).into_iter()
+        .filter(|rook| rook.rank == king.rank)
+        .map(|rook| rook.file);
+
+    match side{
+        CastleSide::KingSide => rooks.filter(|file| *file > king.file).max(),
+        CastleSide::QueenSide => rooks.filter(|file| *file < king.file).min(),
+    }
+}
+
+/// Reads the castling field in FEN, X-FEN or Shredder-FEN
+///
+/// `KQkq` stand for the outermost rook on that side of the king, which needs the king and a rook on their first rank.
+/// A file letter, uppercase for white, names the rook and needs the king and that rook on their first rank.
+fn parse_castling_rights(field: &str, position: &Position) -> Result<CastlingRights, FenError>{
+    let mut castling_rights = CastlingRights::none();
+
+    if field == "-"{
+        return Ok(castling_rights);
+    }
+
+    for letter in field.chars(){
+        let color = match letter.is_ascii_uppercase(){
+            true => Color::White,
+            false => Color::Black,
+        };
+
+        let (side, rook_file) = match letter.to_ascii_lowercase(){
+            'k' | 'q' => {
+                let side = match letter.to_ascii_lowercase(){
+                    'k' => CastleSide::KingSide,
+                    _ => CastleSide::QueenSide,
+                };
+
+                match outermost_rook(position, &color, &side){
+                    Some(rook_file) => (side, rook_file),
+                    None => return Err(FenError::CastlingRights(field.to_string())),
+                }
+            },
+            file @ 'a'..='h' => {
+                let rook = AbsolutePosition::new(file as usize - 'a' as usize, back_rank(&color));
+
+                let king = match king_on_back_rank(position, &color){
+                    Some(king) if king.file != rook.file && position.piece_at(&rook) == Some((color, PieceType::Rook)) => king,
+                    _ => return Err(FenError::CastlingRights(field.to_string())),
+                };
+
+                match rook.file > king.file{
+                    true => (CastleSide::KingSide, rook.file),
+                    false => (CastleSide::QueenSide, rook.file),
+                }
+            },
+            _ => return Err(FenError::CastlingRights(field.to_string())),
+        };
+
+        if castling_rights.has(&color, &side){
+            return Err(FenError::CastlingRights(field.to_string()));
+        }
+
+        castling_rights.set_rook_file(&color, &side, Some(rook_file));
+    }
+
+    Ok(castling_rights)
+}
+
+/// Whether castling with `castling_rights` needs the Chess960 rules, because a castling rook or
+/// king is off its classic file
+fn is_chess960_setup(position: &Position, castling_rights: &CastlingRights) -> bool{
+    let king_moved = [Color::White, Color::Black].iter().any(|color| {
+        let has_rights = castling_rights.has(color, &CastleSide::KingSide) || castling_rights.has(color, &CastleSide::QueenSide);
+
+        has_rights && king_on_back_rank(position, color).is_some_and(|king| king.file != 4)
+    });
+
+    king_moved || !castling_rights.is_classic()
+}
+
+fn parse_en_passant(field: &str, turn: &Color, position: &Position) -> Result<Option<AbsolutePosition>, FenError>{
+    if field == "-"{
+        return Ok(None);
+    }
+
+    // The opponent just pushed a pawn two tiles, from `start` over `square` to `pawn`
+    let (expected_rank, forward) : (u8, isize) = match turn{
+        Color::White => (b'6', -1),
+        Color::Black => (b'3', 1),
+    };
+
+    let square = match field.as_bytes(){
+        [file @ b'a'..=b'h', rank] if *rank == expected_rank => AbsolutePosition::new((file - b'a') as usize, (rank - b'1') as usize),
+        _ => return Err(FenError::EnPassant(field.to_string())),
+    };
+
+    let pawn = AbsolutePosition::new(square.file, square.rank.saturating_add_signed(forward));
+    let start = AbsolutePosition::new(square.file, square.rank.saturating_add_signed(-forward));
+
+    let is_double_step = position.piece_at(&pawn) == Some((turn.opposite(), PieceType::Pawn))
+        && position.piece_at(&square).is_none()
+        && position.piece_at(&start).is_none();
+
+    match is_double_step{
+        true => Ok(Some(square)),
+        false => Err(FenError::EnPassant(field.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{game::{chess960::Chess960Game, classic::ClassicGame, GameState}, movement::{chess_notation::ChessNotationPosition, Movement}};
+
+    use super::*;
+
+    #[test]
+    fn test_starting_position_round_trips(){
+        let game = Game::from_fen(STARTING_FEN).unwrap();
+
+        assert_eq!(game.to_fen(), STARTING_FEN);
+        assert_eq!(ClassicGame::new().to_fen(), STARTING_FEN);
+    }
+
+    #[test]
+    fn test_fen_follows_the_game(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(Movement::new(ChessNotationPosition::new('e', 2).to_position(), ChessNotationPosition::new('e', 4).to_position())).unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+        game.move_piece(Movement::new(ChessNotationPosition::new('c', 7).to_position(), ChessNotationPosition::new('c', 5).to_position())).unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2");
+
+        game.move_piece(Movement::new(ChessNotationPosition::new('g', 1).to_position(), ChessNotationPosition::new('f', 3).to_position())).unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    }
+
+    #[test]
+    fn test_every_field_is_read(){
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 3 17";
+        let game = Game::from_fen(fen).unwrap();
+
+        assert_eq!(game.turn(), &Color::White);
+        assert_eq!(game.castling_rights().to_string(), "Kq");
+        assert_eq!(game.halfmove_clock(), 3);
+        assert_eq!(game.fullmove_number(), 17);
+        assert_eq!(game.to_fen(), fen);
+    }
+
+    #[test]
+    fn test_clocks_are_optional(){
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K -").unwrap();
+
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+    }
+
+    #[test]
+    fn test_en_passant_square_allows_the_capture(){
+        let mut game = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+
+        let capture = Movement::new(ChessNotationPosition::new('e', 5).to_position(), ChessNotationPosition::new('d', 6).to_position());
+
+        assert_eq!(game.move_piece(capture), Ok(&GameState::InProgress));
+        assert_eq!(game.to_fen(), "4k3/8/3P4/8/8/8/8/4K3 b - - 0 2");
+    }
+
+    #[test]
+    fn test_advanced_pawns_cannot_double_step(){
+        let mut game = Game::from_fen("4k3/8/8/8/8/4P3/8/4K3 w - - 0 1").unwrap();
+
+        let movement = Movement::new(ChessNotationPosition::new('e', 3).to_position(), ChessNotationPosition::new('e', 5).to_position());
+
+        assert!(game.move_piece(movement).is_err());
+    }
+
+    #[test]
+    fn test_state_is_computed_on_import(){
+        let game = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+
+        assert_eq!(game.state(), &GameState::Checkmate(Color::White));
+    }
+
+    #[test]
+    fn test_errors_name_the_failing_field(){
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/8 w").err(), Some(FenError::FieldCount(2)));
+        assert!(matches!(Game::from_fen("4k3/8/8/8/8/8/8/4K3/8 w - - 0 1").err(), Some(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("4k3/8/8/8/8/8/8/4K2 w - - 0 1").err(), Some(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("4k3/8/8/8/8/8/8/4X3 w - - 0 1").err(), Some(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("4k3/8/8/8/8/8/8/04K3 w - - 0 1").err(), Some(FenError::PiecePlacement(_))));
+        assert!(matches!(Game::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").err(), Some(FenError::PiecePlacement(_))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1").err(), Some(FenError::SideToMove(String::from("x"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1").unwrap().state(), &GameState::Check(Color::Black));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1").err(), Some(FenError::SideToMove(String::from("w, Black is in check"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w KA - 0 1").err(), Some(FenError::CastlingRights(String::from("KA"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - e3 0 1").err(), Some(FenError::EnPassant(String::from("e3"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - e6 0 1").err(), Some(FenError::EnPassant(String::from("e6"))));
+        assert_eq!(Game::from_fen("4k3/8/4n3/4p3/8/8/8/4K3 w - e6 0 1").err(), Some(FenError::EnPassant(String::from("e6"))));
+        assert_eq!(Game::from_fen("4k3/4n3/8/4p3/8/8/8/4K3 w - e6 0 1").err(), Some(FenError::EnPassant(String::from("e6"))));
+        assert_eq!(Game::from_fen("4k3/8/8/4P3/8/8/8/4K3 w - e6 0 1").err(), Some(FenError::EnPassant(String::from("e6"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap().en_passant(), Some(&AbsolutePosition::new(4, 2)));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1").err(), Some(FenError::HalfmoveClock(String::from("x"))));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 0").err(), Some(FenError::FullmoveNumber(String::from("0"))));
+    }
+
+    #[test]
+    fn test_castling_rights_need_the_king_and_rook_on_the_first_rank(){
+        let no_rook = String::from("K");
+
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w K - 0 1").err(), Some(FenError::CastlingRights(no_rook.clone())));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w K - 0 1").err(), Some(FenError::CastlingRights(no_rook.clone())));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/4K3/7R w K - 0 1").err(), Some(FenError::CastlingRights(no_rook)));
+        assert_eq!(Game::from_fen("4k2r/8/8/8/8/8/8/4K2R w Kq - 0 1").err(), Some(FenError::CastlingRights(String::from("Kq"))));
+
+        let fen = "r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1";
+
+        assert_eq!(Game::from_fen(fen).unwrap().to_fen(), fen);
+    }
+
+    #[test]
+    fn test_chess960_castling_is_only_read_as_chess960(){
+        let fen = "4k3/8/8/8/8/8/8/5RKR w H - 0 1";
+
+        assert!(matches!(Game::from_fen(fen).err(), Some(FenError::CastlingRights(_))));
+        assert!(Chess960Game::from_fen(fen).unwrap().is_chess960());
+        assert!(!Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap().is_chess960());
+    }
+}
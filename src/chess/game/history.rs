@@ -0,0 +1,294 @@
+use crate::chess::{error::ChessError, movement::{absolute_position::AbsolutePosition, Movement}, piece::PieceType};
+
+use super::{castling::{Castle, CastlingRights}, movegen, Game, GameState};
+
+/// A played move together with everything it changed, so it can be taken back exactly
+#[derive(Clone)]
+pub struct MoveRecord{
+    pub(super) movement: Movement,
+    pub(super) piece: PieceType,
+    pub(super) captured: Option<(PieceType, AbsolutePosition)>,
+    pub(super) castle: Option<Castle>,
+    pub(super) castling_rights: CastlingRights,
+    pub(super) en_passant: Option<AbsolutePosition>,
+    pub(super) halfmove_clock: u32,
+    pub(super) fullmove_number: u32,
+    pub(super) state: GameState,
+    pub(super) zobrist: u64,
+}
+
+impl MoveRecord{
+    pub fn movement(&self) -> &Movement{
+        &self.movement
+    }
+
+    /// Returns the type of the piece that moved, a pawn for a promotion
+    pub fn piece(&self) -> PieceType{
+        self.piece
+    }
+
+    /// Returns the type of the captured piece, which for en passant is not on the destination tile
+    pub fn captured(&self) -> Option<PieceType>{
+        self.captured.map(|(piece_type, _)| piece_type)
+    }
+
+    /// Returns the castle the move played, if it was one
+    pub fn castle(&self) -> Option<&Castle>{
+        self.castle.as_ref()
+    }
+
+    /// Returns the castling rights before the move
+    pub fn castling_rights(&self) -> &CastlingRights{
+        &self.castling_rights
+    }
+
+    /// Returns the en passant square before the move
+    pub fn en_passant(&self) -> Option<&AbsolutePosition>{
+        self.en_passant.as_ref()
+    }
+
+    /// Returns the halfmove clock before the move
+    pub fn halfmove_clock(&self) -> u32{
+        self.halfmove_clock
+    }
+
+    /// Returns the game state before the move
+    pub fn state(&self) -> &GameState{
+        &self.state
+    }
+
+    /// Returns the Zobrist hash of the position before the move
+    pub fn zobrist_hash(&self) -> u64{
+        self.zobrist
+    }
+}
+
+impl Game{
+    /// Returns every move played so far with what it changed, in order
+    pub fn history(&self) -> &[MoveRecord]{
+        &self.history
+    }
+
+    /// Returns the number of halfmoves played since the initial position
+    pub fn ply(&self) -> usize{
+        self.history.len()
+    }
+
+    /// Takes back the last move, or cancels a promotion that is waiting for a piece
+    /// 
+    /// A game that ended off the board, by resignation, agreement, a claimed draw, abandonment or on time,
+    /// keeps its result and its moves.
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns the movement that was taken back, `None` if there is nothing to undo
+    pub fn undo(&mut self) -> Option<Movement>{
+        if self.ended_off_the_board(){
+            return None;
+        }
+
+        if let Some(movement) = self.pending_promotion.take(){
+            self.update_state();
+            return Some(movement);
+        }
+
+        let movement = self.unmake_move()?;
+
+        self.undone.push(movement);
+        self.draw_offer = None;
+        self.sync_clock();
+
+        Some(movement)
+    }
+
+    /// Plays again the last move taken back by `undo`
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns the movement that was played, `None` if there is nothing to redo
+    pub fn redo(&mut self) -> Option<Movement>{
+        if self.state == GameState::Promoting{
+            return None;
+        }
+
+        let movement = self.undone.pop()?;
+
+        self.make_move(movement).expect("An undone move is legal in the position it was played from");
+        self.update_state();
+        self.sync_clock();
+
+        Some(movement)
+    }
+
+    /// Moves through the history until `ply` halfmoves have been played
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `ply` - 0 for the initial position, up to the number of played and undone moves
+    pub fn go_to_ply(&mut self, ply: usize) -> Result<&GameState, ChessError>{
+        if ply > self.history.len() + self.undone.len(){
+            return Err(ChessError::InvalidPly(ply));
+        }
+
+        if self.ended_off_the_board() && ply != self.ply(){
+            return Err(ChessError::GameOver);
+        }
+
+        while self.ply() > ply || self.pending_promotion.is_some(){
+            self.undo();
+        }
+
+        while self.ply() < ply{
+            self.redo();
+        }
+
+        Ok(&self.state)
+    }
+
+    /// Restores the position from before the last move in the history
+    pub(crate) fn unmake_move(&mut self) -> Option<Movement>{
+        let record = self.history.pop()?;
+        let movement = record.movement;
+        let color = self.turn.opposite();
+
+        movegen::revert_movement(&mut self.position, &record, color);
+
+        self.turn = color;
+        self.castling_rights = record.castling_rights;
+        self.en_passant = record.en_passant;
+        self.halfmove_clock = record.halfmove_clock;
+        self.fullmove_number = record.fullmove_number;
+        self.state = record.state;
+        self.zobrist = record.zobrist;
+
+        Some(movement)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{color::Color, game::{classic::ClassicGame, fen::STARTING_FEN}, piece::PieceType};
+
+    use super::*;
+
+    fn play(game: &mut Game, moves: &[&str]){
+        for san in moves{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+    }
+
+    fn assert_undo_restores(fen: &str, san: &str){
+        let mut game = Game::from_fen(fen).unwrap();
+
+        play(&mut game, &[san]);
+
+        assert_ne!(game.to_fen(), fen);
+        assert!(game.undo().is_some());
+        assert_eq!(game.to_fen(), fen, "undoing {} did not restore the position", san);
+    }
+
+    #[test]
+    fn test_undo_restores_every_kind_of_move(){
+        assert_undo_restores("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 3 17", "Qxf6");
+        assert_undo_restores("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 3 17", "O-O");
+        assert_undo_restores("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 3 17", "O-O-O");
+        assert_undo_restores("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 3 17", "hxg2");
+        assert_undo_restores("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "exd6");
+        assert_undo_restores("1r2k3/P7/8/8/8/8/8/4K3 w - - 7 40", "axb8=N");
+        assert_undo_restores("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "e4");
+    }
+
+    #[test]
+    fn test_undone_pawn_can_double_step_again(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4"]);
+        game.undo();
+        play(&mut game, &["e3", "a6"]);
+        game.undo();
+        game.undo();
+        play(&mut game, &["e4"]);
+
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    }
+
+    #[test]
+    fn test_undo_restores_the_state(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+
+        assert_eq!(game.state(), &GameState::Checkmate(Color::White));
+
+        let mate = *game.history()[3].movement();
+
+        assert_eq!(game.undo(), Some(mate));
+        assert_eq!(game.state(), &GameState::InProgress);
+        assert_eq!(game.ply(), 3);
+    }
+
+    #[test]
+    fn test_redo_replays_undone_moves(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4", "e5", "Nf3"]);
+
+        let fen = game.to_fen();
+
+        game.undo();
+        game.undo();
+
+        assert_eq!(game.ply(), 1);
+        assert!(game.redo().is_some());
+        assert!(game.redo().is_some());
+        assert!(game.redo().is_none());
+        assert_eq!(game.to_fen(), fen);
+    }
+
+    #[test]
+    fn test_new_move_clears_redo(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4", "e5"]);
+        game.undo();
+        play(&mut game, &["c5"]);
+
+        assert!(game.redo().is_none());
+        assert_eq!(game.ply(), 2);
+    }
+
+    #[test]
+    fn test_go_to_ply(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+
+        let fen = game.to_fen();
+
+        game.go_to_ply(0).unwrap();
+        assert_eq!(game.to_fen(), STARTING_FEN);
+
+        game.go_to_ply(3).unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+        game.go_to_ply(5).unwrap();
+        assert_eq!(game.to_fen(), fen);
+
+        assert!(game.go_to_ply(6).is_err());
+    }
+
+    #[test]
+    fn test_undo_cancels_pending_promotion(){
+        let mut game = Game::from_fen("8/P3k3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let movement = game.parse_san("a8=Q").unwrap();
+
+        game.move_piece(Movement::new(*movement.from(), *movement.to())).unwrap();
+
+        assert_eq!(game.state(), &GameState::Promoting);
+        assert_eq!(game.undo(), Some(Movement::new(*movement.from(), *movement.to())));
+        assert_eq!(game.state(), &GameState::InProgress);
+        assert_eq!(game.to_fen(), "8/P3k3/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(game.promote(PieceType::Queen).is_err());
+    }
+}
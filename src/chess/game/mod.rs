@@ -1,47 +1,119 @@
-use super::{board::Board, color::Color, movement::{Movement, relative_position::RelativePosition, absolute_position::AbsolutePosition, generate_valid_moves::GenerateValidMoves}, piece::Piece};
+use self::{castling::{CastleSide, CastlingRights}, clock::Clock, draw::DrawReason, history::MoveRecord};
+
+use super::{bitboard::position::Position, board::Board, error::ChessError, color::Color, movement::{Movement, absolute_position::AbsolutePosition}, piece::{Piece, PieceType}};
 
 pub mod classic;
+pub mod chess960;
+pub mod castling;
+pub mod clock;
+pub mod fen;
+pub mod san;
+pub mod history;
+pub mod draw;
+pub mod result;
+pub mod perft;
+pub mod movegen;
+pub mod zobrist;
 
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum GameState{
     Check(Color),
     Checkmate(Color),
+    /// The player ran out of time while the opponent could still checkmate
+    Timeout(Color),
+    /// The player resigned
+    Resigned(Color),
+    /// The player lost, read from a record that does not say how
+    Lost(Color),
     Stalemate,
+    Draw(DrawReason),
+    /// The game was left without a result
+    Abandoned,
     InProgress,
     Promoting,
 }
 
 #[derive(Clone)]
 pub struct Game{
-    board: Board,
+    position: Position,
     turn: Color,
-    moves: Vec<Movement>,
-    state: GameState
-}
-
-pub trait IGame{
-    fn new(board: Board, turn: Color) -> Self;
-    fn board(&self) -> &Board;
-    fn turn(&self) -> &Color;
-    fn state(&self) -> &GameState;
-    fn move_piece(&mut self, movement: Movement) -> Result<&GameState, String>;
-    fn get_values(&self) -> (i32, i32);
+    history: Vec<MoveRecord>,
+    undone: Vec<Movement>,
+    state: GameState,
+    pending_promotion: Option<Movement>,
+    en_passant: Option<AbsolutePosition>,
+    castling_rights: CastlingRights,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    initial_fen: String,
+    zobrist: u64,
+    chess960: bool,
+    clock: Option<Clock>,
+    draw_offer: Option<Color>,
 }
 
 impl Game{
 
+    /// Creates a game from a board, granting each castle whose king and rook are on their starting tiles
     pub fn new(board: Board, turn: Color) -> Self{
-        Game{
-            board,
+        Game::from_position(Position::from_board(&board), turn)
+    }
+
+    /// Creates a game from a bitboard position, granting each castle whose king and rook are on their starting tiles
+    pub fn from_position(position: Position, turn: Color) -> Self{
+        let castling_rights = Game::castling_rights_from_position(&position);
+
+        let mut game = Game{
+            position,
             turn,
-            moves: Vec::new(),
+            history: Vec::new(),
+            undone: Vec::new(),
             state: GameState::InProgress,
+            pending_promotion: None,
+            en_passant: None,
+            castling_rights,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            initial_fen: String::new(),
+            zobrist: 0,
+            chess960: false,
+            clock: None,
+            draw_offer: None,
+        };
+
+        game.zobrist = game.position.zobrist_hash() ^ game.zobrist_state_keys();
+        game.initial_fen = game.to_fen();
+
+        game
+    }
+
+    fn castling_rights_from_position(position: &Position) -> CastlingRights{
+        let mut castling_rights = CastlingRights::none();
+
+        for (color, rank) in [(Color::White, 0), (Color::Black, 7)]{
+            if position.piece_at(&AbsolutePosition::new(4, rank)) != Some((color, PieceType::King)){
+                continue;
+            }
+
+            for side in [CastleSide::KingSide, CastleSide::QueenSide]{
+                let rook = position.piece_at(&AbsolutePosition::new(side.rook_file(), rank));
+
+                castling_rights.set(&color, &side, rook == Some((color, PieceType::Rook)));
+            }
         }
+
+        castling_rights
+    }
+
+    /// Returns a tile by tile view of the position, built on every call
+    pub fn board(&self) -> Board{
+        self.position.to_board()
     }
 
-    pub fn board(&self) -> &Board{
-        &self.board
+    /// Returns the bitboards the game runs on
+    pub fn position(&self) -> &Position{
+        &self.position
     }
  
     pub fn turn(&self) -> &Color{
@@ -55,28 +127,128 @@ impl Game{
     /// 
     /// * `GameState::Check(color)` - If the current player is in check
     /// * `GameState::Checkmate(color)` - If the current player is in checkmate
+    /// * `GameState::Timeout(color)` - If the player ran out of time, see `set_clock`
+    /// * `GameState::Resigned(color)` - If the player resigned
+    /// * `GameState::Lost(color)` - If the player lost in a way the PGN the game was read from does not tell
+    /// * `GameState::Abandoned` - If the game was abandoned
     /// * `GameState::Stalemate` - If the game is in stalemate
+    /// * `GameState::Draw(reason)` - If the game ended in a draw on its own, or a player claimed one
     /// * `GameState::InProgress` - If the game is in progress
+    /// * `GameState::Promoting` - If a pawn reached the last rank and is waiting for `promote`
     /// 
     pub fn state(&self) -> &GameState{
         &self.state
     }
 
+    /// Whether the game ended, on the board, on time, by resignation, by agreement or by abandonment
+    pub fn is_over(&self) -> bool{
+        matches!(self.state, GameState::Checkmate(_) | GameState::Timeout(_) | GameState::Resigned(_) | GameState::Lost(_) | GameState::Stalemate | GameState::Draw(_) | GameState::Abandoned)
+    }
+
+    /// Returns the square a pawn skipped with a double step on the last move, which
+    /// an opposing pawn may capture en passant
+    pub fn en_passant(&self) -> Option<&AbsolutePosition>{
+        self.en_passant.as_ref()
+    }
+
+    /// Returns every movement played so far, in order
+    pub fn moves(&self) -> Vec<Movement>{
+        self.history.iter().map(|record| *record.movement()).collect()
+    }
+
+    /// Returns the position the game started from in Forsyth–Edwards Notation
+    pub fn initial_fen(&self) -> &str{
+        &self.initial_fen
+    }
+
+    /// Returns a new game from the position this one started from, under the same rules
+    pub(crate) fn initial_game(&self) -> Game{
+        match self.chess960{
+            true => chess960::Chess960Game::from_fen(&self.initial_fen),
+            false => Game::from_fen(&self.initial_fen),
+        }.expect("The initial position of a game is always valid")
+    }
+
+    /// Whether the game follows the Chess960 rules, where castles are played by moving the king onto its own rook
+    pub fn is_chess960(&self) -> bool{
+        self.chess960
+    }
+
+    /// Returns the number of halfmoves since the last capture or pawn move
+    pub fn halfmove_clock(&self) -> u32{
+        self.halfmove_clock
+    }
+
+    /// Returns the number of the current full move, starting at 1 and incremented after Black moves
+    pub fn fullmove_number(&self) -> u32{
+        self.fullmove_number
+    }
+
+    /// Returns the castles each player may still make
+    pub fn castling_rights(&self) -> &CastlingRights{
+        &self.castling_rights
+    }
+
+    /// Returns the pawn movement waiting for a piece to be chosen, if the game is promoting
+    pub fn pending_promotion(&self) -> Option<&Movement>{
+        self.pending_promotion.as_ref()
+    }
+
+    /// Returns every legal movement of the player to move
+    /// 
+    /// A pawn reaching the last rank yields one movement per promotion piece.
+    /// Nothing is legal once the game is over or while a promotion is pending.
+    pub fn legal_moves(&self) -> Vec<Movement>{
+        if self.is_over() || self.state == GameState::Promoting{
+            return Vec::new();
+        }
+
+        self.generate_legal_plays()
+    }
+
+    /// Returns the legal movements of the piece standing on `square`
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `square` - The tile the piece moves from, an empty tile or an opponent's piece has no movements
+    pub fn legal_moves_from(&self, square: &AbsolutePosition) -> Vec<Movement>{
+        self.legal_moves().into_iter().filter(|movement| movement.from() == square).collect()
+    }
+
+    /// Returns the legal movements that capture a piece, en passant included
+    pub fn legal_captures(&self) -> Vec<Movement>{
+        self.legal_moves().into_iter()
+            .filter(|movement| {
+                let is_pawn = self.position.pieces_of_type(PieceType::Pawn).contains(movement.from());
+
+                self.is_capture(movement.to(), is_pawn)
+            })
+            .collect()
+    }
+
 
     /// Moves a piece on the board
     /// 
+    /// With a clock attached, the move presses it once it is played. A player whose flag fell
+    /// cannot move anymore, the game is then over on time. A move completed after the flag fell during
+    /// it does not count and is taken back.
+    /// 
     /// ### Arguments
     /// 
     /// * `movement` - The movement to be made
     /// 
     /// ### Returns
     /// 
-    /// Returns a Result containing a reference to the game state if the move was successful, otherwise an error message
+    /// Returns a Result containing a reference to the game state if the move was successful, otherwise the `ChessError` explaining why not
+    /// 
+    /// A pawn moving onto the last rank promotes to the piece carried by the movement.
+    /// If the movement carries no promotion the game pauses in `GameState::Promoting`
+    /// until `promote` is called.
     /// 
     /// ## Examples
     /// 
     /// ```
-    /// use chess::{game::classic::ClassicGame, movement::chess_notation::ChessNotationPosition};
+    /// use better_chess::{ClassicGame, ChessNotationPosition, GameState, Movement};
     /// 
     /// let mut game = ClassicGame::new();
     /// 
@@ -84,527 +256,257 @@ impl Game{
     /// 
     /// let result = match game.move_piece(movement){
     ///    Ok(state) => state,
-    ///    Err(e) => panic!(e),
-    /// }; // GameState::InProgress
-    /// 
-    /// do_something_fun_with(result);
+    ///    Err(e) => panic!("{}", e),
+    /// };
     /// 
+    /// assert_eq!(result, &GameState::InProgress);
     /// ```
-    pub fn move_piece(&mut self, movement: Movement) -> Result<&GameState, String>{
+    pub fn move_piece(&mut self, movement: Movement) -> Result<&GameState, ChessError>{
         if self.state == GameState::Promoting{
-            return Err(String::from("Cannot move piece while promoting!"));
+            return Err(ChessError::PromotionPending);
         }
 
-        self.state = GameState::InProgress;
-
-        if let Err(e) = self.is_legal_play(&movement){
-            return Err(e);
+        if self.is_over() || self.check_flag().is_some(){
+            return Err(ChessError::GameOver);
         }
 
+        self.is_legal_play(&movement)?;
 
-        let mut game = self.clone();
-
-        game.make_move(movement.clone())?;
-
-        if let Some(color) = game.is_check(){
-            if color == *self.turn(){
-                return Err(String::from("Cannot move into check!"));
+        if movement.promotion().is_none() && self.is_promotion(&movement){
+            if self.leaves_king_in_check(&Movement::with_promotion(*movement.from(), *movement.to(), PieceType::Queen)){
+                return Err(ChessError::LeavesKingInCheck);
             }
 
-            self.state = GameState::Check(color);
-        }
+            self.pending_promotion = Some(movement);
+            self.state = GameState::Promoting;
 
-        if let Some(state) = game.is_check_mate(){
-            self.state = state;
+            return Ok(&self.state);
         }
-        
-        match self.make_move(movement){
-            Ok(_) => Ok(&self.state),
-            Err(e) => Err(e),
-        }
-    }
-
-    /// Returns the value of the pieces on the board for each player
-    /// 
-    /// ### Returns
-    /// 
-    /// * `(white_value, black_value)` - The value of the pieces for each player
-    pub fn get_values(&self) -> (i32, i32){
-        let mut white_value : i32 = 0;
-        let mut black_value : i32 = 0;
 
-        for (tile, _) in self.board.get_tiles(){
-            if let Some(piece) = tile.get_piece(){
-                if piece.color() == &Color::White{
-                    white_value += piece.value() as i32;
-                }else{
-                    black_value += piece.value() as i32;
-                }
-            }
+        if self.leaves_king_in_check(&movement){
+            return Err(ChessError::LeavesKingInCheck);
         }
 
-        (white_value, black_value)
-    }
-
-    fn make_move(&mut self, movement: Movement) -> Result<(), String>{
-        
-        match self.execute_castle(&movement) {
-            Ok(_) => (),
-            Err(err) => {
-                if err != String::from("Movement is not a castle!"){
-                    return Err(err);
-                }
-            }
-        };
-
-
-        let mut piece = match self.board.get_tile_mut(movement.from()).remove_piece(){
-            Some(piece) => piece,
-            None => return Err(String::from("No piece on tile!")),
-        };
-
-        piece.moved();
+        self.make_move(movement)?;
+        self.update_state();
 
-        self.board.get_tile_mut(movement.to()).set_piece(piece);
+        let is_over = self.is_over();
 
-        self.turn = match self.turn(){
-            Color::White => Color::Black,
-            Color::Black => Color::White,
-        };
-
-        self.moves.push(movement);
-
-        Ok(())
-    }
-
-    fn is_legal_play(&self, movement: &Movement) -> Result<(), String>{
-        let piece = match self.board.get_tile(movement.from()).get_piece(){
-            Some(piece) => piece,
-            None => return Err(String::from("No piece on tile!")),
-        };
-
-        if piece.color() != self.turn(){
-            return Err(String::from("Cannot move opponent's piece!"));
+        if let Some(clock) = &mut self.clock{
+            match is_over{
+                true => clock.stop(),
+                false => clock.press(),
+            }
         }
 
-        let relative_position = movement.to().to_relative(movement.from());
-
-        let valid = match self.board.get_tile(movement.to()).get_piece() {
-            None => self.is_legal_move(piece, movement.from(), &relative_position),
-            Some(to_piece) => self.is_legal_capture(piece, movement.from(), to_piece, &relative_position),
-        };
+        // The time may have run out while the move was checked, pressing the clock then flags the mover
+        if self.clock.as_ref().and_then(Clock::flagged).is_some(){
+            self.unmake_move();
+            self.check_flag();
 
-        if valid {
-            return Ok(());
+            return Err(ChessError::GameOver);
         }
 
-        if piece.prefix() == 'P'{
-            return self.allow_en_passante(piece, movement);
-        }
+        self.undone.clear();
 
-        if piece.prefix() == 'K'{
-            return self.allow_castle(piece, movement);
+        if self.draw_offer == Some(self.turn){
+            self.draw_offer = None;
         }
 
-        Err(String::from("Invalid move!"))
+        Ok(&self.state)
     }
 
-    fn allow_en_passante(&self, piece : &Piece, movement: &Movement) -> Result<(), String>{
-        if piece.prefix() != 'P'{
-            return Err(String::from("Invalid move!"));
-        }
-
-        let last_move = match self.moves.last() {
+    /// Finishes a pawn promotion started by `move_piece`
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `piece_type` - The piece the pawn turns into, one of queen, rook, bishop or knight
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns a Result containing a reference to the game state once the pawn has been promoted, otherwise the `ChessError` explaining why not
+    pub fn promote(&mut self, piece_type: PieceType) -> Result<&GameState, ChessError>{
+        let movement = match self.pending_promotion.take(){
             Some(movement) => movement,
-            None => return Err(String::from("Invalid move!")),
+            None => return Err(ChessError::NoPendingPromotion),
         };
 
-        let last_move_piece = self.board.get_tile(last_move.to()).get_piece().as_ref().unwrap();
-
-        if last_move_piece.prefix() != 'P' {
-            return Err(String::from("Invalid move!"));
-        }
+        self.update_state();
 
-        if last_move.from().to_relative(last_move.to()) != RelativePosition::new(0, 2){
-            return Err(String::from("Invalid move!"));
-        }
+        if let Err(e) = self.move_piece(Movement::with_promotion(*movement.from(), *movement.to(), piece_type)){
+            if !self.is_over(){
+                self.pending_promotion = Some(movement);
+                self.state = GameState::Promoting;
+            }
 
-        if  RelativePosition::new(0, 1).to_absolute(last_move.to()).unwrap() != *movement.to(){
-            return Err(String::from("Invalid move!"));
+            return Err(e);
         }
 
-        return Ok(());
+        Ok(&self.state)
     }
 
-    /// Allows the player to castle
-    /// 
-    /// ### Arguments
-    /// 
-    /// * `piece` - The king piece
-    /// * `movement` - The movement to be made
+    /// Returns the value of the pieces on the board for each player
     /// 
-    /// A queen side castle is defined by moving the king two tiles to the left
-    /// A king side castle is defined by moving the king two tiles to the right
+    /// ### Returns
     /// 
-    /// then a check is ran to verify that the king and corresponding rook have not moved
-    /// also checks that the king is not moving through check
-    fn allow_castle(&self, piece : &Piece, movement: &Movement) -> Result<(), String>{
+    /// * `(white_value, black_value)` - The value of the pieces for each player
+    pub fn get_values(&self) -> (i32, i32){
+        let mut white_value : i32 = 0;
+        let mut black_value : i32 = 0;
 
+        for square in self.position.occupied(){
+            if let Some((color, piece_type)) = self.position.piece_at(&square){
+                let value = Piece::new(color, piece_type).value() as i32;
 
-        if piece.prefix() != 'K'{
-            return Err(String::from("Invalid move!"));
+                match color{
+                    Color::White => white_value += value,
+                    Color::Black => black_value += value,
+                }
+            }
         }
 
-        if piece.has_moved(){
-            return Err(String::from("Invalid move!"));
-        }
+        (white_value, black_value)
+    }
 
-        enum Castle{
-            QueenSide,
-            KingSide,
-        }
+    /// Plays `movement` without checking that it is legal or updating the game state, `unmake_move` takes it back
+    pub(crate) fn make_move(&mut self, movement: Movement) -> Result<(), ChessError>{
+        let (color, piece_type) = match self.position.piece_at(movement.from()){
+            Some(piece) => piece,
+            None => return Err(ChessError::NoPieceOnSquare(*movement.from())),
+        };
 
-        let allowed_rank = match piece.color(){
-            Color::White => 0,
-            Color::Black => 7,
+        let castle = self.castle(&movement);
+
+        let record = MoveRecord{
+            movement,
+            piece: piece_type,
+            captured: None,
+            castle,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            state: self.state.clone(),
+            zobrist: self.zobrist,
         };
 
-        let allowed_file = 4;
+        let placement = self.position.zobrist_hash();
+        self.zobrist ^= self.zobrist_state_keys();
 
-        let allowed_king_position = AbsolutePosition::new(allowed_file, allowed_rank);
+        let is_pawn = piece_type == PieceType::Pawn;
+        let captured = movegen::apply_movement(&mut self.position, &movement, self.en_passant, castle);
 
-        if movement.from() != &allowed_king_position{
-            return Err(String::from("Invalid move!"));
+        if piece_type == PieceType::King{
+            self.castling_rights.remove_all(&color);
         }
 
-        let castle = match movement.to().file{
-            2 => Castle::QueenSide,
-            6 => Castle::KingSide,
-            _ => return Err(String::from("Invalid move!")),
-        };
-
-        let rook_position = match castle{
-            Castle::QueenSide => AbsolutePosition::new(0, allowed_rank),
-            Castle::KingSide => AbsolutePosition::new(7, allowed_rank),
-        };
+        self.castling_rights.remove_rook_square(movement.from());
+        self.castling_rights.remove_rook_square(movement.to());
 
-        let rook = match self.board.get_tile(&rook_position).get_piece(){
-            Some(piece) => {
-                if piece.prefix() != 'R'{
-                    return Err(String::from("Invalid move!"));
-                }
+        self.en_passant = movegen::en_passant_square(piece_type, &movement);
 
-                piece
-            },
-            None => return Err(String::from("Invalid move!"))
+        self.halfmove_clock = match is_pawn || captured.is_some(){
+            true => 0,
+            false => self.halfmove_clock + 1,
         };
 
-        if rook.has_moved(){
-            return Err(String::from("Invalid move!"));
+        if self.turn == Color::Black{
+            self.fullmove_number += 1;
         }
 
-        let relative_position = movement.to().to_relative(movement.from());
-
-        let (path, valid) = match castle{
-            Castle::QueenSide => piece.castle_queen_side(&relative_position),
-            Castle::KingSide => piece.castle_king_side(&relative_position),
+        self.turn = match self.turn(){
+            Color::White => Color::Black,
+            Color::Black => Color::White,
         };
 
-        if !valid || self.is_colliding(&path, movement.from()){
-            return Err(String::from("Invalid move!"));
-        }
-
-        if self.path_is_in_check(&path, movement.from()){
-            return Err(String::from("Invalid move!"));
-        }
+        self.zobrist ^= placement ^ self.position.zobrist_hash() ^ self.zobrist_state_keys();
+        self.history.push(MoveRecord{captured, ..record});
 
         Ok(())
     }
 
-    fn execute_castle(&mut self, movement: &Movement) -> Result<(), String>{
-
-        enum Castle{
-            QueenSide,
-            KingSide,
-        }
-
-        let piece = match self.board.get_tile(movement.from()).get_piece(){
-            Some(piece) => piece,
-            None => return Err(String::from("Movement is not a castle!")),
-        };
-
-        if !self.movement_is_castle(piece, movement){
-            return Err(String::from("Movement is not a castle!"));
-        }
-
-        let castle = match movement.to().file{
-            2 => Castle::QueenSide,
-            6 => Castle::KingSide,
-            _ => return Err(String::from("Invalid move!")),
-        };
-
-        let rook_position = match castle{
-            Castle::QueenSide => AbsolutePosition::new(0, movement.to().rank),
-            Castle::KingSide => AbsolutePosition::new(7, movement.to().rank),
-        };
-
-        let rook = match self.board.get_tile_mut(&rook_position).remove_piece(){
+    fn is_legal_play(&self, movement: &Movement) -> Result<(), ChessError>{
+        let (color, piece_type) = match self.position.piece_at(movement.from()){
             Some(piece) => piece,
-            None => return Err(String::from("Invalid move!")),
-        };
-
-        let rook_position = match castle{
-            Castle::QueenSide => AbsolutePosition::new(3, movement.to().rank),
-            Castle::KingSide => AbsolutePosition::new(5, movement.to().rank),
+            None => return Err(ChessError::NoPieceOnSquare(*movement.from())),
         };
 
-        self.board.get_tile_mut(&rook_position).set_piece(rook);
-
-        Ok(())
-    }
-
-    fn movement_is_castle(&self, piece : &Piece, movement: &Movement) -> bool{
-        if piece.prefix() != 'K'{
-            return false;
-        }
-
-        let relative_position = movement.to().to_relative(movement.from());
-
-        if !piece.castle_king_side(&relative_position).1 && !piece.castle_queen_side(&relative_position).1{
-            return false;
+        if &color != self.turn(){
+            return Err(ChessError::NotYourTurn);
         }
 
-        return true;
-    }
-
-    fn is_legal_move(&self, piece: &Piece, piece_position: &AbsolutePosition, relative_position: &RelativePosition) -> bool{
-        let (path, valid) = piece.valid_move(relative_position);
-
-        if !valid || self.is_colliding(&path, piece_position){
-            return false;
-        }
-
-        true
-    }
-
-    fn is_legal_capture(&self, piece: &Piece, piece_position: &AbsolutePosition, to_piece: &Piece, relative_position: &RelativePosition) -> bool{
-        if piece.color() == to_piece.color(){
-            return false;
-        }
-
-        let (path, valid) = piece.valid_capture(relative_position);
-
-        if !valid || self.is_colliding(&path, piece_position){
-            return false;
-        }
-
-        true
-    }
-    
-    fn movement_from_chess_notation(&self, destination: AbsolutePosition, piece_prefix : Option<char>, rank_from : Option<usize>, file_from: Option<usize>, is_capture: bool) -> Result<Movement,String>{
-        let piece_prefix = match piece_prefix{
-            Some(prefix) => prefix,
-            None => 'P',
-        };
-
-        let mut response : Option<Movement> = None;
-
-        let legal_moves = match is_capture{
-            true => self.generate_legal_captures_for_piece_type(piece_prefix, &self.turn()),
-            false => self.generate_legal_move_for_piece_type(piece_prefix, &self.turn()),
-        };
-
-        for movement in legal_moves{
-            if movement.to() == &destination{
-                if response.is_some(){
-
-                    if let Some(rank_from) = rank_from{
-                        if movement.from().rank != rank_from{
-                            continue;
-                        }
-                    }
-
-                    if let Some(file_from) = file_from{
-                        if movement.from().file != file_from{
-                            continue;
-                        }
-                    }
-
-                    return Err(String::from("Ambiguous move!"));
-
-                }
-
-                response = Some(movement);
+        if let Some(promotion) = movement.promotion(){
+            if !promotion.is_promotion() || piece_type != PieceType::Pawn || !self.is_promotion(movement){
+                return Err(ChessError::InvalidPromotion);
             }
         }
 
-        match response{
-            Some(movement) => Ok(movement),
-            None => Err(String::from("Invalid move!")),
-        }
-    }
-
-    fn generate_legal_move_for_piece_type(&self, piece_prefix: char,color : &Color) -> Vec<Movement>{
-        let mut legal_moves = Vec::new();
-
-        for (tile, position) in self.board.get_tiles(){
-            if let Some(piece) = tile.get_piece(){
-                if piece.color() == color{
-                    if piece.prefix() != piece_prefix {
-                        continue;
-                    }
-                    for relative_position in piece.generate_valid_moves(){
-
-                        let absolute = match relative_position.to_absolute(&position){
-                            Ok(position) => position,
-                            Err(_) => continue,
-                        };
-
-                        match self.is_legal_play(&Movement::new(position.clone(), absolute.clone())){
-                            Ok(_) => (),
-                            Err(_) => continue,
-                        };
-
-                        let movement = Movement::new(position.clone(), absolute);
-
-                        let mut game = self.clone();
-
-                        game.make_move(movement.clone()).unwrap();
-
-                        if let Some(color) = game.is_check(){
-                            if color == *self.turn(){
-                                continue;
-                            }
-                        }
-
-                        legal_moves.push(movement);
-                    }
-                }
-            }
+        if !self.pseudo_legal_targets(movement.from()).contains(movement.to()){
+            return Err(ChessError::IllegalMove);
         }
 
-        legal_moves
+        Ok(())
     }
 
+    fn is_promotion(&self, movement: &Movement) -> bool{
+        let last_rank = match self.position.piece_at(movement.from()){
+            Some((Color::White, PieceType::Pawn)) => 7,
+            Some((Color::Black, PieceType::Pawn)) => 0,
+            _ => return false,
+        };
 
-    fn generate_legal_captures_for_piece_type(&self, piece_prefix: char,color : &Color) -> Vec<Movement>{
-        let mut legal_moves = Vec::new();
-
-        for (tile, position) in self.board.get_tiles(){
-            if let Some(piece) = tile.get_piece(){
-                if piece.color() == color{
-                    if piece.prefix() != piece_prefix {
-                        continue;
-                    }
-                    for relative_position in piece.generate_valid_captures(){
-
-                        let absolute = match relative_position.to_absolute(&position){
-                            Ok(position) => position,
-                            Err(_) => continue,
-                        };
-
-                        match self.is_legal_play(&Movement::new(position.clone(), absolute.clone())){
-                            Ok(_) => (),
-                            Err(_) => continue,
-                        };
-
-                        let movement = Movement::new(position.clone(), absolute);
-
-                        let mut game = self.clone();
-
-                        game.make_move(movement.clone()).unwrap();
-
-                        if let Some(color) = game.is_check(){
-                            if color == *self.turn(){
-                                continue;
-                            }
-                        }
-
-                        legal_moves.push(movement);
-                    }
-                }
-            }
-        }
-
-        legal_moves
+        movement.to().rank == last_rank
     }
 
-    fn generate_legal_plays(&self, color : &Color) -> Vec<Movement>{
-        let mut legal_moves = Vec::new();
-
-        for (tile, position) in self.board.get_tiles(){
-            if let Some(piece) = tile.get_piece(){
-                if piece.color() == color{
-                    for relative_position in piece.generate_valid_plays(){
-
-                        let absolute = match relative_position.to_absolute(&position){
-                            Ok(position) => position,
-                            Err(_) => continue,
-                        };
-
-                        match self.is_legal_play(&Movement::new(position.clone(), absolute.clone())){
-                            Ok(_) => (),
-                            Err(_) => continue,
-                        };
-
-                        let movement = Movement::new(position.clone(), absolute);
-
-                        let mut game = self.clone();
-
-                        game.make_move(movement.clone()).unwrap();
-
-                        if let Some(color) = game.is_check(){
-                            if color == *self.turn(){
-                                continue;
-                            }
-                        }
-
-                        legal_moves.push(movement);
-                    }
-                }
-            }
+    /// Expands a pawn movement onto the last rank into one movement per promotion piece
+    fn with_promotions(&self, movement: Movement) -> Vec<Movement>{
+        if !self.is_promotion(&movement){
+            return vec![movement];
         }
 
-        legal_moves
+        PieceType::PROMOTIONS.iter().map(|piece_type| Movement::with_promotion(*movement.from(), *movement.to(), *piece_type)).collect()
     }
 
-    fn is_colliding(&self, movement_path: &Vec<RelativePosition>, piece_position : &AbsolutePosition) -> bool{
-        for relative_position in movement_path{
-            let position = relative_position.to_absolute(piece_position).unwrap();
-
-            if self.board.get_tile(&position).get_piece().is_some(){
-                return true;
-            }
+    /// Finds the legal movement described by the parts of a SAN move
+    /// 
+    /// Every hint that is given has to match, so a file or rank hint only picks between
+    /// movements that really reach the same destination
+    fn movement_from_chess_notation(&self, destination: AbsolutePosition, piece_prefix : Option<char>, rank_from : Option<usize>, file_from: Option<usize>, is_capture: bool, promotion: Option<PieceType>) -> Result<Movement, ChessError>{
+        let piece_type = piece_prefix.and_then(PieceType::from_prefix).unwrap_or(PieceType::Pawn);
+
+        let candidates : Vec<Movement> = self.generate_legal_plays_for_piece_type(piece_type).into_iter()
+            .filter(|movement| movement.to() == &destination)
+            .filter(|movement| piece_type != PieceType::Pawn || is_capture == (movement.from().file != movement.to().file))
+            .filter(|movement| rank_from.is_none_or(|rank| movement.from().rank == rank))
+            .filter(|movement| file_from.is_none_or(|file| movement.from().file == file))
+            .filter(|movement| movement.promotion() == promotion)
+            .collect();
+
+        match candidates.as_slice(){
+            [movement] => Ok(*movement),
+            [] => Err(ChessError::IllegalMove),
+            _ => Err(ChessError::AmbiguousMove(candidates)),
         }
-        false   
     }
 
-    fn path_is_in_check(&self, movement_path: &Vec<RelativePosition>, piece_position : &AbsolutePosition) -> bool{
-        let game = self.clone();
-
-        for relative_position in movement_path{
-            let position = relative_position.to_absolute(piece_position).unwrap();
-
-            let mut game = game.clone();
-
-            game.make_move(Movement::new(piece_position.clone(), position.clone())).unwrap();
-
-            if let Some(color) = game.is_check(){
-                if color == *self.turn(){
-                    return true;
-                }
-            }
-        }
-
-        false
+    /// Recomputes the state for the player to move, used when a game starts from an arbitrary position
+    fn update_state(&mut self){
+        self.state = match self.is_check_mate(){
+            Some(state) => state,
+            None => match self.automatic_draw(){
+                Some(reason) => GameState::Draw(reason),
+                None if self.is_check_color(self.turn()) => GameState::Check(*self.turn()),
+                None => GameState::InProgress,
+            },
+        };
     }
 
     fn is_check_mate(&self) -> Option<GameState>{
-        let legal_moves = self.generate_legal_plays(&self.turn());
+        let legal_moves = self.generate_legal_plays();
 
-        if legal_moves.len() == 0{
-            if let Some(color) = self.is_check(){
-                return Some(GameState::Checkmate(color));
+        if legal_moves.is_empty(){
+            if self.is_check_color(self.turn()){
+                return Some(GameState::Checkmate(*self.turn()));
             }
 
             return Some(GameState::Stalemate);
@@ -613,48 +515,16 @@ impl Game{
         None
     }
 
-    fn is_check(&self) -> Option<Color>{
-        if self.is_check_color(&Color::White){
-            Some(Color::White)
-        }else if self.is_check_color(&Color::Black){
-            Some(Color::Black)
-        }else{
-            None
-        }
-    }
-
-
     fn is_check_color(&self, color: &Color) -> bool{
-        let king_position = match self.board.get_king_position(color){
-            Some(position) => position,
-            None => return false,
-        };
-
-        let king_piece = match self.board.get_tile(&king_position).get_piece(){
-            Some(piece) => piece,
-            None => return false,
-        };
-
-        for (tile, position) in self.board.get_tiles(){
-            if let Some(piece) = tile.get_piece(){
-                if piece.color() != color{
-                    let relative_position = king_position.to_relative(&position);
-
-                    let valid = self.is_legal_capture(piece, &position, king_piece, &relative_position);
-
-                    if valid {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        self.position.in_check(color)
     }
 }
 
 #[cfg(test)]
 mod tests{
-    use crate::chess::{game::classic::ClassicGame, movement::chess_notation::ChessNotationPosition};
+    use std::str::FromStr;
+
+    use crate::chess::{game::{chess960::Chess960Game, classic::ClassicGame}, movement::chess_notation::ChessNotationPosition};
 
     use super::*;
 
@@ -673,7 +543,7 @@ mod tests{
 
         let movement = Movement::new(AbsolutePosition::new(0, 6), AbsolutePosition::new(0, 5));
 
-        assert_eq!(game.move_piece(movement), Err(String::from("Cannot move opponent's piece!")));
+        assert_eq!(game.move_piece(movement), Err(ChessError::NotYourTurn));
     }
 
     #[test]
@@ -682,7 +552,7 @@ mod tests{
 
         let movement = Movement::new(ChessNotationPosition::new('a', 1).to_position(), ChessNotationPosition::new('a', 2).to_position());
 
-        assert_eq!(game.move_piece(movement), Err(String::from("Invalid move!")));
+        assert_eq!(game.move_piece(movement), Err(ChessError::IllegalMove));
     }
 
     #[test]
@@ -691,7 +561,7 @@ mod tests{
 
         let movement = Movement::new(ChessNotationPosition::new('a', 1).to_position(), ChessNotationPosition::new('a', 4).to_position());
 
-        assert_eq!(game.move_piece(movement), Err(String::from("Invalid move!")));
+        assert_eq!(game.move_piece(movement), Err(ChessError::IllegalMove));
     }
 
     #[test]
@@ -733,7 +603,7 @@ mod tests{
     fn test_generates_valid_moves(){
         let game = ClassicGame::new();
 
-        let moves = game.generate_legal_plays(game.turn());
+        let moves = game.generate_legal_plays();
 
         for movement in moves{
             let mut game = game.clone();
@@ -758,7 +628,7 @@ mod tests{
         assert_eq!(game.move_piece(m3),  Ok(&GameState::Check(Color::Black)));
         assert_eq!(game.state(), &GameState::Check(Color::Black));
 
-        let moves = game.generate_legal_plays(game.turn());
+        let moves = game.generate_legal_plays();
 
         let expected_move = Movement::new(ChessNotationPosition::new('g', 7).to_position(), ChessNotationPosition::new('g', 6).to_position());
 
@@ -807,7 +677,7 @@ mod tests{
         assert_eq!(game.move_piece(m4), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m5), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m6),  Ok(&GameState::Check(Color::White)));
-        assert_eq!(game.move_piece(m7), Err(String::from("Cannot move into check!")));
+        assert_eq!(game.move_piece(m7), Err(ChessError::LeavesKingInCheck));
     }
 
     #[test]
@@ -906,8 +776,9 @@ mod tests{
         assert_eq!(game.move_piece(m6), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m7), Ok(&GameState::InProgress));
 
-        let king_tile = game.board.get_tile(&ChessNotationPosition::new('g', 1).to_position());
-        let rook_tile = game.board.get_tile(&ChessNotationPosition::new('f', 1).to_position());
+        let board = game.board();
+        let king_tile = board.get_tile(&ChessNotationPosition::new('g', 1).to_position());
+        let rook_tile = board.get_tile(&ChessNotationPosition::new('f', 1).to_position());
 
         let king_piece = match king_tile.get_piece(){
             Some(piece) => piece,
@@ -947,8 +818,9 @@ mod tests{
         assert_eq!(game.move_piece(m8), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m9), Ok(&GameState::InProgress));
 
-        let king_tile = game.board.get_tile(&ChessNotationPosition::new('c', 1).to_position());
-        let rook_tile = game.board.get_tile(&ChessNotationPosition::new('d', 1).to_position());
+        let board = game.board();
+        let king_tile = board.get_tile(&ChessNotationPosition::new('c', 1).to_position());
+        let rook_tile = board.get_tile(&ChessNotationPosition::new('d', 1).to_position());
 
         let king_piece = match king_tile.get_piece(){
             Some(piece) => piece,
@@ -986,10 +858,11 @@ mod tests{
         assert_eq!(game.move_piece(m6), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m7), Ok(&GameState::InProgress));
         assert_eq!(game.move_piece(m8), Ok(&GameState::InProgress));
-        assert_eq!(game.move_piece(m9), Err(String::from("Invalid move!")));
+        assert_eq!(game.move_piece(m9), Err(ChessError::IllegalMove));
 
-        let king_tile = game.board.get_tile(&ChessNotationPosition::new('e', 1).to_position());
-        let rook_tile = game.board.get_tile(&ChessNotationPosition::new('a', 1).to_position());
+        let board = game.board();
+        let king_tile = board.get_tile(&ChessNotationPosition::new('e', 1).to_position());
+        let rook_tile = board.get_tile(&ChessNotationPosition::new('a', 1).to_position());
 
         let king_piece = match king_tile.get_piece(){
             Some(piece) => piece,
@@ -1011,7 +884,7 @@ mod tests{
 
         let input = ChessNotationPosition::new('e', 4).to_position();
 
-        let movement = game.movement_from_chess_notation(input, None, None, None, false);
+        let movement = game.movement_from_chess_notation(input, None, None, None, false, None);
 
         assert_eq!(movement, Ok(Movement::new(ChessNotationPosition::new('e', 2).to_position(), ChessNotationPosition::new('e', 4).to_position())));
     }
@@ -1026,24 +899,398 @@ mod tests{
         let i4 = ChessNotationPosition::new('a', 5).to_position();
         let i5_ambiguous = ChessNotationPosition::new('f', 5).to_position();
 
-        let m1 = game.movement_from_chess_notation(i1, None, None, None, false);
+        let m1 = game.movement_from_chess_notation(i1, None, None, None, false, None);
         game.move_piece(m1.unwrap()).unwrap();
-        let m2 = game.movement_from_chess_notation(i2, None, None, None, false);
+        let m2 = game.movement_from_chess_notation(i2, None, None, None, false, None);
         game.move_piece(m2.unwrap()).unwrap();
-        let m3 = game.movement_from_chess_notation(i3, None, None, None, false);
+        let m3 = game.movement_from_chess_notation(i3, None, None, None, false, None);
         game.move_piece(m3.unwrap()).unwrap();
-        let m4 = game.movement_from_chess_notation(i4, None, None, None, false);
+        let m4 = game.movement_from_chess_notation(i4, None, None, None, false, None);
         game.move_piece(m4.unwrap()).unwrap();
-        let m5_ambiguous = game.movement_from_chess_notation(i5_ambiguous, None, None, None, true);
+        let m5_ambiguous = game.movement_from_chess_notation(i5_ambiguous, None, None, None, true, None);
 
-        assert_eq!(m5_ambiguous, Err(String::from("Ambiguous move!")));
+        assert!(matches!(m5_ambiguous, Err(ChessError::AmbiguousMove(_))));
 
-        let m5_ambiguous = game.movement_from_chess_notation(i5_ambiguous, None,  Some(i1.rank), None, true);
+        let m5_ambiguous = game.movement_from_chess_notation(i5_ambiguous, None,  Some(i1.rank), None, true, None);
 
-        assert_eq!(m5_ambiguous, Err(String::from("Ambiguous move!")));
+        assert!(matches!(m5_ambiguous, Err(ChessError::AmbiguousMove(_))));
 
-        let m5 = game.movement_from_chess_notation(i5_ambiguous, None, None, Some(i1.file), true);
+        let m5 = game.movement_from_chess_notation(i5_ambiguous, None, None, Some(i1.file), true, None);
 
         assert_eq!(m5, Ok(Movement::new(ChessNotationPosition::new('e', 4).to_position(), ChessNotationPosition::new('f', 5).to_position())));
     }
-}
\ No newline at end of file
+
+    fn game_with_pieces(pieces: &[(&str, Color, PieceType)], turn: Color) -> Game{
+        let mut board = Board::new();
+
+        for (position, color, piece_type) in pieces{
+            let position = ChessNotationPosition::from_str(position).unwrap().to_position();
+            board.get_tile_mut(&position).set_piece(Piece::new(*color, *piece_type));
+        }
+
+        Game::new(board, turn)
+    }
+
+    fn notation(from: &str, to: &str) -> Movement{
+        Movement::new(ChessNotationPosition::from_str(from).unwrap().to_position(), ChessNotationPosition::from_str(to).unwrap().to_position())
+    }
+
+    #[test]
+    fn test_pawn_reaching_last_rank_waits_for_promotion(){
+        let mut game = game_with_pieces(&[
+            ("a7", Color::White, PieceType::Pawn),
+            ("e1", Color::White, PieceType::King),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::White);
+
+        assert_eq!(game.move_piece(notation("a7", "a8")), Ok(&GameState::Promoting));
+        assert_eq!(game.pending_promotion(), Some(&notation("a7", "a8")));
+        assert_eq!(game.turn(), &Color::White);
+        assert_eq!(game.move_piece(notation("e1", "d1")), Err(ChessError::PromotionPending));
+
+        assert_eq!(game.promote(PieceType::Queen), Ok(&GameState::Check(Color::Black)));
+
+        let board = game.board();
+        let piece = board.get_tile(&ChessNotationPosition::new('a', 8).to_position()).get_piece().as_ref().unwrap();
+
+        assert_eq!(piece.piece_type(), PieceType::Queen);
+        assert_eq!(game.turn(), &Color::Black);
+        assert_eq!(game.pending_promotion(), None);
+        assert_eq!(game.moves().last().unwrap().promotion(), Some(PieceType::Queen));
+    }
+
+    #[test]
+    fn test_cannot_promote_to_king_or_pawn(){
+        let mut game = game_with_pieces(&[
+            ("a7", Color::White, PieceType::Pawn),
+            ("e1", Color::White, PieceType::King),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::White);
+
+        assert_eq!(game.move_piece(notation("a7", "a8")), Ok(&GameState::Promoting));
+        assert_eq!(game.promote(PieceType::King), Err(ChessError::InvalidPromotion));
+        assert_eq!(game.promote(PieceType::Pawn), Err(ChessError::InvalidPromotion));
+        assert_eq!(game.state(), &GameState::Promoting);
+        assert_eq!(game.promote(PieceType::Rook), Ok(&GameState::Check(Color::Black)));
+    }
+
+    #[test]
+    fn test_promotion_is_only_allowed_on_the_last_rank(){
+        let mut game = ClassicGame::new();
+
+        let movement = Movement::with_promotion(ChessNotationPosition::new('e', 2).to_position(), ChessNotationPosition::new('e', 4).to_position(), PieceType::Queen);
+
+        assert_eq!(game.move_piece(movement), Err(ChessError::InvalidPromotion));
+        assert_eq!(game.promote(PieceType::Queen), Err(ChessError::NoPendingPromotion));
+    }
+
+    #[test]
+    fn test_under_promotion_to_knight_gives_check(){
+        let mut game = game_with_pieces(&[
+            ("f7", Color::White, PieceType::Pawn),
+            ("a1", Color::White, PieceType::King),
+            ("h7", Color::Black, PieceType::King),
+            ("a7", Color::Black, PieceType::Pawn),
+        ], Color::White);
+
+        let movement = Movement::with_promotion(ChessNotationPosition::new('f', 7).to_position(), ChessNotationPosition::new('f', 8).to_position(), PieceType::Knight);
+
+        assert_eq!(game.move_piece(movement), Ok(&GameState::Check(Color::Black)));
+    }
+
+    #[test]
+    fn test_promotion_can_deliver_checkmate(){
+        let mut game = game_with_pieces(&[
+            ("a7", Color::White, PieceType::Pawn),
+            ("g6", Color::White, PieceType::King),
+            ("h8", Color::Black, PieceType::King),
+        ], Color::White);
+
+        let movement = Movement::with_promotion(ChessNotationPosition::new('a', 7).to_position(), ChessNotationPosition::new('a', 8).to_position(), PieceType::Queen);
+
+        assert_eq!(game.move_piece(movement), Ok(&GameState::Checkmate(Color::Black)));
+    }
+
+    #[test]
+    fn test_black_pawn_can_promote_by_capturing(){
+        let mut game = game_with_pieces(&[
+            ("b2", Color::Black, PieceType::Pawn),
+            ("a1", Color::White, PieceType::Rook),
+            ("h1", Color::White, PieceType::King),
+            ("h8", Color::Black, PieceType::King),
+        ], Color::Black);
+
+        let movement = Movement::with_promotion(ChessNotationPosition::new('b', 2).to_position(), ChessNotationPosition::new('a', 1).to_position(), PieceType::Queen);
+
+        assert_eq!(game.move_piece(movement), Ok(&GameState::Check(Color::White)));
+        assert_eq!(game.get_values(), (0, 9));
+    }
+
+    #[test]
+    fn test_generated_moves_include_every_promotion(){
+        let game = game_with_pieces(&[
+            ("c7", Color::White, PieceType::Pawn),
+            ("a1", Color::White, PieceType::King),
+            ("h1", Color::Black, PieceType::King),
+        ], Color::White);
+
+        let moves = game.generate_legal_plays();
+
+        for piece_type in PieceType::PROMOTIONS{
+            let movement = Movement::with_promotion(ChessNotationPosition::new('c', 7).to_position(), ChessNotationPosition::new('c', 8).to_position(), piece_type);
+            assert!(moves.contains(&movement), "Missing promotion to {:?}", piece_type);
+        }
+
+        assert!(!moves.contains(&notation("c7", "c8")));
+    }
+
+    #[test]
+    fn test_en_passant_removes_the_captured_pawn(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(notation("e2", "e4")).unwrap();
+        game.move_piece(notation("a7", "a6")).unwrap();
+        game.move_piece(notation("e4", "e5")).unwrap();
+        game.move_piece(notation("d7", "d5")).unwrap();
+
+        assert_eq!(game.en_passant(), Some(&ChessNotationPosition::new('d', 6).to_position()));
+        assert_eq!(game.move_piece(notation("e5", "d6")), Ok(&GameState::InProgress));
+
+        let board = game.board();
+        assert!(board.get_tile(&ChessNotationPosition::new('d', 5).to_position()).get_piece().is_none());
+        assert!(board.get_tile(&ChessNotationPosition::new('d', 6).to_position()).get_piece().is_some());
+        assert_eq!(game.get_values(), (39, 38));
+    }
+
+    #[test]
+    fn test_black_can_capture_en_passant(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(notation("a2", "a3")).unwrap();
+        game.move_piece(notation("d7", "d5")).unwrap();
+        game.move_piece(notation("a3", "a4")).unwrap();
+        game.move_piece(notation("d5", "d4")).unwrap();
+        game.move_piece(notation("c2", "c4")).unwrap();
+
+        assert_eq!(game.en_passant(), Some(&ChessNotationPosition::new('c', 3).to_position()));
+        assert!(game.generate_legal_plays().contains(&notation("d4", "c3")));
+        assert_eq!(game.move_piece(notation("d4", "c3")), Ok(&GameState::InProgress));
+
+        let board = game.board();
+        assert!(board.get_tile(&ChessNotationPosition::new('c', 4).to_position()).get_piece().is_none());
+        assert_eq!(game.get_values(), (38, 39));
+    }
+
+    #[test]
+    fn test_en_passant_expires_after_one_move(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(notation("e2", "e4")).unwrap();
+        game.move_piece(notation("a7", "a6")).unwrap();
+        game.move_piece(notation("e4", "e5")).unwrap();
+        game.move_piece(notation("d7", "d5")).unwrap();
+        game.move_piece(notation("h2", "h3")).unwrap();
+        game.move_piece(notation("h7", "h6")).unwrap();
+
+        assert_eq!(game.en_passant(), None);
+        assert_eq!(game.move_piece(notation("e5", "d6")), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_en_passant_cannot_expose_the_king_along_the_rank(){
+        let mut game = game_with_pieces(&[
+            ("a5", Color::White, PieceType::King),
+            ("b5", Color::White, PieceType::Pawn),
+            ("c7", Color::Black, PieceType::Pawn),
+            ("h5", Color::Black, PieceType::Rook),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::Black);
+
+        game.move_piece(notation("c7", "c5")).unwrap();
+
+        assert!(!game.generate_legal_plays().contains(&notation("b5", "c6")));
+        assert_eq!(game.move_piece(notation("b5", "c6")), Err(ChessError::LeavesKingInCheck));
+    }
+
+    #[test]
+    fn test_en_passant_can_capture_a_checking_pawn(){
+        let mut game = game_with_pieces(&[
+            ("d4", Color::White, PieceType::King),
+            ("d5", Color::White, PieceType::Pawn),
+            ("e7", Color::Black, PieceType::Pawn),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::Black);
+
+        game.move_piece(notation("e7", "e5")).unwrap();
+
+        assert_eq!(game.state(), &GameState::Check(Color::White));
+        assert_eq!(game.move_piece(notation("d5", "e6")), Ok(&GameState::InProgress));
+        let board = game.board();
+        assert!(board.get_tile(&ChessNotationPosition::new('e', 5).to_position()).get_piece().is_none());
+    }
+
+    #[test]
+    fn test_en_passant_revealing_a_diagonal_is_illegal(){
+        let mut game = game_with_pieces(&[
+            ("b2", Color::White, PieceType::King),
+            ("e5", Color::White, PieceType::Pawn),
+            ("d7", Color::Black, PieceType::Pawn),
+            ("f6", Color::Black, PieceType::Bishop),
+            ("h8", Color::Black, PieceType::King),
+        ], Color::Black);
+
+        game.move_piece(notation("d7", "d5")).unwrap();
+
+        assert_eq!(game.move_piece(notation("e5", "d6")), Err(ChessError::LeavesKingInCheck));
+    }
+
+    #[test]
+    fn test_classic_game_starts_with_every_castling_right(){
+        let game = ClassicGame::new();
+
+        assert_eq!(game.castling_rights(), &CastlingRights::all());
+    }
+
+    #[test]
+    fn test_cannot_castle_after_king_moves_back(){
+        let mut game = game_with_pieces(&[
+            ("e1", Color::White, PieceType::King),
+            ("h1", Color::White, PieceType::Rook),
+            ("a1", Color::White, PieceType::Rook),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::White);
+
+        game.move_piece(notation("e1", "f1")).unwrap();
+        game.move_piece(notation("e8", "d8")).unwrap();
+        game.move_piece(notation("f1", "e1")).unwrap();
+        game.move_piece(notation("d8", "e8")).unwrap();
+
+        assert_eq!(game.castling_rights().to_string(), "-");
+        assert_eq!(game.move_piece(notation("e1", "g1")), Err(ChessError::IllegalMove));
+        assert_eq!(game.move_piece(notation("e1", "c1")), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_cannot_castle_with_a_rook_that_moved_back(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(notation("g1", "f3")).unwrap();
+        game.move_piece(notation("a7", "a6")).unwrap();
+        game.move_piece(notation("e2", "e3")).unwrap();
+        game.move_piece(notation("a6", "a5")).unwrap();
+        game.move_piece(notation("f1", "e2")).unwrap();
+        game.move_piece(notation("a5", "a4")).unwrap();
+        game.move_piece(notation("h1", "g1")).unwrap();
+        game.move_piece(notation("a8", "a5")).unwrap();
+        game.move_piece(notation("g1", "h1")).unwrap();
+        game.move_piece(notation("a5", "a8")).unwrap();
+
+        assert_eq!(game.castling_rights().to_string(), "Qk");
+        assert_eq!(game.move_piece(notation("e1", "g1")), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_capturing_a_rook_on_its_square_removes_the_right(){
+        let mut game = game_with_pieces(&[
+            ("e1", Color::White, PieceType::King),
+            ("h1", Color::White, PieceType::Rook),
+            ("e8", Color::Black, PieceType::King),
+            ("h8", Color::Black, PieceType::Rook),
+            ("a8", Color::Black, PieceType::Rook),
+        ], Color::White);
+
+        assert_eq!(game.castling_rights().to_string(), "Kkq");
+
+        game.move_piece(notation("h1", "h8")).unwrap();
+
+        assert_eq!(game.castling_rights().to_string(), "q");
+    }
+
+    #[test]
+    fn test_cannot_castle_out_of_check(){
+        let mut game = game_with_pieces(&[
+            ("e1", Color::White, PieceType::King),
+            ("h1", Color::White, PieceType::Rook),
+            ("e8", Color::Black, PieceType::King),
+            ("e5", Color::Black, PieceType::Rook),
+        ], Color::White);
+
+        assert_eq!(game.move_piece(notation("e1", "g1")), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_cannot_castle_queen_side_through_a_knight(){
+        let mut game = game_with_pieces(&[
+            ("e1", Color::White, PieceType::King),
+            ("a1", Color::White, PieceType::Rook),
+            ("b1", Color::White, PieceType::Knight),
+            ("e8", Color::Black, PieceType::King),
+        ], Color::White);
+
+        assert_eq!(game.move_piece(notation("e1", "c1")), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_legal_moves_from_a_square(){
+        let game = ClassicGame::new();
+
+        let knight = game.legal_moves_from(&ChessNotationPosition::new('g', 1).to_position());
+
+        assert_eq!(knight.len(), 2);
+        assert!(knight.contains(&notation("g1", "f3")));
+        assert!(knight.contains(&notation("g1", "h3")));
+        assert!(game.legal_moves_from(&ChessNotationPosition::new('e', 4).to_position()).is_empty());
+        assert!(game.legal_moves_from(&ChessNotationPosition::new('g', 8).to_position()).is_empty());
+        assert_eq!(game.legal_moves().len(), 20);
+    }
+
+    #[test]
+    fn test_legal_captures_include_en_passant(){
+        let game = Game::from_fen("4k3/8/8/n2pP3/8/8/8/R3K3 w - d6 0 2").unwrap();
+
+        let captures = game.legal_captures();
+
+        assert_eq!(captures.len(), 2);
+        assert!(captures.contains(&notation("e5", "d6")));
+        assert!(captures.contains(&notation("a1", "a5")));
+    }
+
+    #[test]
+    fn test_chess960_castle_onto_the_own_rook_is_no_capture(){
+        let game = Chess960Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let castle = notation("e1", "h1");
+
+        assert!(game.legal_moves().contains(&castle));
+        assert!(game.legal_captures().is_empty());
+        assert_eq!(game.captured_piece(&castle), None);
+    }
+
+    #[test]
+    fn test_no_legal_moves_once_the_game_is_over(){
+        let game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(game.state(), &GameState::Stalemate);
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn test_pinned_piece_only_moves_along_the_pin(){
+        let mut game = Game::from_fen("4k3/7p/8/1b6/8/3B4/8/5K2 w - - 0 1").unwrap();
+        let bishop = game.legal_moves_from(&ChessNotationPosition::new('d', 3).to_position());
+
+        assert_eq!(bishop.len(), 3);
+        assert!(bishop.contains(&notation("d3", "b5")));
+        assert!(bishop.contains(&notation("d3", "e2")));
+        assert_eq!(game.move_piece(notation("d3", "e4")), Err(ChessError::LeavesKingInCheck));
+    }
+
+    #[test]
+    fn test_only_the_king_moves_out_of_double_check(){
+        let game = Game::from_fen("4k3/8/8/8/8/8/3n4/R3rK2 w - - 0 1").unwrap();
+        let moves = game.legal_moves();
+
+        assert!(moves.iter().all(|movement| movement.from() == &ChessNotationPosition::new('f', 1).to_position()));
+        assert!(moves.contains(&notation("f1", "e1")));
+        assert!(!moves.contains(&notation("f1", "g1")));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::chess::{bitboard::{attacks, Bitboard, position::Position}, color::Color, movement::{absolute_position::AbsolutePosition, Movement}, piece::PieceType};
+
+use super::{castling::{Castle, CastleSide}, history::MoveRecord, Game};
+
+
+/// Plays `movement` on the bitboards without checking that it is legal
+///
+/// ### Arguments
+///
+/// * `position` - The position the movement is played on
+/// * `movement` - The movement, a pawn moving diagonally onto `en_passant` captures en passant
+/// * `en_passant` - The tile a pawn may capture en passant on
+/// * `castle` - The castle the movement stands for, which moves the rook as well
+///
+/// ### Returns
+///
+/// Returns the type and tile of the captured piece, `None` if nothing was captured
+pub(super) fn apply_movement(position: &mut Position, movement: &Movement, en_passant: Option<AbsolutePosition>, castle: Option<Castle>) -> Option<(PieceType, AbsolutePosition)>{
+    if let Some(castle) = castle{
+        let (color, _) = position.remove_piece(&castle.king_from())?;
+
+        position.remove_piece(&castle.rook_from());
+        position.set_piece(&castle.king_to(), color, PieceType::King);
+        position.set_piece(&castle.rook_to(), color, PieceType::Rook);
+
+        return None;
+    }
+
+    let (color, piece_type) = position.remove_piece(movement.from())?;
+    let from = movement.from();
+    let to = movement.to();
+
+    let mut captured = position.remove_piece(to).map(|(_, captured)| (captured, *to));
+
+    if piece_type == PieceType::Pawn && from.file != to.file && en_passant == Some(*to){
+        let square = AbsolutePosition::new(to.file, from.rank);
+
+        captured = position.remove_piece(&square).map(|(_, captured)| (captured, square));
+    }
+
+    position.set_piece(to, color, movement.promotion().unwrap_or(piece_type));
+
+    captured
+}
+
+/// Returns the tile a pawn skips with a double step, which an opposing pawn may then capture en passant
+pub(super) fn en_passant_square(piece_type: PieceType, movement: &Movement) -> Option<AbsolutePosition>{
+    match piece_type == PieceType::Pawn && movement.from().rank.abs_diff(movement.to().rank) == 2{
+        true => Some(AbsolutePosition::new(movement.from().file, (movement.from().rank + movement.to().rank) / 2)),
+        false => None,
+    }
+}
+
+/// Takes `record` back on the bitboards, the opposite of `apply_movement`
+///
+/// ### Arguments
+///
+/// * `position` - The position right after the recorded move
+/// * `record` - The move to take back
+/// * `color` - The color of the player who made the move
+pub(super) fn revert_movement(position: &mut Position, record: &MoveRecord, color: Color){
+    let movement = record.movement;
+
+    if let Some(castle) = record.castle{
+        position.remove_piece(&castle.king_to());
+        position.remove_piece(&castle.rook_to());
+        position.set_piece(&castle.king_from(), color, PieceType::King);
+        position.set_piece(&castle.rook_from(), color, PieceType::Rook);
+
+        return;
+    }
+
+    position.remove_piece(movement.to());
+
+    if let Some((piece_type, square)) = record.captured{
+        position.set_piece(&square, color.opposite(), piece_type);
+    }
+
+    position.set_piece(movement.from(), color, record.piece);
+}
+
+/// What checks and pins allow the player to move, worked out once per position
+pub(super) struct Restrictions{
+    king: Option<AbsolutePosition>,
+    /// The tiles a piece other than the king has to move to, the checker and the tiles in between
+    check_mask: Bitboard,
+    /// The pieces that may only move along the line between their king and the piece pinning them
+    pinned: Bitboard,
+}
+
+impl Game{
+    /// Returns the tiles the piece on `from` can reach, ignoring whether its own king is left in check
+    pub(super) fn pseudo_legal_targets(&self, from: &AbsolutePosition) -> Bitboard{
+        let (color, piece_type) = match self.position.piece_at(from){
+            Some(piece) => piece,
+            None => return Bitboard::EMPTY,
+        };
+
+        let own = self.position.pieces_of(&color);
+
+        match piece_type{
+            PieceType::Pawn => self.pawn_targets(from, &color),
+            PieceType::King => (self.position.attacks_from(from) & !own) | self.castle_targets(&color),
+            _ => self.position.attacks_from(from) & !own,
+        }
+    }
+
+    fn pawn_targets(&self, from: &AbsolutePosition, color: &Color) -> Bitboard{
+        let (direction, starting_rank) = match color{
+            Color::White => (1, 1),
+            Color::Black => (-1, 6),
+        };
+
+        let occupied = self.position.occupied();
+        let opponent = self.position.pieces_of(&color.opposite());
+        let mut targets = Bitboard::EMPTY;
+
+        let single = AbsolutePosition::new(from.file, (from.rank as i8 + direction) as usize);
+
+        if !occupied.contains(&single){
+            targets |= Bitboard::from_square(&single);
+
+            let double = AbsolutePosition::new(from.file, (from.rank as i8 + 2 * direction) as usize);
+
+            if from.rank == starting_rank && !occupied.contains(&double){
+                targets |= Bitboard::from_square(&double);
+            }
+        }
+
+        let en_passant = match self.en_passant{
+            Some(square) => Bitboard::from_square(&square),
+            None => Bitboard::EMPTY,
+        };
+
+        targets | (self.position.attacks_from(from) & (opponent | en_passant))
+    }
+
+    /// Returns the tiles the king of `color` moves to in order to castle, see `castle_movement`
+    ///
+    /// A castle needs the right, the rook on its tile, nothing but the king and the rook on the
+    /// tiles either of them crosses or lands on, and no attack on the tiles the king starts from,
+    /// crosses or lands on
+    fn castle_targets(&self, color: &Color) -> Bitboard{
+        let mut targets = Bitboard::EMPTY;
+
+        for side in [CastleSide::KingSide, CastleSide::QueenSide]{
+            if let Some(castle) = self.available_castle(color, &side){
+                targets |= Bitboard::from_square(self.castle_movement(&castle).to());
+            }
+        }
+
+        targets
+    }
+
+    /// Returns the castle of `color` to `side` if it can be played right now
+    fn available_castle(&self, color: &Color, side: &CastleSide) -> Option<Castle>{
+        let rank = match color{
+            Color::White => 0,
+            Color::Black => 7,
+        };
+
+        let king = self.position.king(color).filter(|king| king.rank == rank)?;
+        let rook_file = self.castling_rights.rook_file(color, side)?;
+        let castle = Castle{side: *side, rank, king_file: king.file, rook_file};
+
+        let is_on_its_side = match side{
+            CastleSide::KingSide => rook_file > king.file,
+            CastleSide::QueenSide => rook_file < king.file,
+        };
+
+        if !is_on_its_side || self.position.piece_at(&castle.rook_from()) != Some((*color, PieceType::Rook)){
+            return None;
+        }
+
+        let castling_pieces = Bitboard::from_square(&castle.king_from()) | Bitboard::from_square(&castle.rook_from());
+        let king_path = attacks::between(&castle.king_from(), &castle.king_to()) | Bitboard::from_square(&castle.king_to());
+        let rook_path = attacks::between(&castle.rook_from(), &castle.rook_to()) | Bitboard::from_square(&castle.rook_to());
+
+        if !(self.position.occupied() & (king_path | rook_path) & !castling_pieces).is_empty(){
+            return None;
+        }
+
+        let opponent = color.opposite();
+        let crosses_attack = (attacks::between(&castle.king_from(), &castle.king_to()) | Bitboard::from_square(&castle.king_from())).into_iter()
+            .any(|square| self.position.is_attacked(&square, &opponent));
+
+        // The rook may have been shielding the tile the king lands on, so that one is checked with the pieces moved
+        let occupied_after = (self.position.occupied() ^ castling_pieces) | Bitboard::from_square(&castle.king_to()) | Bitboard::from_square(&castle.rook_to());
+        let lands_in_check = !self.position.attackers_with(&castle.king_to(), &opponent, occupied_after).is_empty();
+
+        match crosses_attack || lands_in_check{
+            true => None,
+            false => Some(castle),
+        }
+    }
+
+    /// Returns the movement that plays `castle`: the king onto its own rook in Chess960, where the
+    /// king may not move at all, and the king moving two files in a classic game
+    pub(super) fn castle_movement(&self, castle: &Castle) -> Movement{
+        match self.chess960{
+            true => Movement::new(castle.king_from(), castle.rook_from()),
+            false => Movement::new(castle.king_from(), castle.king_to()),
+        }
+    }
+
+    /// Returns the castle `movement` stands for, `None` when it is not a castle the player still has the right to
+    pub(super) fn castle(&self, movement: &Movement) -> Option<Castle>{
+        let (color, piece_type) = self.position.piece_at(movement.from())?;
+        let from = movement.from();
+        let to = movement.to();
+
+        if piece_type != PieceType::King || from.rank != to.rank{
+            return None;
+        }
+
+        let side = match to.file > from.file{
+            true => CastleSide::KingSide,
+            false => CastleSide::QueenSide,
+        };
+
+        let rook_file = self.castling_rights.rook_file(&color, &side)?;
+
+        let is_castle = match self.chess960{
+            true => to.file == rook_file,
+            false => from.file.abs_diff(to.file) == 2,
+        };
+
+        if !is_castle{
+            return None;
+        }
+
+        Some(Castle{side, rank: from.rank, king_file: from.file, rook_file})
+    }
+
+    /// Works out the checks and pins of the player to move
+    pub(super) fn restrictions(&self) -> Restrictions{
+        let color = *self.turn();
+
+        let king = match self.position.king(&color){
+            Some(king) => king,
+            None => return Restrictions{king: None, check_mask: Bitboard::FULL, pinned: Bitboard::EMPTY},
+        };
+
+        let opponent = color.opposite();
+        let checkers = self.position.attackers(&king, &opponent);
+
+        let check_mask = match checkers.first(){
+            None => Bitboard::FULL,
+            Some(checker) if checkers.count() == 1 => checkers | attacks::between(&king, &checker),
+            Some(_) => Bitboard::EMPTY,
+        };
+
+        let theirs = self.position.pieces_of(&opponent);
+        let straight = self.position.pieces(&opponent, PieceType::Rook) | self.position.pieces(&opponent, PieceType::Queen);
+        let diagonal = self.position.pieces(&opponent, PieceType::Bishop) | self.position.pieces(&opponent, PieceType::Queen);
+
+        let snipers = (attacks::rook_attacks(&king, theirs) & straight) | (attacks::bishop_attacks(&king, theirs) & diagonal);
+        let mut pinned = Bitboard::EMPTY;
+
+        for sniper in snipers{
+            let blockers = attacks::between(&king, &sniper) & self.position.occupied();
+
+            if blockers.count() == 1{
+                pinned |= blockers & self.position.pieces_of(&color);
+            }
+        }
+
+        Restrictions{king: Some(king), check_mask, pinned}
+    }
+
+    /// Returns the tiles the piece on `from` can legally move to
+    pub(super) fn legal_targets(&self, from: &AbsolutePosition, restrictions: &Restrictions) -> Bitboard{
+        let targets = self.pseudo_legal_targets(from);
+
+        let (color, piece_type) = match self.position.piece_at(from){
+            Some(piece) => piece,
+            None => return Bitboard::EMPTY,
+        };
+
+        if piece_type == PieceType::King{
+            let occupied = self.position.occupied() ^ Bitboard::from_square(from);
+            let castles = self.castle_targets(&color);
+
+            let steps : Bitboard = (targets & !castles).into_iter()
+                .filter(|to| self.position.attackers_with(to, &color.opposite(), occupied).is_empty())
+                .collect();
+
+            return steps | castles;
+        }
+
+        let mut legal = targets & restrictions.check_mask;
+
+        if let Some(king) = restrictions.king{
+            if restrictions.pinned.contains(from){
+                legal &= attacks::line(&king, from);
+            }
+        }
+
+        if let Some(square) = self.en_passant{
+            if piece_type == PieceType::Pawn && targets.contains(&square){
+                legal &= !Bitboard::from_square(&square);
+
+                if !self.en_passant_exposes_king(&Movement::new(*from, square)){
+                    legal |= Bitboard::from_square(&square);
+                }
+            }
+        }
+
+        legal
+    }
+
+    /// Whether playing `movement` would leave the king of the player to move attacked
+    pub(super) fn leaves_king_in_check(&self, movement: &Movement) -> bool{
+        !self.legal_targets(movement.from(), &self.restrictions()).contains(movement.to())
+    }
+
+    /// Whether capturing en passant with `movement` leaves the king attacked
+    ///
+    /// Two pawns leave the same rank at once, which neither the check mask nor the pins describe,
+    /// so the capture is tried on a copy of the bitboards.
+    fn en_passant_exposes_king(&self, movement: &Movement) -> bool{
+        let mut position = self.position;
+
+        apply_movement(&mut position, movement, self.en_passant, None);
+
+        position.in_check(self.turn())
+    }
+
+    /// Returns every legal movement of the player to move, one per promotion piece for pawns reaching the last rank
+    pub(crate) fn generate_legal_plays(&self) -> Vec<Movement>{
+        self.generate_legal_plays_from(self.position.pieces_of(self.turn()))
+    }
+
+    /// Returns the legal movements of the pieces of `piece_type` belonging to the player to move
+    pub(super) fn generate_legal_plays_for_piece_type(&self, piece_type: PieceType) -> Vec<Movement>{
+        self.generate_legal_plays_from(self.position.pieces(self.turn(), piece_type))
+    }
+
+    fn generate_legal_plays_from(&self, pieces: Bitboard) -> Vec<Movement>{
+        let restrictions = self.restrictions();
+        let mut legal_moves = Vec::new();
+
+        for from in pieces{
+            for to in self.legal_targets(&from, &restrictions){
+                legal_moves.extend(self.with_promotions(Movement::new(from, to)));
+            }
+        }
+
+        legal_moves
+    }
+
+    /// Returns the type of the piece `movement` captures, a pawn for an en passant capture
+    ///
+    /// A Chess960 castle moves the king onto its own rook, which captures nothing.
+    pub(crate) fn captured_piece(&self, movement: &Movement) -> Option<PieceType>{
+        if self.is_castle(movement){
+            return None;
+        }
+
+        if let Some((color, piece_type)) = self.position.piece_at(movement.to()){
+            return match color == self.turn.opposite(){
+                true => Some(piece_type),
+                false => None,
+            };
+        }
+
+        let is_pawn = self.position.pieces_of_type(PieceType::Pawn).contains(movement.from());
+
+        match is_pawn && self.en_passant == Some(*movement.to()) && movement.from().file != movement.to().file{
+            true => Some(PieceType::Pawn),
+            false => None,
+        }
+    }
+
+    /// Whether `movement` castles, see `castle_movement`
+    pub(super) fn is_castle(&self, movement: &Movement) -> bool{
+        self.castle(movement).is_some()
+    }
+}
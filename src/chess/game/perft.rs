@@ -0,0 +1,132 @@
+use crate::chess::movement::Movement;
+
+use super::Game;
+
+impl Game{
+    /// Counts the leaf nodes of the legal move tree, the standard way to check a move generator
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `depth` - How many halfmoves deep to search, 0 counts the current position only
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns the number of positions reached after exactly `depth` halfmoves
+    /// 
+    /// ## Examples
+    /// 
+    /// ```
+    /// use better_chess::ClassicGame;
+    /// 
+    /// let game = ClassicGame::new();
+    /// 
+    /// assert_eq!(game.perft(2), 400);
+    /// ```
+    pub fn perft(&self, depth: u32) -> u64{
+        let mut game = self.clone();
+
+        game.perft_nodes(depth)
+    }
+
+    /// Splits `perft` by the first movement, to find which branch disagrees with another generator
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns each legal movement with the number of leaf nodes below it, `depth` counts the movement itself
+    pub fn divide(&self, depth: u32) -> Vec<(Movement, u64)>{
+        if depth == 0{
+            return Vec::new();
+        }
+
+        let mut game = self.clone();
+
+        game.generate_legal_plays().into_iter()
+            .map(|movement| {
+                game.make_move(movement).expect("A generated move is legal");
+                let nodes = game.perft_nodes(depth - 1);
+                game.unmake_move();
+
+                (movement, nodes)
+            })
+            .collect()
+    }
+
+    fn perft_nodes(&mut self, depth: u32) -> u64{
+        if depth == 0{
+            return 1;
+        }
+
+        let movements = self.generate_legal_plays();
+
+        if depth == 1{
+            return movements.len() as u64;
+        }
+
+        let mut nodes = 0;
+
+        for movement in movements{
+            self.make_move(movement).expect("A generated move is legal");
+            nodes += self.perft_nodes(depth - 1);
+            self.unmake_move();
+        }
+
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::fen::STARTING_FEN;
+
+    use super::*;
+
+    /// Published node counts from the Chess Programming Wiki, by depth starting at 1
+    const POSITIONS : [(&str, &[u64]); 6] = [
+        (STARTING_FEN, &[20, 400, 8902, 197281, 4865609]),
+        ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", &[48, 2039, 97862, 4085603]),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", &[14, 191, 2812, 43238, 674624]),
+        ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", &[6, 264, 9467, 422333]),
+        ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", &[44, 1486, 62379, 2103487]),
+        ("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", &[46, 2079, 89890, 3894594]),
+    ];
+
+    fn assert_perft(max_depth: usize){
+        for (fen, counts) in POSITIONS{
+            let game = Game::from_fen(fen).unwrap();
+
+            for (depth, count) in counts.iter().enumerate().take(max_depth){
+                assert_eq!(game.perft(depth as u32 + 1), *count, "perft({}) of {}", depth + 1, fen);
+            }
+        }
+    }
+
+    #[test]
+    fn test_perft_of_standard_positions(){
+        assert_perft(3);
+    }
+
+    #[test]
+    #[ignore = "slow, run with cargo test --release -- --ignored"]
+    fn test_deep_perft_of_standard_positions(){
+        assert_perft(5);
+    }
+
+    #[test]
+    fn test_divide_adds_up_to_perft(){
+        let game = Game::from_fen(POSITIONS[1].0).unwrap();
+        let divide = game.divide(2);
+
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), game.perft(2));
+    }
+
+    #[test]
+    fn test_perft_leaves_the_game_untouched(){
+        let game = Game::from_fen(POSITIONS[3].0).unwrap();
+
+        game.perft(2);
+
+        assert_eq!(game.to_fen(), POSITIONS[3].0);
+        assert!(game.history().is_empty());
+    }
+}
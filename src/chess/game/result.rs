@@ -0,0 +1,373 @@
+use std::fmt::Display;
+
+use crate::chess::{color::Color, error::ChessError};
+
+use super::{draw::DrawReason, Game, GameState};
+
+
+/// Why a game ended, or why it has no result
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Termination{
+    Checkmate,
+    Resignation,
+    /// A player ran out of time
+    TimeForfeit,
+    /// The game was left before it could end
+    Abandonment,
+    Stalemate,
+    /// The players agreed to a draw
+    Agreement,
+    /// A draw by the rules other than stalemate, such as repetition or insufficient material
+    Rule(DrawReason),
+    /// The game is still going
+    Unterminated,
+    /// The game was decided, but its record does not say how
+    Unknown,
+}
+
+impl Termination{
+    /// Returns the value of the PGN `Termination` tag, which only names the unusual ends
+    ///
+    /// ### Returns
+    ///
+    /// * `"normal"` - If the game ended on the board, by resignation or by agreement
+    /// * `"time forfeit"` - If a player ran out of time
+    /// * `"abandoned"` - If the game was abandoned
+    /// * `"unterminated"` - If the game is still going
+    pub fn pgn_tag(&self) -> &'static str{
+        match self{
+            Termination::TimeForfeit => "time forfeit",
+            Termination::Abandonment => "abandoned",
+            Termination::Unterminated => "unterminated",
+            _ => "normal",
+        }
+    }
+}
+
+impl Display for Termination{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            Termination::Checkmate => write!(f, "checkmate"),
+            Termination::Resignation => write!(f, "resignation"),
+            Termination::TimeForfeit => write!(f, "time forfeit"),
+            Termination::Abandonment => write!(f, "abandonment"),
+            Termination::Stalemate => write!(f, "stalemate"),
+            Termination::Agreement => write!(f, "agreement"),
+            Termination::Rule(reason) => write!(f, "{}", reason),
+            Termination::Unterminated => write!(f, "no termination"),
+            Termination::Unknown => write!(f, "unknown means"),
+        }
+    }
+}
+
+/// The result of a game as written in PGN, with how the game ended
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameResult{
+    /// `1-0` or `0-1`, the color is the winner
+    Win(Color, Termination),
+    /// `1/2-1/2`
+    Draw(Termination),
+    /// `*`, the game is still going or was abandoned
+    Unfinished(Termination),
+}
+
+impl GameResult{
+    /// Returns the player who won, `None` for draws and unfinished games
+    pub fn winner(&self) -> Option<Color>{
+        match self{
+            GameResult::Win(color, _) => Some(*color),
+            _ => None,
+        }
+    }
+
+    pub fn termination(&self) -> Termination{
+        match self{
+            GameResult::Win(_, termination) | GameResult::Draw(termination) | GameResult::Unfinished(termination) => *termination,
+        }
+    }
+}
+
+/// Writes the result as the PGN `Result` tag does: `1-0`, `0-1`, `1/2-1/2` or `*`
+impl Display for GameResult{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            GameResult::Win(Color::White, _) => write!(f, "1-0"),
+            GameResult::Win(Color::Black, _) => write!(f, "0-1"),
+            GameResult::Draw(_) => write!(f, "1/2-1/2"),
+            GameResult::Unfinished(_) => write!(f, "*"),
+        }
+    }
+}
+
+impl Game{
+    /// Returns the result of the game, `GameResult::Unfinished` while it is going
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use better_chess::{ClassicGame, Color, GameResult, Termination};
+    ///
+    /// let mut game = ClassicGame::new();
+    ///
+    /// assert_eq!(game.result(), GameResult::Unfinished(Termination::Unterminated));
+    ///
+    /// game.resign(Color::White).unwrap();
+    ///
+    /// assert_eq!(game.result(), GameResult::Win(Color::Black, Termination::Resignation));
+    /// assert_eq!(game.result().to_string(), "0-1");
+    /// ```
+    pub fn result(&self) -> GameResult{
+        match self.state(){
+            GameState::Checkmate(color) => GameResult::Win(color.opposite(), Termination::Checkmate),
+            GameState::Resigned(color) => GameResult::Win(color.opposite(), Termination::Resignation),
+            GameState::Lost(color) => GameResult::Win(color.opposite(), Termination::Unknown),
+            GameState::Timeout(color) => GameResult::Win(color.opposite(), Termination::TimeForfeit),
+            GameState::Stalemate => GameResult::Draw(Termination::Stalemate),
+            GameState::Draw(DrawReason::Agreement) => GameResult::Draw(Termination::Agreement),
+            GameState::Draw(DrawReason::TimeoutVsInsufficientMaterial) => GameResult::Draw(Termination::TimeForfeit),
+            GameState::Draw(reason) => GameResult::Draw(Termination::Rule(*reason)),
+            GameState::Abandoned => GameResult::Unfinished(Termination::Abandonment),
+            GameState::Check(_) | GameState::InProgress | GameState::Promoting => GameResult::Unfinished(Termination::Unterminated),
+        }
+    }
+
+    /// Ends the game with `color` resigning, which either player may do at any time
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing a reference to the new game state, or `ChessError::GameOver` if the game already ended
+    pub fn resign(&mut self, color: Color) -> Result<&GameState, ChessError>{
+        self.end(GameState::Resigned(color))
+    }
+
+    /// Ends the game without a result, such as when a player leaves or the connection is lost
+    pub fn abandon(&mut self) -> Result<&GameState, ChessError>{
+        self.end(GameState::Abandoned)
+    }
+
+    /// Offers a draw on behalf of the player to move
+    ///
+    /// The offer stands until the opponent accepts or declines it, or plays a move, which
+    /// declines it too.
+    ///
+    /// ### Returns
+    ///
+    /// Returns an empty Result, or `ChessError::GameOver` if the game already ended
+    pub fn offer_draw(&mut self) -> Result<(), ChessError>{
+        if self.is_over(){
+            return Err(ChessError::GameOver);
+        }
+
+        self.draw_offer = Some(*self.turn());
+
+        Ok(())
+    }
+
+    /// Returns the player whose draw offer is waiting for an answer, if any
+    pub fn draw_offer(&self) -> Option<Color>{
+        self.draw_offer
+    }
+
+    /// Ends the game in a draw by agreement, the player to move answering the opponent's offer
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing a reference to the new game state, or `ChessError::NoDrawOffer` if the opponent offered no draw
+    pub fn accept_draw(&mut self) -> Result<&GameState, ChessError>{
+        if !self.is_draw_offered_to_turn(){
+            return Err(ChessError::NoDrawOffer);
+        }
+
+        self.end(GameState::Draw(DrawReason::Agreement))
+    }
+
+    /// Turns down the opponent's draw offer on behalf of the player to move, the game goes on
+    ///
+    /// ### Returns
+    ///
+    /// Returns an empty Result, or `ChessError::NoDrawOffer` if the opponent offered no draw
+    pub fn decline_draw(&mut self) -> Result<(), ChessError>{
+        if !self.is_draw_offered_to_turn(){
+            return Err(ChessError::NoDrawOffer);
+        }
+
+        self.draw_offer = None;
+
+        Ok(())
+    }
+
+    /// Whether the opponent of the player to move offered a draw, a player cannot answer their own offer
+    fn is_draw_offered_to_turn(&self) -> bool{
+        self.draw_offer == Some(self.turn.opposite())
+    }
+
+    /// Ends a game read from PGN with its `Result` tag, when the moves alone do not end it
+    ///
+    /// A decisive result is lost by unknown means, and a draw is taken as an agreement, unless the
+    /// `Termination` tag says the game was lost on time or abandoned.
+    pub(crate) fn record_result(&mut self, result: &str, termination: Option<&str>){
+        if self.is_over(){
+            return;
+        }
+
+        let state = match (result, termination){
+            ("*", Some("abandoned")) => GameState::Abandoned,
+            ("1-0", Some("time forfeit")) => GameState::Timeout(Color::Black),
+            ("0-1", Some("time forfeit")) => GameState::Timeout(Color::White),
+            ("1-0", _) => GameState::Lost(Color::Black),
+            ("0-1", _) => GameState::Lost(Color::White),
+            ("1/2-1/2", _) => GameState::Draw(DrawReason::Agreement),
+            _ => return,
+        };
+
+        let _ = self.end(state);
+    }
+
+    /// Whether the game ended by resignation, agreement, a claimed draw, abandonment, on time or by means
+    /// a PGN record does not tell, rather than by a move
+    /// 
+    /// A threefold repetition or fifty move draw only ends the game once a player claims it, so taking
+    /// back a move would let the game go on after the claim as if it had never been made
+    pub fn ended_off_the_board(&self) -> bool{
+        match self.state{
+            GameState::Draw(reason) => reason.is_claimable() || matches!(reason, DrawReason::Agreement | DrawReason::TimeoutVsInsufficientMaterial),
+            _ => matches!(self.state, GameState::Resigned(_) | GameState::Lost(_) | GameState::Abandoned | GameState::Timeout(_)),
+        }
+    }
+
+    /// Ends the game in `state`, dropping the pending promotion and draw offer and stopping the clock
+    fn end(&mut self, state: GameState) -> Result<&GameState, ChessError>{
+        if self.is_over(){
+            return Err(ChessError::GameOver);
+        }
+
+        self.state = state;
+        self.pending_promotion = None;
+        self.draw_offer = None;
+
+        if let Some(clock) = &mut self.clock{
+            clock.stop();
+        }
+
+        Ok(&self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::game::classic::ClassicGame;
+
+    use super::*;
+
+    fn play(game: &mut Game, moves: &[&str]){
+        for san in moves{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_results_of_finished_games(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+
+        assert_eq!(game.result(), GameResult::Win(Color::Black, Termination::Checkmate));
+        assert_eq!(game.result().winner(), Some(Color::Black));
+
+        let game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert_eq!(game.result(), GameResult::Draw(Termination::Stalemate));
+        assert_eq!(game.result().to_string(), "1/2-1/2");
+
+        let game = Game::from_fen("8/8/4k3/8/8/3K4/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(game.result(), GameResult::Draw(Termination::Rule(DrawReason::InsufficientMaterial)));
+        assert_eq!(game.result().termination().pgn_tag(), "normal");
+    }
+
+    #[test]
+    fn test_resigned_game_takes_no_more_moves(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4"]);
+
+        assert_eq!(game.resign(Color::Black), Ok(&GameState::Resigned(Color::Black)));
+        assert_eq!(game.result(), GameResult::Win(Color::White, Termination::Resignation));
+        assert_eq!(game.result().to_string(), "1-0");
+        assert_eq!(game.move_piece("e7e5".parse().unwrap()), Err(ChessError::GameOver));
+        assert_eq!(game.resign(Color::White), Err(ChessError::GameOver));
+        assert_eq!(game.offer_draw(), Err(ChessError::GameOver));
+    }
+
+    #[test]
+    fn test_undo_keeps_a_result_reached_off_the_board(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4"]);
+        game.resign(Color::Black).unwrap();
+
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.go_to_ply(0), Err(ChessError::GameOver));
+        assert_eq!(game.ply(), 1);
+        assert_eq!(game.result(), GameResult::Win(Color::White, Termination::Resignation));
+
+        // A result on the board is taken back with the move that reached it
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+
+        assert!(game.undo().is_some());
+        assert!(!game.is_over());
+    }
+
+    #[test]
+    fn test_draw_offers(){
+        let mut game = ClassicGame::new();
+
+        assert_eq!(game.accept_draw(), Err(ChessError::NoDrawOffer));
+        assert_eq!(game.decline_draw(), Err(ChessError::NoDrawOffer));
+
+        // The offer stands after the player who made it moves, the opponent's move declines it
+        game.offer_draw().unwrap();
+        play(&mut game, &["e4"]);
+
+        assert_eq!(game.draw_offer(), Some(Color::White));
+
+        play(&mut game, &["e5"]);
+
+        assert_eq!(game.draw_offer(), None);
+
+        // Only the opponent answers, once the player who offered has moved
+        game.offer_draw().unwrap();
+
+        assert_eq!(game.accept_draw(), Err(ChessError::NoDrawOffer));
+        assert_eq!(game.decline_draw(), Err(ChessError::NoDrawOffer));
+
+        play(&mut game, &["Nf3"]);
+        game.decline_draw().unwrap();
+
+        assert_eq!(game.draw_offer(), None);
+        assert_eq!(game.state(), &GameState::InProgress);
+
+        game.offer_draw().unwrap();
+        play(&mut game, &["Nc6"]);
+
+        assert_eq!(game.accept_draw(), Ok(&GameState::Draw(DrawReason::Agreement)));
+        assert_eq!(game.result(), GameResult::Draw(Termination::Agreement));
+        assert_eq!(game.draw_offer(), None);
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn test_abandoned_game_has_no_result(){
+        let mut game = ClassicGame::new();
+
+        game.abandon().unwrap();
+
+        assert!(game.is_over());
+        assert_eq!(game.result(), GameResult::Unfinished(Termination::Abandonment));
+        assert_eq!(game.result().to_string(), "*");
+        assert_eq!(game.result().termination().pgn_tag(), "abandoned");
+    }
+}
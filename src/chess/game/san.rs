@@ -0,0 +1,394 @@
+use crate::chess::{error::ChessError, movement::{absolute_position::AbsolutePosition, Movement}, piece::PieceType};
+
+use super::{castling::{Castle, CastleSide, CastlingRights}, movegen, Game, GameState};
+
+impl Game{
+    /// Reads a move written in Standard Algebraic Notation for the player to move
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `san` - The move, such as `e4`, `Nbd7`, `exd5`, `R1a3`, `O-O-O` or `e8=Q+`
+    /// 
+    /// ### Returns
+    /// 
+    /// Returns a Result containing the legal movement the notation describes, otherwise the `ChessError` explaining why not
+    /// 
+    /// ## Examples
+    /// 
+    /// ```
+    /// use better_chess::{ClassicGame, ChessNotationPosition, Movement};
+    /// 
+    /// let game = ClassicGame::new();
+    /// 
+    /// let movement = game.parse_san("Nf3").unwrap();
+    /// 
+    /// assert_eq!(movement, Movement::new(ChessNotationPosition::new('g', 1).to_position(), ChessNotationPosition::new('f', 3).to_position()));
+    /// ```
+    pub fn parse_san(&self, san: &str) -> Result<Movement, ChessError>{
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        if let Some(side) = castle_side(san){
+            return self.castle_from_san(&side);
+        }
+
+        let (san, promotion) = match san.split_once('='){
+            Some((san, piece)) => (san, Some(parse_promotion(piece)?)),
+            None => match san.chars().last(){
+                Some(piece) if piece.is_ascii_uppercase() && san.len() > 2 => (&san[..san.len() - 1], Some(parse_promotion(&piece.to_string())?)),
+                _ => (san, None),
+            },
+        };
+
+        let mut chars : Vec<char> = san.chars().collect();
+
+        let piece_prefix = match chars.first(){
+            Some(letter) if letter.is_ascii_uppercase() => {
+                let prefix = *letter;
+                chars.remove(0);
+
+                match PieceType::from_prefix(prefix){
+                    Some(PieceType::Pawn) | None => return Err(ChessError::ParseError(format!("Invalid piece: {}", prefix))),
+                    Some(_) => Some(prefix),
+                }
+            },
+            _ => None,
+        };
+
+        if chars.len() < 2{
+            return Err(ChessError::ParseError(format!("Invalid move: {}", san)));
+        }
+
+        let destination = parse_square(chars[chars.len() - 2], chars[chars.len() - 1])
+            .ok_or_else(|| ChessError::ParseError(format!("Invalid destination: {}", san)))?;
+
+        let mut file_from = None;
+        let mut rank_from = None;
+        let mut is_capture = false;
+
+        for letter in &chars[..chars.len() - 2]{
+            match letter{
+                'x' if !is_capture => is_capture = true,
+                'a'..='h' if file_from.is_none() && !is_capture => file_from = Some(*letter as usize - 'a' as usize),
+                '1'..='8' if rank_from.is_none() && !is_capture => rank_from = Some(*letter as usize - '1' as usize),
+                _ => return Err(ChessError::ParseError(format!("Invalid move: {}", san))),
+            }
+        }
+
+        if is_capture && !self.is_capture(&destination, piece_prefix.is_none()){
+            return Err(ChessError::IllegalMove);
+        }
+
+        self.movement_from_chess_notation(destination, piece_prefix, rank_from, file_from, is_capture, promotion)
+    }
+
+    /// Returns every move played so far in Standard Algebraic Notation, in order
+    pub fn san_moves(&self) -> Vec<String>{
+        let mut replay = self.initial_game();
+
+        self.moves().iter().map(|movement| {
+            let san = replay.to_san(movement);
+            replay.move_piece(*movement).expect("Every recorded move is legal");
+            san
+        }).collect()
+    }
+
+    /// Writes a legal movement in Standard Algebraic Notation for the player to move
+    /// 
+    /// The piece letter, the disambiguation, the capture, the promotion and the check or
+    /// checkmate suffix are derived from the current position
+    pub fn to_san(&self, movement: &Movement) -> String{
+        let piece_type = match self.position.piece_at(movement.from()){
+            Some((_, piece_type)) => piece_type,
+            None => return format!("{}{}", movement.from(), movement.to()),
+        };
+
+        let mut san = String::new();
+
+        if self.is_castle(movement){
+            match movement.to().file > movement.from().file{
+                true => san.push_str("O-O"),
+                false => san.push_str("O-O-O"),
+            }
+        }else{
+            let is_pawn = piece_type == PieceType::Pawn;
+            let is_capture = self.is_capture(movement.to(), is_pawn);
+
+            if is_pawn{
+                if is_capture{
+                    san.push((b'a' + movement.from().file as u8) as char);
+                }
+            }else{
+                san.push(piece_type.prefix());
+                san.push_str(&self.disambiguation(piece_type, movement));
+            }
+
+            if is_capture{
+                san.push('x');
+            }
+
+            san.push_str(&movement.to().to_string());
+
+            if let Some(piece_type) = movement.promotion(){
+                san.push('=');
+                san.push(piece_type.prefix());
+            }
+        }
+
+        if let Some(suffix) = self.check_suffix(piece_type, movement){
+            san.push(suffix);
+        }
+
+        san
+    }
+
+    /// Returns `#` if `movement` checkmates, `+` if it checks, worked out on a copy of the bitboards
+    fn check_suffix(&self, piece_type: PieceType, movement: &Movement) -> Option<char>{
+        let mut position = self.position;
+        let opponent = self.turn.opposite();
+
+        movegen::apply_movement(&mut position, movement, self.en_passant, self.castle(movement));
+
+        if !position.in_check(&opponent){
+            return None;
+        }
+
+        // Only the tiles matter to the replies, castling never gets a king out of check
+        let reply = Game{
+            position,
+            turn: opponent,
+            history: Vec::new(),
+            undone: Vec::new(),
+            state: GameState::Check(opponent),
+            pending_promotion: None,
+            en_passant: movegen::en_passant_square(piece_type, movement),
+            castling_rights: CastlingRights::none(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            initial_fen: String::new(),
+            zobrist: 0,
+            chess960: self.chess960,
+            clock: None,
+            draw_offer: None,
+        };
+
+        match reply.generate_legal_plays().is_empty(){
+            true => Some('#'),
+            false => Some('+'),
+        }
+    }
+
+    fn castle_from_san(&self, side: &CastleSide) -> Result<Movement, ChessError>{
+        let king = match self.position.king(self.turn()){
+            Some(position) => position,
+            None => return Err(ChessError::IllegalMove),
+        };
+
+        let rook_file = self.castling_rights.rook_file(self.turn(), side).ok_or(ChessError::IllegalMove)?;
+        let castle = self.castle_movement(&Castle{side: *side, rank: king.rank, king_file: king.file, rook_file});
+
+        match self.generate_legal_plays_for_piece_type(PieceType::King).contains(&castle){
+            true => Ok(castle),
+            false => Err(ChessError::IllegalMove),
+        }
+    }
+
+    /// Whether moving to `destination` takes an opponent's piece, the own rook of a Chess960 castle is not taken
+    pub(super) fn is_capture(&self, destination: &AbsolutePosition, is_pawn: bool) -> bool{
+        self.position.pieces_of(&self.turn.opposite()).contains(destination) || (is_pawn && self.en_passant == Some(*destination))
+    }
+
+    /// The shortest origin hint that tells `movement` apart from other pieces of the same kind reaching the same tile
+    fn disambiguation(&self, piece_type: PieceType, movement: &Movement) -> String{
+        let others : Vec<AbsolutePosition> = self.generate_legal_plays_for_piece_type(piece_type)
+            .into_iter()
+            .filter(|other| other.to() == movement.to() && other.from() != movement.from())
+            .map(|other| *other.from())
+            .collect();
+
+        if others.is_empty(){
+            return String::new();
+        }
+
+        let from = movement.from().to_string();
+
+        if others.iter().all(|other| other.file != movement.from().file){
+            from[..1].to_string()
+        }else if others.iter().all(|other| other.rank != movement.from().rank){
+            from[1..].to_string()
+        }else{
+            from
+        }
+    }
+}
+
+fn castle_side(san: &str) -> Option<CastleSide>{
+    match san{
+        "O-O" | "0-0" => Some(CastleSide::KingSide),
+        "O-O-O" | "0-0-0" => Some(CastleSide::QueenSide),
+        _ => None,
+    }
+}
+
+fn parse_promotion(piece: &str) -> Result<PieceType, ChessError>{
+    let mut chars = piece.chars();
+
+    match (chars.next().and_then(PieceType::from_prefix), chars.next()){
+        (Some(piece_type), None) if piece_type.is_promotion() => Ok(piece_type),
+        _ => Err(ChessError::ParseError(format!("Invalid promotion: {}", piece))),
+    }
+}
+
+fn parse_square(file: char, rank: char) -> Option<AbsolutePosition>{
+    match (file, rank){
+        ('a'..='h', '1'..='8') => Some(AbsolutePosition::new(file as usize - 'a' as usize, rank as usize - '1' as usize)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use std::str::FromStr;
+
+    use crate::chess::{game::classic::ClassicGame, movement::chess_notation::ChessNotationPosition};
+
+    use super::*;
+
+    fn movement(from: &str, to: &str) -> Movement{
+        Movement::new(ChessNotationPosition::from_str(from).unwrap().to_position(), ChessNotationPosition::from_str(to).unwrap().to_position())
+    }
+
+    fn play(game: &mut Game, moves: &[&str]){
+        for san in moves{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parses_pawn_and_piece_moves(){
+        let game = ClassicGame::new();
+
+        assert_eq!(game.parse_san("e4"), Ok(movement("e2", "e4")));
+        assert_eq!(game.parse_san("Nc3"), Ok(movement("b1", "c3")));
+        assert_eq!(game.parse_san("Ke2"), Err(ChessError::IllegalMove));
+        assert_eq!(game.parse_san("Pe4"), Err(ChessError::ParseError(String::from("Invalid piece: P"))));
+        assert_eq!(game.parse_san("e9"), Err(ChessError::ParseError(String::from("Invalid destination: e9"))));
+    }
+
+    #[test]
+    fn test_parses_captures(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["e4", "d5"]);
+
+        assert_eq!(game.parse_san("exd5"), Ok(movement("e4", "d5")));
+        assert_eq!(game.parse_san("Nxf3"), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_rank_hint_is_not_ignored(){
+        let game = Game::from_fen("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(matches!(game.parse_san("Ra3"), Err(ChessError::AmbiguousMove(_))));
+        assert_eq!(game.parse_san("R1a3"), Ok(movement("a1", "a3")));
+        assert_eq!(game.parse_san("R5a3"), Ok(movement("a5", "a3")));
+    }
+
+    #[test]
+    fn test_file_and_square_hints(){
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1").unwrap();
+
+        assert!(matches!(game.parse_san("Nd2"), Err(ChessError::AmbiguousMove(_))));
+        assert_eq!(game.parse_san("Nbd2"), Ok(movement("b1", "d2")));
+        assert_eq!(game.parse_san("Nfd2"), Ok(movement("f1", "d2")));
+
+        let game = Game::from_fen("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1").unwrap();
+
+        assert!(matches!(game.parse_san("Qab2"), Err(ChessError::AmbiguousMove(_))));
+        assert_eq!(game.parse_san("Qa1b2"), Ok(movement("a1", "b2")));
+        assert_eq!(game.to_san(&movement("a1", "b2")), "Qa1b2");
+        assert_eq!(game.to_san(&movement("a3", "b2")), "Q3b2");
+        assert_eq!(game.to_san(&movement("c1", "b2")), "Qcb2");
+        assert_eq!(game.to_san(&movement("c1", "d2")), "Qd2");
+    }
+
+    #[test]
+    fn test_parses_castles(){
+        let game = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(game.parse_san("O-O"), Ok(movement("e1", "g1")));
+        assert_eq!(game.parse_san("0-0-0"), Ok(movement("e1", "c1")));
+        assert_eq!(game.to_san(&movement("e1", "g1")), "O-O");
+        assert_eq!(game.to_san(&movement("e1", "c1")), "O-O-O");
+
+        let game = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b Qk - 0 1").unwrap();
+
+        assert_eq!(game.parse_san("O-O"), Ok(movement("e8", "g8")));
+        assert_eq!(game.parse_san("O-O-O"), Err(ChessError::IllegalMove));
+    }
+
+    #[test]
+    fn test_parses_promotions(){
+        let game = Game::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        let a8 = ChessNotationPosition::new('a', 8).to_position();
+        let b8 = ChessNotationPosition::new('b', 8).to_position();
+        let a7 = ChessNotationPosition::new('a', 7).to_position();
+
+        assert_eq!(game.parse_san("a8=Q"), Ok(Movement::with_promotion(a7, a8, PieceType::Queen)));
+        assert_eq!(game.parse_san("axb8=N"), Ok(Movement::with_promotion(a7, b8, PieceType::Knight)));
+        assert_eq!(game.parse_san("a8R"), Ok(Movement::with_promotion(a7, a8, PieceType::Rook)));
+        assert_eq!(game.parse_san("a8"), Err(ChessError::IllegalMove));
+        assert_eq!(game.parse_san("a8=K"), Err(ChessError::ParseError(String::from("Invalid promotion: K"))));
+
+        assert_eq!(game.to_san(&Movement::with_promotion(a7, a8, PieceType::Queen)), "a8=Q");
+        assert_eq!(game.to_san(&Movement::with_promotion(a7, b8, PieceType::Knight)), "axb8=N");
+    }
+
+    #[test]
+    fn test_writes_check_and_checkmate(){
+        let mut game = ClassicGame::new();
+
+        play(&mut game, &["f3", "e5", "g4"]);
+
+        assert_eq!(game.to_san(&movement("d8", "h4")), "Qh4#");
+        assert_eq!(game.parse_san("Qh4#"), Ok(movement("d8", "h4")));
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert_eq!(game.to_san(&movement("a1", "a8")), "Ra8+");
+
+        // Taking the checking pawn en passant is the only reply
+        let game = Game::from_fen("7k/1p6/8/P7/K7/7r/8/1r6 b - - 0 1").unwrap();
+
+        assert_eq!(game.to_san(&movement("b7", "b5")), "b5+");
+    }
+
+    #[test]
+    fn test_writes_en_passant_as_a_capture(){
+        let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+
+        assert_eq!(game.to_san(&movement("e5", "d6")), "exd6");
+        assert_eq!(game.parse_san("exd6"), Ok(movement("e5", "d6")));
+    }
+
+    #[test]
+    fn test_san_round_trips_for_every_legal_move(){
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        for movement in game.generate_legal_plays(){
+            let san = game.to_san(&movement);
+            assert_eq!(game.parse_san(&san), Ok(movement), "{} did not round trip", san);
+        }
+    }
+
+    #[test]
+    fn test_lists_the_moves_played(){
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K2R w K - 0 1").unwrap();
+
+        play(&mut game, &["O-O", "Kd7", "e4"]);
+
+        assert_eq!(game.san_moves(), ["O-O", "Kd7", "e4"]);
+        assert!(ClassicGame::new().san_moves().is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::chess::{bitboard::{attacks, zobrist}, color::Color, piece::PieceType};
+
+use super::{castling::CastleSide, Game};
+
+
+impl Game{
+    /// Returns the Zobrist hash of the current position
+    ///
+    /// The hash covers the pieces, the player to move, the castling rights and the en passant
+    /// file, and matches the keys used by Polyglot opening books. Like in Polyglot, the en passant
+    /// file only counts when a pawn of the player to move stands next to the pawn that just
+    /// advanced two tiles.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use better_chess::ClassicGame;
+    ///
+    /// assert_eq!(ClassicGame::new().zobrist_hash(), 0x463b96181691fc9c);
+    /// ```
+    pub fn zobrist_hash(&self) -> u64{
+        self.zobrist
+    }
+
+    /// The keys of everything the hash covers besides the pieces
+    pub(super) fn zobrist_state_keys(&self) -> u64{
+        let mut keys = 0;
+
+        if self.turn == Color::White{
+            keys ^= zobrist::turn_key();
+        }
+
+        for color in [Color::White, Color::Black]{
+            for side in [CastleSide::KingSide, CastleSide::QueenSide]{
+                if self.castling_rights.has(&color, &side){
+                    keys ^= zobrist::castling_key(&color, &side);
+                }
+            }
+        }
+
+        if let Some(square) = self.en_passant{
+            let capturers = attacks::pawn_attacks(&self.turn.opposite(), &square) & self.position.pieces(&self.turn, PieceType::Pawn);
+
+            if !capturers.is_empty(){
+                keys ^= zobrist::en_passant_key(square.file);
+            }
+        }
+
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{bitboard::position::Position, game::fen::STARTING_FEN};
+
+    use super::*;
+
+    /// Reference hashes published with the Polyglot book format
+    const POLYGLOT_HASHES : [(&str, u64); 9] = [
+        (STARTING_FEN, 0x463b96181691fc9c),
+        ("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", 0x823c9b50fd114196),
+        ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", 0x0756b94461c50fb0),
+        ("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2", 0x662fafb965db29d4),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", 0x22a48b5a8e47ff78),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 1 3", 0x652a607ca3f242c1),
+        ("rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 2 4", 0x00fdd303c946bdd9),
+        ("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3", 0x3c8123ea7b067637),
+        ("rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 1 4", 0x5c3f9b829b279560),
+    ];
+
+    fn play(game: &mut Game, moves: &[&str]){
+        for san in moves{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_hash_of_a_position_matches_polyglot(){
+        for (fen, hash) in POLYGLOT_HASHES{
+            assert_eq!(Game::from_fen(fen).unwrap().zobrist_hash(), hash, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_hash_is_updated_move_by_move(){
+        let mut game = Game::from_fen(STARTING_FEN).unwrap();
+
+        for (san, (_, hash)) in ["e4", "d5", "e5", "f5", "Ke2", "Kf7"].iter().zip(POLYGLOT_HASHES.iter().skip(1)){
+            play(&mut game, &[san]);
+            assert_eq!(game.zobrist_hash(), *hash, "after {}", san);
+        }
+
+        let mut game = Game::from_fen(STARTING_FEN).unwrap();
+
+        play(&mut game, &["a4", "b5", "h4", "b4", "c4"]);
+        assert_eq!(game.zobrist_hash(), POLYGLOT_HASHES[7].1);
+
+        play(&mut game, &["bxc3", "Ra3"]);
+        assert_eq!(game.zobrist_hash(), POLYGLOT_HASHES[8].1);
+    }
+
+    #[test]
+    fn test_undo_restores_the_hash(){
+        let mut game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let hash = game.zobrist_hash();
+
+        play(&mut game, &["O-O", "Bxe2", "Qxe2", "O-O-O"]);
+        assert_ne!(game.zobrist_hash(), hash);
+
+        for _ in 0..4{
+            game.undo();
+        }
+
+        assert_eq!(game.zobrist_hash(), hash);
+    }
+
+    #[test]
+    fn test_transpositions_share_a_hash(){
+        let mut first = Game::from_fen(STARTING_FEN).unwrap();
+        let mut second = Game::from_fen(STARTING_FEN).unwrap();
+
+        play(&mut first, &["Nf3", "Nf6", "Nc3"]);
+        play(&mut second, &["Nc3", "Nf6", "Nf3"]);
+
+        assert_eq!(first.zobrist_hash(), second.zobrist_hash());
+        assert_eq!(first.zobrist_hash(), Game::from_fen(&first.to_fen()).unwrap().zobrist_hash());
+    }
+
+    fn assert_incremental_hash(game: &mut Game, depth: u32){
+        assert_eq!(game.zobrist_hash(), game.position.zobrist_hash() ^ game.zobrist_state_keys(), "{}", game.to_fen());
+        assert_eq!(game.position.zobrist_hash(), Position::from_board(&game.board()).zobrist_hash());
+
+        if depth == 0{
+            return;
+        }
+
+        for movement in game.generate_legal_plays(){
+            game.make_move(movement).unwrap();
+            assert_incremental_hash(game, depth - 1);
+            game.unmake_move();
+        }
+    }
+
+    #[test]
+    fn test_incremental_hash_matches_a_fresh_one(){
+        for fen in ["r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1"]{
+            assert_incremental_hash(&mut Game::from_fen(fen).unwrap(), 2);
+        }
+    }
+}
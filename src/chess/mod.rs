@@ -2,4 +2,8 @@ pub mod color;
 pub mod piece;
 pub mod board;
 pub mod game;
-pub mod movement;
\ No newline at end of file
+pub mod movement;
+pub mod pgn;
+pub mod error;
+pub mod bitboard;
+pub mod engine;
\ No newline at end of file
@@ -1,6 +1,8 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
-use super::relative_position::RelativePosition;
+use crate::chess::error::ChessError;
+
+use super::{chess_notation::ChessNotationPosition, relative_position::RelativePosition};
 
 
 #[derive(PartialEq, Debug, Eq, Clone, Copy)]
@@ -16,6 +18,15 @@ impl Display for AbsolutePosition {
     }
 }
 
+/// Reads a tile in chess notation such as `e4`
+impl FromStr for AbsolutePosition {
+    type Err = ChessError;
+
+    fn from_str(string: &str) -> Result<Self, ChessError> {
+        Ok(ChessNotationPosition::from_str(string)?.to_position())
+    }
+}
+
 impl AbsolutePosition {
 
     pub fn new(file: usize, rank: usize) -> Self {
@@ -32,12 +43,12 @@ impl AbsolutePosition {
         }
     }
 
-    pub fn from_relative(from : &AbsolutePosition, to : &RelativePosition) -> Result<AbsolutePosition, String> {
+    pub fn from_relative(from : &AbsolutePosition, to : &RelativePosition) -> Result<AbsolutePosition, ChessError> {
         let file = to.file + from.file as i8;
         let rank = to.rank + from.rank as i8;
 
         if file < 0 || file > 7 || rank < 0 || rank > 7 {
-            Err(format!("Invalid position!"))
+            Err(ChessError::OutOfBoard{file, rank})
         } else {
             Ok(AbsolutePosition {
                 file: file as usize,
@@ -77,4 +88,11 @@ fn test_should_fail_position_from_relative() {
     let position = AbsolutePosition::from_relative(&position, &relative_position);
 
     assert!(position.is_err());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_position_from_str() {
+    assert_eq!("c7".parse::<AbsolutePosition>().unwrap(), AbsolutePosition::new(2, 6));
+    assert_eq!(AbsolutePosition::new(2, 6).to_string(), "c7");
+    assert!("z9".parse::<AbsolutePosition>().is_err());
+}
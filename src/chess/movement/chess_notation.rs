@@ -1,43 +1,124 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::chess::error::ChessError;
+
 use super::absolute_position::AbsolutePosition;
 
 
-#[derive(Debug)]
+/// A tile on the board as written in chess notation, always between `a1` and `h8`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct ChessNotationPosition{
-    pub file: char,
-    pub rank: u8,
+    file: char,
+    rank: u8,
 }
 
 impl ChessNotationPosition {
-    pub fn new(file: char, rank: u8) -> Self {
+    /// Creates a tile from its file and rank, meant for tiles written out in the code
+    /// ### Arguments
+    /// * `file` - The file, from `a` to `h`
+    /// * `rank` - The rank, from `1` to `8`
+    /// ### Panics
+    /// If the tile lies off the board, `try_new` returns an error instead
+    pub const fn new(file: char, rank: u8) -> Self {
+        assert!(file >= 'a' && file <= 'h' && rank >= 1 && rank <= 8, "The tile lies off the board");
+
         Self {
             file,
             rank,
         }
     }
 
+    /// Creates a tile from its file and rank
+    /// ### Arguments
+    /// * `file` - The file, from `a` to `h`
+    /// * `rank` - The rank, from `1` to `8`
+    /// ### Returns
+    /// The tile, or a `ParseError` if it lies off the board
+    pub fn try_new(file: char, rank: u8) -> Result<Self, ChessError> {
+        if !('a'..='h').contains(&file) {
+            return Err(ChessError::ParseError(format!("Invalid file {:?}, expected a to h", file)));
+        }
+
+        if !(1..=8).contains(&rank) {
+            return Err(ChessError::ParseError(format!("Invalid rank {:?}, expected 1 to 8", rank)));
+        }
+
+        Ok(Self::new(file, rank))
+    }
+
+    pub fn file(&self) -> char {
+        self.file
+    }
+
+    pub fn rank(&self) -> u8 {
+        self.rank
+    }
+
     pub fn to_position(&self) -> AbsolutePosition {
         AbsolutePosition {
             file: self.file as usize - 97,
             rank: self.rank as usize - 1,
         }
     }
+}
 
-    pub fn from_str(string: &str) -> Result<Self, String> {
-        if string.len() != 2 {
-            return Err(String::from("Invalid string length"));
+/// Reads a tile such as `e4`, the file from `a` to `h` and the rank from `1` to `8`
+impl FromStr for ChessNotationPosition {
+    type Err = ChessError;
+
+    fn from_str(string: &str) -> Result<Self, ChessError> {
+        let mut chars = string.chars();
+
+        let (file, rank) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(file), Some(rank), None) => (file, rank),
+            _ => return Err(ChessError::ParseError(format!("Invalid tile {:?}, expected a file and a rank", string))),
+        };
+
+        match rank.to_digit(10) {
+            Some(rank) => Self::try_new(file, rank as u8),
+            None => Err(ChessError::ParseError(format!("Invalid rank {:?}, expected 1 to 8", rank))),
         }
+    }
+}
 
-        let file = string.chars().nth(0).unwrap();
-        let rank = string.chars().nth(1).unwrap();
+impl Display for ChessNotationPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.file, self.rank)
+    }
+}
 
-        if !file.is_ascii_alphabetic() {
-            return Err(String::from("Invalid file"));
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_parses_tiles_on_the_board(){
+        let position = ChessNotationPosition::from_str("e4").unwrap();
+
+        assert_eq!(position, ChessNotationPosition::new('e', 4));
+        assert_eq!(position.to_position(), AbsolutePosition::new(4, 3));
+        assert_eq!(position.to_string(), "e4");
+        assert_eq!("a1".parse::<ChessNotationPosition>().unwrap().to_position(), AbsolutePosition::new(0, 0));
+        assert_eq!("h8".parse::<ChessNotationPosition>().unwrap().to_position(), AbsolutePosition::new(7, 7));
+    }
+
+    #[test]
+    fn test_rejects_tiles_off_the_board(){
+        for string in ["z9", "i1", "a0", "a9", "A1", "e", "e44", "", "é4", "4e"]{
+            assert!(matches!(ChessNotationPosition::from_str(string), Err(ChessError::ParseError(_))), "{}", string);
         }
+    }
 
-        if !rank.is_ascii_digit() {
-            return Err(String::from("Invalid rank"));
+    #[test]
+    fn test_rejects_tiles_off_the_board_on_construction(){
+        for (file, rank) in [('z', 9), ('i', 1), ('a', 0), ('a', 9), ('A', 1), ('`', 1)]{
+            assert!(matches!(ChessNotationPosition::try_new(file, rank), Err(ChessError::ParseError(_))), "{}{}", file, rank);
         }
+    }
 
-        Ok(Self::new(file, rank.to_digit(10).unwrap() as u8))
+    #[test]
+    #[should_panic(expected = "The tile lies off the board")]
+    fn test_new_panics_off_the_board(){
+        ChessNotationPosition::new('z', 9);
     }
 }
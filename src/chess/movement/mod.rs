@@ -1,5 +1,9 @@
+use std::{fmt::Display, str::FromStr};
+
 use self::absolute_position::AbsolutePosition;
 
+use super::{error::ChessError, piece::PieceType};
+
 pub mod line;
 pub mod diagonal;
 pub mod generate_valid_moves;
@@ -13,6 +17,7 @@ pub mod chess_notation;
 pub struct Movement{
     from: AbsolutePosition,
     to: AbsolutePosition,
+    promotion: Option<PieceType>,
 } 
 
 
@@ -21,6 +26,16 @@ impl Movement {
         Self {
             from,
             to,
+            promotion: None,
+        }
+    }
+
+    /// Creates a pawn movement onto the last rank that promotes to `piece_type`
+    pub fn with_promotion(from: AbsolutePosition, to: AbsolutePosition, piece_type: PieceType) -> Self {
+        Self {
+            from,
+            to,
+            promotion: Some(piece_type),
         }
     }
 
@@ -31,5 +46,95 @@ impl Movement {
     pub fn to(&self) -> &AbsolutePosition {
         &self.to
     }
+
+    pub fn promotion(&self) -> Option<PieceType> {
+        self.promotion
+    }
+
+    /// Reads a movement in the long algebraic notation of UCI, like `e2e4` or `e7e8q`
+    ///
+    /// ### Arguments
+    ///
+    /// * `string` - The starting tile, the destination tile and an optional promotion letter
+    ///
+    /// ### Returns
+    ///
+    /// Returns a `ChessError::ParseError` when a tile is off the board or the promotion letter
+    /// is not one of `q`, `r`, `b` and `n`
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use better_chess::{AbsolutePosition, Movement, PieceType};
+    ///
+    /// let movement = Movement::from_uci("e7e8q").unwrap();
+    ///
+    /// assert_eq!(movement, Movement::with_promotion(AbsolutePosition::new(4, 6), AbsolutePosition::new(4, 7), PieceType::Queen));
+    /// assert_eq!(movement.to_uci(), "e7e8q");
+    /// assert!(Movement::from_uci("e7").is_err());
+    /// ```
+    pub fn from_uci(string: &str) -> Result<Self, ChessError> {
+        if !string.is_ascii() || !(4..=5).contains(&string.len()) {
+            return Err(ChessError::ParseError(format!("Invalid movement {:?}, expected a tile, a tile and an optional promotion", string)));
+        }
+
+        let from = AbsolutePosition::from_str(&string[0..2])?;
+        let to = AbsolutePosition::from_str(&string[2..4])?;
+
+        match string[4..].chars().next() {
+            Some(letter) => match PieceType::from_prefix(letter) {
+                Some(piece_type) if PieceType::PROMOTIONS.contains(&piece_type) => Ok(Movement::with_promotion(from, to, piece_type)),
+                _ => Err(ChessError::ParseError(format!("Invalid promotion {:?}, expected q, r, b or n", letter))),
+            },
+            None => Ok(Movement::new(from, to)),
+        }
+    }
+
+    /// Writes the movement in the long algebraic notation of UCI, the promotion in lowercase
+    pub fn to_uci(&self) -> String {
+        match self.promotion {
+            Some(piece_type) => format!("{}{}{}", self.from, self.to, piece_type.prefix().to_ascii_lowercase()),
+            None => format!("{}{}", self.from, self.to),
+        }
+    }
+}
+
+impl FromStr for Movement {
+    type Err = ChessError;
+
+    fn from_str(string: &str) -> Result<Self, ChessError> {
+        Movement::from_uci(string)
+    }
 }
 
+/// Writes the movement like `to_uci`
+impl Display for Movement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_uci())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_uci_round_trip(){
+        for string in ["e2e4", "g1f3", "a7a8q", "h2h1n", "b7c8r", "d2d1b"]{
+            let movement = Movement::from_uci(string).unwrap();
+
+            assert_eq!(movement.to_uci(), string);
+            assert_eq!(movement.to_string(), string);
+            assert_eq!(string.parse::<Movement>().unwrap(), movement);
+        }
+
+        assert_eq!(Movement::from_uci("e7e8Q").unwrap().to_uci(), "e7e8q");
+    }
+
+    #[test]
+    fn test_invalid_uci_is_an_error(){
+        for string in ["", "e", "e2", "e2e", "e2e9", "z9e4", "e7e8k", "e7e8p", "e7e8qq", "e2-e4", "é2e4"]{
+            assert!(matches!(Movement::from_uci(string), Err(ChessError::ParseError(_))), "{}", string);
+        }
+    }
+}
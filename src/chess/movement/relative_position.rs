@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::chess::error::ChessError;
+
 use super::absolute_position::AbsolutePosition;
 
 #[derive(PartialEq, Debug)]
@@ -17,12 +19,12 @@ impl RelativePosition{
         }
     }
 
-    pub fn to_absolute(&self, position: &AbsolutePosition) -> Result<AbsolutePosition, String> {
+    pub fn to_absolute(&self, position: &AbsolutePosition) -> Result<AbsolutePosition, ChessError> {
         let file = self.file + position.file as i8;
         let rank = self.rank + position.rank as i8;
 
         if file < 0 || file > 7 || rank < 0 || rank > 7 {
-            Err(format!("Invalid position: {}{}", file, rank))
+            Err(ChessError::OutOfBoard{file, rank})
         } else {
             Ok(AbsolutePosition {
                 file: file as usize,
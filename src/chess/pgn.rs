@@ -0,0 +1,656 @@
+use std::{fmt::Display, io::{BufRead, Lines}};
+
+use super::{color::Color, error::ChessError, game::{chess960::Chess960Game, classic::ClassicGame, fen::{FenError, STARTING_FEN}, Game}};
+
+/// The tags written before the moves of a PGN game
+/// 
+/// The seven tag roster always comes first, `others` keeps every other tag in file order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnTags{
+    pub event: String,
+    pub site: String,
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+    pub others: Vec<(String, String)>,
+}
+
+impl Default for PgnTags{
+    fn default() -> Self {
+        PgnTags{
+            event: String::from("?"),
+            site: String::from("?"),
+            date: String::from("????.??.??"),
+            round: String::from("?"),
+            white: String::from("?"),
+            black: String::from("?"),
+            others: Vec::new(),
+        }
+    }
+}
+
+impl PgnTags{
+    /// Returns the value of a tag, looking at the roster and the other tags
+    pub fn get(&self, name: &str) -> Option<&str>{
+        match name{
+            "Event" => Some(&self.event),
+            "Site" => Some(&self.site),
+            "Date" => Some(&self.date),
+            "Round" => Some(&self.round),
+            "White" => Some(&self.white),
+            "Black" => Some(&self.black),
+            _ => self.others.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str()),
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: &str){
+        let value = value.to_string();
+
+        match name{
+            "Event" => self.event = value,
+            "Site" => self.site = value,
+            "Date" => self.date = value,
+            "Round" => self.round = value,
+            "White" => self.white = value,
+            "Black" => self.black = value,
+            _ => match self.others.iter_mut().find(|(tag, _)| tag == name){
+                Some((_, old)) => *old = value,
+                None => self.others.push((name.to_string(), value)),
+            },
+        }
+    }
+}
+
+/// The reason a PGN game could not be read
+#[derive(Debug)]
+pub enum PgnError{
+    Io(std::io::Error),
+    /// A tag pair line that is not `[Name "value"]`
+    Tag(String),
+    /// The `FEN` tag holds an invalid position
+    Fen(FenError),
+    UnterminatedComment,
+    UnbalancedVariation,
+    /// A move of the main line that is not legal, `ply` counts from 1
+    Move{
+        ply: usize,
+        san: String,
+        reason: ChessError,
+    },
+}
+
+impl Display for PgnError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            PgnError::Io(error) => write!(f, "Could not read PGN: {}", error),
+            PgnError::Tag(line) => write!(f, "Invalid tag pair: {}", line),
+            PgnError::Fen(error) => write!(f, "Invalid FEN tag: {}", error),
+            PgnError::UnterminatedComment => write!(f, "Comment is never closed"),
+            PgnError::UnbalancedVariation => write!(f, "Variation parentheses do not match"),
+            PgnError::Move{ply, san, reason} => write!(f, "Invalid move {} at ply {}: {}", san, ply, reason),
+        }
+    }
+}
+
+impl std::error::Error for PgnError{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self{
+            PgnError::Io(error) => Some(error),
+            PgnError::Fen(error) => Some(error),
+            PgnError::Move{reason, ..} => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// A game read from PGN, with its tags and the game after replaying the main line
+#[derive(Clone)]
+pub struct PgnGame{
+    tags: PgnTags,
+    result: String,
+    game: Game,
+}
+
+impl PgnGame{
+    pub fn tags(&self) -> &PgnTags{
+        &self.tags
+    }
+
+    /// Returns the result token of the game, `*` when it is unknown
+    pub fn result(&self) -> &str{
+        &self.result
+    }
+
+    pub fn game(&self) -> &Game{
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game{
+        self.game
+    }
+}
+
+/// Reads the games of a PGN file one by one
+/// 
+/// Only the lines of the game being read are kept in memory, so arbitrarily large archives can be streamed.
+/// A game ends at its result token, so games without tags need no blank line or tag section between them
+/// 
+/// ## Examples
+/// 
+/// ```
+/// use better_chess::PgnReader;
+/// 
+/// let pgn = "[Event \"First\"]\n\n1. e4 e5 1-0\n\n[Event \"Second\"]\n\n1. d4 {a comment} d5 (1... Nf6) *\n";
+/// 
+/// let games : Vec<_> = PgnReader::new(pgn.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// 
+/// assert_eq!(games.len(), 2);
+/// assert_eq!(games[1].tags().event, "Second");
+/// assert_eq!(games[1].game().moves().len(), 2);
+/// ```
+pub struct PgnReader<R>{
+    lines: Lines<R>,
+    pending: Option<String>,
+}
+
+impl<R: BufRead> PgnReader<R>{
+    pub fn new(reader: R) -> Self{
+        PgnReader{
+            lines: reader.lines(),
+            pending: None,
+        }
+    }
+
+    fn next_line(&mut self) -> Option<Result<String, std::io::Error>>{
+        match self.pending.take(){
+            Some(line) => Some(Ok(line)),
+            None => self.lines.next(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R>{
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut tag_lines = Vec::new();
+        let mut movetext = String::new();
+        let mut in_comment = false;
+        let mut depth : usize = 0;
+
+        while let Some(line) = self.next_line(){
+            let line = match line{
+                Ok(line) => line,
+                Err(error) => return Some(Err(PgnError::Io(error))),
+            };
+
+            let trimmed = line.trim();
+
+            if !in_comment{
+                if trimmed.starts_with('%'){
+                    continue;
+                }
+
+                if trimmed.starts_with('['){
+                    if !movetext.trim().is_empty(){
+                        self.pending = Some(line);
+                        break;
+                    }
+
+                    tag_lines.push(trimmed.to_string());
+                    continue;
+                }
+            }
+
+            let mut token_start = None;
+            let mut game_end = None;
+
+            for (index, letter) in line.char_indices().chain(std::iter::once((line.len(), '\n'))){
+                let is_separator = letter.is_whitespace() || matches!(letter, '{' | '}' | ';' | '(' | ')' | '$');
+
+                if is_separator{
+                    if let Some(start) = token_start.take(){
+                        if is_result(&line[start..index]){
+                            game_end = Some(index);
+                            break;
+                        }
+                    }
+                }
+
+                match (in_comment, letter){
+                    (true, '}') => in_comment = false,
+                    (false, '{') => in_comment = true,
+                    (false, ';') => break,
+                    (false, '(') => depth += 1,
+                    (false, ')') => depth = depth.saturating_sub(1),
+                    (false, _) if !is_separator && depth == 0 && token_start.is_none() => token_start = Some(index),
+                    _ => (),
+                }
+            }
+
+            if let Some(end) = game_end{
+                movetext.push_str(&line[..end]);
+                self.pending = Some(line[end..].to_string());
+                break;
+            }
+
+            movetext.push_str(&line);
+            movetext.push('\n');
+        }
+
+        if tag_lines.is_empty() && movetext.trim().is_empty(){
+            return None;
+        }
+
+        Some(read_game(&tag_lines, &movetext))
+    }
+}
+
+impl Game{
+    /// Reads the first game of a PGN text
+    pub fn from_pgn(pgn: &str) -> Result<Game, PgnError>{
+        match PgnReader::new(pgn.as_bytes()).next(){
+            Some(game) => game.map(PgnGame::into_game),
+            None => Ok(ClassicGame::new()),
+        }
+    }
+
+    /// Writes the game in Portable Game Notation
+    /// 
+    /// ### Arguments
+    /// 
+    /// * `tags` - The seven tag roster and any other tags, the `Result` tag is taken from the game,
+    ///   as is the `Termination` tag of games lost on time or abandoned
+    /// 
+    /// Games that did not start from the standard position also get the `SetUp` and `FEN` tags,
+    /// Chess960 games the `Variant` tag
+    pub fn to_pgn(&self, tags: &PgnTags) -> String{
+        let result = self.result().to_string();
+        let termination = self.result().termination().pgn_tag();
+
+        let mut pgn = String::new();
+
+        for (name, value) in [("Event", &tags.event), ("Site", &tags.site), ("Date", &tags.date), ("Round", &tags.round), ("White", &tags.white), ("Black", &tags.black)]{
+            pgn.push_str(&tag_pair(name, value));
+        }
+
+        pgn.push_str(&tag_pair("Result", &result));
+
+        let mut replay = self.initial_game();
+
+        if self.is_chess960(){
+            pgn.push_str(&tag_pair("Variant", "Chess960"));
+        }
+
+        if replay.to_fen() != ClassicGame::new().to_fen(){
+            pgn.push_str(&tag_pair("SetUp", "1"));
+            pgn.push_str(&tag_pair("FEN", self.initial_fen()));
+        }
+
+        if matches!(termination, "time forfeit" | "abandoned"){
+            pgn.push_str(&tag_pair("Termination", termination));
+        }
+
+        for (name, value) in &tags.others{
+            if !matches!(name.as_str(), "Result" | "SetUp" | "FEN" | "Variant" | "Termination"){
+                pgn.push_str(&tag_pair(name, value));
+            }
+        }
+
+        pgn.push('\n');
+
+        let mut tokens = Vec::new();
+
+        for (i, movement) in self.moves().iter().enumerate(){
+            if replay.turn() == &Color::White{
+                tokens.push(format!("{}.", replay.fullmove_number()));
+            }else if i == 0{
+                tokens.push(format!("{}...", replay.fullmove_number()));
+            }
+
+            tokens.push(replay.to_san(movement));
+            replay.move_piece(*movement).expect("Every recorded move is legal");
+        }
+
+        tokens.push(result);
+
+        let mut line = String::new();
+
+        for token in tokens{
+            if !line.is_empty() && line.len() + token.len() + 1 > 80{
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty(){
+                line.push(' ');
+            }
+
+            line.push_str(&token);
+        }
+
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+fn tag_pair(name: &str, value: &str) -> String{
+    format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse_tag_pair(line: &str) -> Result<(String, String), PgnError>{
+    let inner = line.strip_prefix('[').and_then(|line| line.strip_suffix(']'))
+        .ok_or_else(|| PgnError::Tag(line.to_string()))?;
+
+    let (name, value) = inner.trim().split_once(char::is_whitespace)
+        .ok_or_else(|| PgnError::Tag(line.to_string()))?;
+
+    let value = value.trim().strip_prefix('"').and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| PgnError::Tag(line.to_string()))?;
+
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+
+    while let Some(letter) = chars.next(){
+        match letter{
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(letter),
+        }
+    }
+
+    Ok((name.to_string(), unescaped))
+}
+
+/// Whether the token is one of the results that end the movetext of a game
+fn is_result(token: &str) -> bool{
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+fn read_game(tag_lines: &[String], movetext: &str) -> Result<PgnGame, PgnError>{
+    let mut tags = PgnTags::default();
+    let mut result = String::from("*");
+
+    for line in tag_lines{
+        let (name, value) = parse_tag_pair(line)?;
+
+        match name.as_str(){
+            "Result" => result = value,
+            _ => tags.set(&name, &value),
+        }
+    }
+
+    let is_chess960 = tags.get("Variant").is_some_and(|variant| {
+        let variant = variant.to_lowercase();
+
+        variant.contains("960") || variant.contains("fischerandom")
+    });
+
+    let fen = tags.get("FEN").unwrap_or(STARTING_FEN);
+
+    let mut game = match is_chess960{
+        true => Chess960Game::from_fen(fen).map_err(PgnError::Fen)?,
+        false => Game::from_fen(fen).map_err(PgnError::Fen)?,
+    };
+
+    let mut depth = 0;
+    let mut chars = movetext.chars().peekable();
+    let mut token = String::new();
+    let mut tokens = Vec::new();
+
+    while let Some(letter) = chars.next(){
+        let is_separator = letter.is_whitespace() || matches!(letter, '{' | '}' | ';' | '(' | ')' | '$');
+
+        if is_separator && !token.is_empty(){
+            if depth == 0{
+                tokens.push(std::mem::take(&mut token));
+            }
+
+            token.clear();
+        }
+
+        match letter{
+            '{' => {
+                if !chars.by_ref().any(|letter| letter == '}'){
+                    return Err(PgnError::UnterminatedComment);
+                }
+            },
+            ';' => {
+                for letter in chars.by_ref(){
+                    if letter == '\n'{
+                        break;
+                    }
+                }
+            },
+            '(' => depth += 1,
+            ')' => {
+                if depth == 0{
+                    return Err(PgnError::UnbalancedVariation);
+                }
+
+                depth -= 1;
+            },
+            '$' => {
+                while chars.next_if(|letter| letter.is_ascii_digit()).is_some(){}
+            },
+            '}' => return Err(PgnError::UnterminatedComment),
+            _ if letter.is_whitespace() => (),
+            _ => token.push(letter),
+        }
+    }
+
+    if depth != 0{
+        return Err(PgnError::UnbalancedVariation);
+    }
+
+    if !token.is_empty(){
+        tokens.push(token);
+    }
+
+    for token in tokens{
+        if is_result(&token){
+            result = token;
+            break;
+        }
+
+        let san = token.trim_start_matches(|letter: char| letter.is_ascii_digit()).trim_start_matches('.');
+
+        if san.is_empty(){
+            continue;
+        }
+
+        let ply = game.moves().len() + 1;
+        let error = |reason: ChessError| PgnError::Move{ply, san: san.to_string(), reason};
+
+        let movement = game.parse_san(san).map_err(error)?;
+        game.move_piece(movement).map_err(error)?;
+    }
+
+    game.record_result(&result, tags.get("Termination"));
+
+    Ok(PgnGame{
+        tags,
+        result,
+        game,
+    })
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::chess::{game::{draw::DrawReason, result::{GameResult, Termination}, GameState}, movement::chess_notation::ChessNotationPosition};
+
+    use super::*;
+
+    const IMMORTAL_GAME: &str = r#"[Event "London"]
+[Site "London ENG"]
+[Date "1851.06.21"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Lionel Kieseritzky"]
+[Result "1-0"]
+[ECO "C33"]
+
+1. e4 e5 2. f4 exf4 3. Bc4 Qh4+ 4. Kf1 b5 5. Bxb5 Nf6 6. Nf3 Qh6 7. d3 Nh5 8. Nh4
+Qg5 9. Nf5 c6 10. g4 Nf6 11. Rg1 cxb5 12. h4 Qg6 13. h5 Qg5 14. Qf3 Ng8 15. Bxf4
+Qf6 16. Nc3 Bc5 17. Nd5 Qxb2 18. Bd6 Bxg1 19. e5 Qxa1+ 20. Ke2 Na6 21. Nxg7+ Kd8
+22. Qf6+ Nxf6 23. Be7# 1-0
+"#;
+
+    #[test]
+    fn test_reads_a_full_game(){
+        let pgn_game = PgnReader::new(IMMORTAL_GAME.as_bytes()).next().unwrap().unwrap();
+
+        assert_eq!(pgn_game.tags().white, "Adolf Anderssen");
+        assert_eq!(pgn_game.tags().get("ECO"), Some("C33"));
+        assert_eq!(pgn_game.result(), "1-0");
+        assert_eq!(pgn_game.game().moves().len(), 45);
+        assert_eq!(pgn_game.game().state(), &GameState::Checkmate(Color::Black));
+    }
+
+    #[test]
+    fn test_written_game_reads_back_the_same(){
+        let game = Game::from_pgn(IMMORTAL_GAME).unwrap();
+
+        let mut tags = PgnTags::default();
+        tags.set("White", "Adolf Anderssen");
+        tags.set("Black", "Lionel Kieseritzky");
+        tags.set("ECO", "C33");
+
+        let pgn = game.to_pgn(&tags);
+
+        assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"Adolf Anderssen\"]\n[Black \"Lionel Kieseritzky\"]\n[Result \"1-0\"]\n[ECO \"C33\"]\n\n1. e4 e5 2. f4 exf4 3. Bc4 Qh4+ 4. Kf1 b5"));
+        assert!(pgn.ends_with("Be7# 1-0\n"));
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+
+        let read = Game::from_pgn(&pgn).unwrap();
+
+        assert_eq!(read.moves(), game.moves());
+        assert_eq!(read.to_fen(), game.to_fen());
+    }
+
+    #[test]
+    fn test_skips_comments_nags_and_variations(){
+        let pgn = "1. e4 {best by test} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) ; rest of line (\n2... Nc6 {a (comment}\n3. Bb5 a6!? *";
+
+        let game = Game::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.moves().len(), 6);
+        assert_eq!(*game.moves().last().unwrap().to(), ChessNotationPosition::new('a', 6).to_position());
+    }
+
+    #[test]
+    fn test_streams_several_games(){
+        let archive = format!("{}\n{}\n[Event \"Short\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n", IMMORTAL_GAME, IMMORTAL_GAME);
+
+        let games : Vec<PgnGame> = PgnReader::new(archive.as_bytes()).map(Result::unwrap).collect();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[2].tags().event, "Short");
+        assert_eq!(games[2].result(), "0-1");
+        assert_eq!(games[2].game().state(), &GameState::Checkmate(Color::White));
+    }
+
+    #[test]
+    fn test_games_without_tags_end_at_their_result(){
+        let archive = "1. e4 e5 {not the end: 1-0} (1... c5 0-1) 1-0\n1. d4 d5 * 1. c4\n\nc5 1/2-1/2";
+
+        let games : Vec<PgnGame> = PgnReader::new(archive.as_bytes()).map(Result::unwrap).collect();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].result(), "1-0");
+        assert_eq!(games[0].game().moves().len(), 2);
+        assert_eq!(games[1].result(), "*");
+        assert_eq!(*games[1].game().moves().last().unwrap().to(), ChessNotationPosition::new('d', 5).to_position());
+        assert_eq!(games[2].result(), "1/2-1/2");
+        assert_eq!(*games[2].game().moves().last().unwrap().to(), ChessNotationPosition::new('c', 5).to_position());
+    }
+
+    #[test]
+    fn test_games_from_a_position_write_the_fen(){
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+
+        let movement = game.parse_san("Kd7").unwrap();
+        game.move_piece(movement).unwrap();
+
+        let pgn = game.to_pgn(&PgnTags::default());
+
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 1\"]\n"));
+        assert!(pgn.ends_with("\n1... Kd7 *\n"));
+
+        let read = Game::from_pgn(&pgn).unwrap();
+
+        assert_eq!(read.initial_fen(), fen);
+        assert_eq!(read.moves(), game.moves());
+        assert_eq!(ClassicGame::new().initial_fen(), STARTING_FEN);
+    }
+
+    #[test]
+    fn test_chess960_games_write_the_variant(){
+        let mut game = Chess960Game::new(0).unwrap();
+
+        for san in ["d4", "d5", "Nc3", "Nc6", "Nf3", "Nf6", "Qd2", "Qd7", "O-O-O", "O-O-O"]{
+            let movement = game.parse_san(san).unwrap();
+            game.move_piece(movement).unwrap();
+        }
+
+        let pgn = game.to_pgn(&PgnTags::default());
+
+        assert!(pgn.contains("[Variant \"Chess960\"]\n[SetUp \"1\"]\n[FEN \"bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1\"]\n"));
+        assert!(pgn.ends_with("\n1. d4 d5 2. Nc3 Nc6 3. Nf3 Nf6 4. Qd2 Qd7 5. O-O-O O-O-O *\n"));
+
+        let read = Game::from_pgn(&pgn).unwrap();
+
+        assert!(read.is_chess960());
+        assert_eq!(read.moves(), game.moves());
+    }
+
+    #[test]
+    fn test_results_off_the_board_read_back_the_same(){
+        let mut game = ClassicGame::new();
+
+        game.move_piece(game.parse_san("e4").unwrap()).unwrap();
+        game.resign(Color::Black).unwrap();
+
+        let pgn = game.to_pgn(&PgnTags::default());
+
+        assert!(pgn.contains("[Result \"1-0\"]\n\n"));
+        assert!(pgn.ends_with("\n1. e4 1-0\n"));
+        // PGN has no termination for a resignation, the win is read back without one
+        assert_eq!(Game::from_pgn(&pgn).unwrap().result(), GameResult::Win(Color::White, Termination::Unknown));
+
+        let pgn = "[Result \"0-1\"]\n[Termination \"time forfeit\"]\n\n1. e4 0-1";
+        let game = Game::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.state(), &GameState::Timeout(Color::White));
+        assert!(game.to_pgn(&PgnTags::default()).contains("[Result \"0-1\"]\n[Termination \"time forfeit\"]\n"));
+        assert_eq!(Game::from_pgn("1. e4 1/2-1/2").unwrap().state(), &GameState::Draw(DrawReason::Agreement));
+        assert_eq!(Game::from_pgn("1. e4 *").unwrap().state(), &GameState::InProgress);
+    }
+
+    #[test]
+    fn test_reports_the_illegal_move(){
+        let error = Game::from_pgn("1. e4 e5 2. Ke3 *").err().unwrap();
+
+        assert!(matches!(error, PgnError::Move{ply: 3, ref san, reason: ChessError::IllegalMove} if san == "Ke3"));
+        assert!(matches!(Game::from_pgn("[Event \"x]\n1. e4 *"), Err(PgnError::Tag(_))));
+        assert!(matches!(Game::from_pgn("1. e4 e5) *"), Err(PgnError::UnbalancedVariation)));
+    }
+
+    #[test]
+    fn test_tag_values_are_escaped(){
+        let mut tags = PgnTags::default();
+        tags.set("Event", "The \"Immortal\" game");
+
+        let pgn = ClassicGame::new().to_pgn(&tags);
+
+        assert!(pgn.starts_with("[Event \"The \\\"Immortal\\\" game\"]\n"));
+
+        let pgn_game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+
+        assert_eq!(pgn_game.tags().event, "The \"Immortal\" game");
+    }
+}
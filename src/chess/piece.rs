@@ -22,7 +22,7 @@ pub enum Piece{
     Pawn {piece: Pawn},
 }
 
-
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PieceType{
     King,
     Queen,
@@ -32,6 +32,42 @@ pub enum PieceType{
     Pawn,
 }
 
+impl PieceType {
+    /// Every piece type, in the order of their discriminants
+    pub const ALL: [PieceType; 6] = [PieceType::King, PieceType::Queen, PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Pawn];
+
+    /// The pieces a pawn may promote to, strongest first
+    pub const PROMOTIONS: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+
+    pub fn prefix(&self) -> char {
+        match self{
+            PieceType::King => 'K',
+            PieceType::Queen => 'Q',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Pawn => 'P',
+        }
+    }
+
+    /// Parses a piece letter such as `'Q'` or `'n'`, ignoring case
+    pub fn from_prefix(prefix: char) -> Option<PieceType> {
+        match prefix.to_ascii_uppercase(){
+            'K' => Some(PieceType::King),
+            'Q' => Some(PieceType::Queen),
+            'N' => Some(PieceType::Knight),
+            'B' => Some(PieceType::Bishop),
+            'R' => Some(PieceType::Rook),
+            'P' => Some(PieceType::Pawn),
+            _ => None,
+        }
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        PieceType::PROMOTIONS.contains(self)
+    }
+}
+
 impl Piece {
     pub fn new(color: super::color::Color, piece: PieceType) -> Self {
         match piece{
@@ -66,6 +102,29 @@ impl Piece {
         }
     }
 
+    /// Returns the chess symbol of the piece, such as `♔` for the white king and `♚` for the black one
+    pub fn icon(&self) -> char {
+        match self{
+            Piece::King{piece} => piece.icon(),
+            Piece::Queen{piece} => piece.icon(),
+            Piece::Knight{piece} => piece.icon(),
+            Piece::Bishop{piece} => piece.icon(),
+            Piece::Rook{piece} => piece.icon(),
+            Piece::Pawn{piece} => piece.icon(),
+        }
+    }
+
+    pub fn piece_type(&self) -> PieceType {
+        match self{
+            Piece::King{..} => PieceType::King,
+            Piece::Queen{..} => PieceType::Queen,
+            Piece::Knight{..} => PieceType::Knight,
+            Piece::Bishop{..} => PieceType::Bishop,
+            Piece::Rook{..} => PieceType::Rook,
+            Piece::Pawn{..} => PieceType::Pawn,
+        }
+    }
+
     pub fn value(&self) -> u8 {
         match self{
             Piece::King{piece} => piece.value(),
@@ -91,6 +150,14 @@ impl Piece {
         }
     }
 
+    /// The king movements that would castle, the game decides whether they are allowed
+    pub fn generate_castles(&self) -> Vec<RelativePosition> {
+        match self{
+            Piece::King{..} => vec![RelativePosition::new(2, 0), RelativePosition::new(-2, 0)],
+            _ => Vec::new(),
+        }
+    }
+
     pub fn valid_move(&self, position: &super::movement::relative_position::RelativePosition) -> (Vec<super::movement::relative_position::RelativePosition>, bool) {
         match self{
             Piece::King{piece} => piece.valid_move(position),
@@ -121,8 +188,6 @@ impl Piece {
 
     pub fn has_moved(&self) -> bool{
         match self{
-            Piece::King{piece} => piece.has_moved(),
-            Piece::Rook{piece} => piece.has_moved(),
             Piece::Pawn{piece} => piece.has_moved(),
             _ => false,
         }
@@ -150,6 +215,14 @@ impl Piece {
         }
     }
 
+    /// Whether this piece is a pawn that promotes when it reaches `rank`
+    pub fn promotes_on(&self, rank: usize) -> bool{
+        match self{
+            Piece::Pawn{piece} => piece.last_rank() == rank,
+            _ => false,
+        }
+    }
+
     pub fn promote(&self, piece_type : PieceType) -> Piece{
         match self{
             Piece::Pawn{piece} => piece.promote(piece_type),
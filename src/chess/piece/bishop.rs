@@ -1,7 +1,5 @@
 use std::fmt::Display;
 
-use colored::Colorize;
-
 use crate::chess::{color::Color, movement::{ diagonal::DiagonalMovement, generate_valid_moves::GenerateValidMoves, relative_position::RelativePosition}};
 
 #[derive(Clone)]
@@ -28,8 +26,12 @@ impl Bishop{
         'B'
     }
 
+    /// Returns the chess symbol of the piece, outlined for White and filled for Black
     pub fn icon(&self) -> char{
-        '♗'
+        match self.color{
+            Color::White => '♗',
+            Color::Black => '♝',
+        }
     }
 
     pub fn valid_move(&self,  position: &RelativePosition) -> (Vec<RelativePosition>, bool) {
@@ -60,17 +62,7 @@ impl GenerateValidMoves for Bishop{
 
 impl Display for Bishop {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                match self.color() {
-            Color::White => {
-                format!(" {} ", self.icon())
-                .fmt(f)
-            },
-            Color::Black => {
-                format!(" {} ", self.icon())
-                .yellow()
-                .fmt(f)
-            }
-        }
+        write!(f, " {} ", self.icon())
     }
 }
 
@@ -1,19 +1,16 @@
 use std::fmt::Display;
 
-use colored::Colorize;
-
 use crate::chess::{color::Color, movement::{ generate_valid_moves::GenerateValidMoves, relative_position::RelativePosition}};
 
 #[derive(Clone)]
 
 pub struct King{
     color: Color,
-    is_first_move: bool,
 }
 
 impl King{
     pub fn new(color: Color) -> Self {
-        King{color , is_first_move: true}
+        King{color}
     }
 
     pub fn color(&self) -> &Color {
@@ -28,16 +25,12 @@ impl King{
         'K'
     }
 
+    /// Returns the chess symbol of the piece, outlined for White and filled for Black
     pub fn icon(&self) -> char{
-        '♔'
-    }
-
-    pub fn moved(&mut self){
-        self.is_first_move = false;
-    }
-
-    pub fn has_moved(&self) -> bool{
-        !self.is_first_move
+        match self.color{
+            Color::White => '♔',
+            Color::Black => '♚',
+        }
     }
 
     pub fn valid_move(&self, position: &RelativePosition) -> (Vec<RelativePosition>, bool) {
@@ -88,17 +81,7 @@ impl GenerateValidMoves for King{
 
 impl Display for King {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                match self.color() {
-            Color::White => {
-                format!(" {} ", self.icon())
-                .fmt(f)
-            },
-            Color::Black => {
-                format!(" {} ", self.icon())
-                .yellow()
-                .fmt(f)
-            }
-        }
+        write!(f, " {} ", self.icon())
     }
 }
 
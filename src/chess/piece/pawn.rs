@@ -1,7 +1,5 @@
 use std::fmt::Display;
 
-use colored::Colorize;
-
 use crate::chess::{color::Color, movement::{generate_valid_moves::GenerateValidMoves, relative_position::RelativePosition}};
 
 use super::{Piece, PieceType};
@@ -31,8 +29,12 @@ impl Pawn{
         'P'
     }
 
+    /// Returns the chess symbol of the piece, outlined for White and filled for Black
     pub fn icon(&self) -> char{
-        '♙'
+        match self.color{
+            Color::White => '♙',
+            Color::Black => '♟',
+        }
     }
 
     pub fn moved(&mut self){
@@ -62,8 +64,16 @@ impl Pawn{
         }
     }
 
+    /// The rank on which this pawn has to promote
+    pub fn last_rank(&self) -> usize{
+        match self.color{
+            Color::White => 7,
+            Color::Black => 0,
+        }
+    }
+
     pub fn valid_capture(&self, position : &RelativePosition) -> (Vec<RelativePosition>, bool) {
-        if position.file.abs() == 1 && position.rank == 1{
+        if position.file.abs() == 1 && position.rank == self.multiplier(){
             (Vec::new(), true)
         }else{
             (Vec::new(), false) 
@@ -116,17 +126,7 @@ impl GenerateValidMoves for Pawn{
 
 impl Display for Pawn {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.color() {
-            Color::White => {
-                format!(" {} ", self.icon())
-                .fmt(f)
-            },
-            Color::Black => {
-                format!(" {} ", self.icon())
-                .yellow()
-                .fmt(f)
-            }
-        }
+        write!(f, " {} ", self.icon())
     }
 }
 
@@ -186,6 +186,13 @@ mod tests{
         assert_eq!(move_path.len(), 0, "Moves that are one tile of distance should not contain a path");
     }
 
+    #[test]
+    fn test_black_pawn_captures_downwards(){
+        let pawn = Pawn::new(Color::Black);
+        assert!(pawn.valid_capture(&RelativePosition{file: -1, rank: -1}).1, "Black pawn should capture towards the first rank");
+        assert!(!pawn.valid_capture(&RelativePosition{file: 1, rank: 1}).1, "Black pawn should not capture backwards");
+    }
+
     #[test]
     fn test_pawn_cannot_capture_forward(){
         let pawn = Pawn::new(Color::White);
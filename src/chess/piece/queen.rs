@@ -1,7 +1,5 @@
 use std::fmt::Display;
 
-use colored::Colorize;
-
 use crate::chess::{color::Color, movement::{ diagonal::DiagonalMovement, line::LineMovement, generate_valid_moves::GenerateValidMoves, relative_position::RelativePosition}};
 
 
@@ -32,8 +30,12 @@ impl Queen{
         'Q'
     }
 
+    /// Returns the chess symbol of the piece, outlined for White and filled for Black
     pub fn icon(&self) -> char{
-        '♕'
+        match self.color{
+            Color::White => '♕',
+            Color::Black => '♛',
+        }
     }
 
     pub fn valid_move(&self, position: &RelativePosition) -> (Vec<RelativePosition>, bool) {
@@ -74,17 +76,7 @@ impl GenerateValidMoves for Queen{
 
 impl Display for Queen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                match self.color() {
-            Color::White => {
-                format!(" {} ", self.icon())
-                .fmt(f)
-            },
-            Color::Black => {
-                format!(" {} ", self.icon())
-                .yellow()
-                .fmt(f)
-            }
-        }
+        write!(f, " {} ", self.icon())
     }
 }
 
@@ -1,7 +1,5 @@
 use std::fmt::Display;
 
-use colored::Colorize;
-
 use crate::chess::{color::Color, movement::{ line::LineMovement,generate_valid_moves::GenerateValidMoves, relative_position::RelativePosition}};
 
 
@@ -9,14 +7,13 @@ use crate::chess::{color::Color, movement::{ line::LineMovement,generate_valid_m
 
 pub struct Rook{
     color: Color,
-    is_first_move: bool,
 }
 
 impl LineMovement for Rook{}
 
 impl Rook{
     pub fn new(color: Color) -> Self {
-        Rook{color , is_first_move: true}
+        Rook{color}
     }
 
     pub fn color(&self) -> &Color {
@@ -31,16 +28,12 @@ impl Rook{
         'R'
     }
 
+    /// Returns the chess symbol of the piece, outlined for White and filled for Black
     pub fn icon(&self) -> char{
-        '♖'
-    }
-
-    pub fn moved(&mut self){
-        self.is_first_move = false;
-    }
-
-    pub fn has_moved(&self) -> bool{
-        !self.is_first_move
+        match self.color{
+            Color::White => '♖',
+            Color::Black => '♜',
+        }
     }
 
     pub fn valid_move(&self, position: &RelativePosition) -> (Vec<RelativePosition>, bool) {
@@ -71,17 +64,7 @@ impl GenerateValidMoves for Rook{
 
 impl Display for Rook {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                match self.color() {
-            Color::White => {
-                format!(" {} ", self.icon())
-                .fmt(f)
-            },
-            Color::Black => {
-                format!(" {} ", self.icon())
-                .yellow()
-                .fmt(f)
-            }
-        }
+        write!(f, " {} ", self.icon())
     }
 }
 
@@ -0,0 +1,588 @@
+//! The interactive game of the binary, one move or command per line
+//!
+//! Moves are read in SAN such as `Nf3`, `exd5` or `O-O`, or in coordinates such as `g1f3`.
+//! Everything the commands print is kept until the board is drawn again, so the screen can be
+//! cleared on every turn without losing an error message. With `--vs-engine` the engine plays
+//! one side and answers draw offers.
+
+use std::{fs, io::{self, BufRead, Write}, time::Duration};
+
+use better_chess::{engine::{level::Level, Engine, Score}, prelude::*, ColoredRenderer, GameResult, PgnTags, RenderOptions};
+
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+/// How far behind the engine has to be, in centipawns, to accept a draw offer
+const DRAW_ACCEPTANCE: i32 = 100;
+
+const HELP: &str = "Moves are entered in SAN (Nf3, exd5, O-O, e8=Q) or in coordinates (g1f3, e7e8q)
+Commands:
+  undo         take back the last move
+  moves        list the legal moves
+  flip         draw the board from the side of the player to move, or back from White's side
+  fen          show the position in FEN
+  pgn          show the game in PGN
+  save <file>  write the game to a PGN file
+  load <file>  read a game from a PGN or FEN file
+  resign       resign the game for the player to move
+  draw         claim a draw when there is one, offer one otherwise
+  accept       accept the draw offer
+  decline      decline the draw offer
+  help         show this help
+  quit         leave the game";
+
+/// A line of input, either a move or one of the commands listed by `help`
+#[derive(Debug, PartialEq)]
+pub enum Command{
+    Play(Movement),
+    Undo,
+    Moves,
+    Flip,
+    Fen,
+    Pgn,
+    Save(String),
+    Load(String),
+    Resign,
+    Draw,
+    AcceptDraw,
+    DeclineDraw,
+    Help,
+    Quit,
+}
+
+impl Command{
+    /// Reads a command, or a move of the player to move in SAN or in coordinates
+    ///
+    /// ### Returns
+    ///
+    /// Returns a Result containing the command, otherwise the message explaining what is wrong with the line
+    pub fn parse(line: &str, game: &Game) -> Result<Command, String>{
+        let mut words = line.split_whitespace();
+
+        let word = match words.next(){
+            Some(word) => word,
+            None => return Err(String::from("Please enter a move or a command, type help to list them")),
+        };
+
+        let argument = words.collect::<Vec<&str>>().join(" ");
+        let file = |command: &str| match argument.is_empty(){
+            true => Err(format!("{} needs a file name, such as {} game.pgn", command, command)),
+            false => Ok(argument.clone()),
+        };
+
+        let command = match word.to_lowercase().as_str(){
+            "undo" => Command::Undo,
+            "moves" => Command::Moves,
+            "flip" => Command::Flip,
+            "fen" => Command::Fen,
+            "pgn" => Command::Pgn,
+            "save" => Command::Save(file("save")?),
+            "load" => Command::Load(file("load")?),
+            "resign" => Command::Resign,
+            "draw" => Command::Draw,
+            "accept" => Command::AcceptDraw,
+            "decline" => Command::DeclineDraw,
+            "help" | "?" => Command::Help,
+            "quit" | "exit" => Command::Quit,
+            _ => return parse_movement(word, game).map(Command::Play),
+        };
+
+        Ok(command)
+    }
+}
+
+/// Reads a movement in coordinates, or else in SAN
+fn parse_movement(text: &str, game: &Game) -> Result<Movement, String>{
+    if let Ok(movement) = Movement::from_uci(text){
+        return Ok(movement);
+    }
+
+    game.parse_san(text).map_err(|error| format!("{}: {} Type help for the commands", text, error))
+}
+
+/// The engine playing one side of the game
+pub struct Opponent{
+    engine: Engine,
+    color: Color,
+    level: Level,
+}
+
+impl Opponent{
+    /// Creates the engine playing `color` at `level`
+    pub fn new(color: Color, level: Level) -> Self{
+        Opponent{
+            engine: Engine::new(),
+            color,
+            level,
+        }
+    }
+
+    pub fn color(&self) -> Color{
+        self.color
+    }
+
+    /// Whether the engine has to move now in `game`
+    pub fn is_on_move(&self, game: &Game) -> bool{
+        !game.is_over() && game.state() != &GameState::Promoting && game.turn() == &self.color
+    }
+
+    /// Answers the player's draw offer, then plays the engine's move in `game`
+    ///
+    /// ### Returns
+    ///
+    /// Returns what the engine did, such as the move it played in SAN with its evaluation
+    pub fn play(&mut self, game: &mut Game) -> Vec<String>{
+        let mut messages = Vec::new();
+
+        let choice = match self.engine.play(game, self.level){
+            Some(choice) => choice,
+            None => return messages,
+        };
+
+        if game.draw_offer() == Some(self.color.opposite()){
+            if matches!(choice.score, Score::Centipawns(score) if score < -DRAW_ACCEPTANCE) || matches!(choice.score, Score::Mate(moves) if moves < 0){
+                game.accept_draw().expect("The draw offer stands");
+                messages.push(String::from("The engine accepts the draw"));
+                return messages;
+            }
+
+            game.decline_draw().expect("The draw offer stands");
+            messages.push(String::from("The engine declines the draw"));
+        }
+
+        let san = game.to_san(&choice.movement);
+        let how = match choice.is_random{
+            true => " at random",
+            false => "",
+        };
+
+        messages.push(format!("The engine plays {}{}, evaluation {} for {}", san, how, choice.score, self.color));
+
+        if let Err(error) = game.move_piece(choice.movement){
+            messages.push(error.to_string());
+        }
+
+        messages
+    }
+}
+
+/// The game being played on the command line and how it is shown
+pub struct Cli{
+    game: Game,
+    opponent: Option<Opponent>,
+    /// Whether the board is drawn from the side of the player to move instead of the player's
+    flip: bool,
+    /// What the last command printed, shown under the board
+    messages: Vec<String>,
+}
+
+impl Cli{
+    /// Creates a game between two players sharing the keyboard
+    pub fn new(game: Game) -> Self{
+        Cli{
+            game,
+            opponent: None,
+            flip: false,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Creates a game where the player plays `color` and the engine the other side at `level`
+    pub fn against_engine(game: Game, color: Color, level: Level) -> Self{
+        let mut cli = Cli::new(game);
+
+        cli.opponent = Some(Opponent::new(color.opposite(), level));
+        cli.messages.push(format!("You play {} against the engine at level {}", color, level));
+
+        cli
+    }
+
+    /// Plays lines from `input` until `quit` or the end of the input, drawing the game on `output`
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()>{
+        let mut lines = input.lines();
+
+        loop{
+            self.game.check_flag();
+
+            write!(output, "{}", self.screen())?;
+            self.messages.clear();
+            output.flush()?;
+
+            if self.is_engine_turn(){
+                self.play_engine_move();
+                continue;
+            }
+
+            let line = match lines.next(){
+                Some(line) => line?,
+                None => return Ok(()),
+            };
+
+            if !self.handle(&line){
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handles one line of input
+    ///
+    /// ### Returns
+    ///
+    /// Returns false once the player asked to quit
+    pub fn handle(&mut self, line: &str) -> bool{
+        if self.game.state() == &GameState::Promoting && !matches!(line.trim(), "undo" | "quit" | "exit"){
+            self.promote(line);
+            return true;
+        }
+
+        match Command::parse(line, &self.game){
+            Ok(command) => self.execute(command),
+            Err(message) => {
+                self.messages.push(message);
+                true
+            },
+        }
+    }
+
+    fn execute(&mut self, command: Command) -> bool{
+        let result = match command{
+            Command::Play(movement) => self.game.move_piece(movement).map(|_| ()).map_err(|error| error.to_string()),
+            Command::Undo => match self.game.undo(){
+                Some(_) => {
+                    // Against the engine, its reply is taken back too so the player moves again
+                    if self.is_engine_turn() && self.game.ply() > 0{
+                        self.game.undo();
+                    }
+
+                    Ok(())
+                },
+                None if self.game.ended_off_the_board() => Err(format!("The game ended by {}, its moves cannot be taken back", self.game.result().termination())),
+                None => Err(String::from("There is no move to undo")),
+            },
+            Command::Moves => {
+                let moves: Vec<String> = self.game.legal_moves().iter().map(|movement| self.game.to_san(movement)).collect();
+
+                match moves.is_empty(){
+                    true => Err(String::from("There are no legal moves")),
+                    false => {
+                        self.messages.push(format!("Legal moves: {}", moves.join(" ")));
+                        Ok(())
+                    },
+                }
+            },
+            Command::Flip => {
+                self.flip = !self.flip;
+                Ok(())
+            },
+            Command::Fen => {
+                self.messages.push(self.game.to_fen());
+                Ok(())
+            },
+            Command::Pgn => {
+                self.messages.push(self.game.to_pgn(&PgnTags::default()));
+                Ok(())
+            },
+            Command::Save(path) => fs::write(&path, self.game.to_pgn(&PgnTags::default()))
+                .map(|_| self.messages.push(format!("Saved the game to {}", path)))
+                .map_err(|error| format!("Could not save to {}: {}", path, error)),
+            Command::Load(path) => self.load(&path),
+            Command::Resign => {
+                let color = match &self.opponent{
+                    Some(opponent) => opponent.color().opposite(),
+                    None => *self.game.turn(),
+                };
+                self.game.resign(color).map(|_| ()).map_err(|error| error.to_string())
+            },
+            Command::Draw => match self.game.claimable_draw(){
+                Some(_) => self.game.claim_draw().map(|_| ()).map_err(|error| error.to_string()),
+                None => self.game.offer_draw()
+                    .map(|_| self.messages.push(String::from("Draw offered, the opponent may accept or decline")))
+                    .map_err(|error| error.to_string()),
+            },
+            Command::AcceptDraw => self.game.accept_draw().map(|_| ()).map_err(|error| error.to_string()),
+            Command::DeclineDraw => self.game.decline_draw().map_err(|error| error.to_string()),
+            Command::Help => {
+                self.messages.push(HELP.to_string());
+                Ok(())
+            },
+            Command::Quit => return false,
+        };
+
+        if let Err(message) = result{
+            self.messages.push(message);
+        }
+
+        true
+    }
+
+    /// Whether the engine has to move now
+    fn is_engine_turn(&self) -> bool{
+        self.opponent.as_ref().is_some_and(|opponent| opponent.is_on_move(&self.game))
+    }
+
+    /// Lets the engine answer a draw offer, then play its move
+    fn play_engine_move(&mut self){
+        if let Some(opponent) = &mut self.opponent{
+            self.messages.extend(opponent.play(&mut self.game));
+        }
+    }
+
+    /// Finishes a pending promotion with the piece named on `line`
+    fn promote(&mut self, line: &str){
+        let piece_type = line.trim().chars().next().and_then(PieceType::from_prefix);
+
+        let result = match piece_type{
+            Some(piece_type) => self.game.promote(piece_type).map(|_| ()).map_err(|error| error.to_string()),
+            None => Err(format!("{} is not a piece, promote to Q, R, B or N", line.trim())),
+        };
+
+        if let Err(message) = result{
+            self.messages.push(message);
+        }
+    }
+
+    /// Replaces the game with the one in the file at `path`, in PGN or FEN
+    fn load(&mut self, path: &str) -> Result<(), String>{
+        let content = fs::read_to_string(path).map_err(|error| format!("Could not read {}: {}", path, error))?;
+
+        let game = match Game::from_pgn(&content){
+            Ok(game) => game,
+            Err(pgn_error) => Game::from_fen(content.trim()).map_err(|_| format!("{} is neither PGN nor FEN: {}", path, pgn_error))?,
+        };
+
+        self.game = game;
+        self.messages.push(format!("Loaded the game from {}", path));
+
+        Ok(())
+    }
+
+    /// Returns everything drawn for a turn: the board, the clock, the state and the messages
+    pub fn screen(&self) -> String{
+        let perspective = match (self.flip, &self.opponent){
+            (true, _) => *self.game.turn(),
+            (false, Some(opponent)) => opponent.color().opposite(),
+            (false, None) => Color::White,
+        };
+
+        let mut screen = String::from(CLEAR_SCREEN);
+
+        let options = RenderOptions{
+            highlights: self.game.history().last().map(|record| vec![*record.movement().from(), *record.movement().to()]).unwrap_or_default(),
+            ..RenderOptions::from_side(perspective)
+        };
+
+        screen.push_str(&self.game.board().render(&ColoredRenderer, &options));
+
+        if let Some(clock) = self.game.clock(){
+            screen.push_str(&format!("White {} - Black {}\n", format_time(clock.remaining(&Color::White)), format_time(clock.remaining(&Color::Black))));
+        }
+
+        for message in &self.messages{
+            screen.push_str(message);
+            screen.push('\n');
+        }
+
+        match self.game.result(){
+            GameResult::Win(color, termination) => screen.push_str(&format!("{} wins by {}! ({})\n", color, termination, self.game.result())),
+            GameResult::Draw(termination) => screen.push_str(&format!("Draw by {}! ({})\n", termination, self.game.result())),
+            GameResult::Unfinished(_) if self.game.is_over() => screen.push_str("The game was abandoned\n"),
+            GameResult::Unfinished(_) => screen.push_str(&self.prompt()),
+        }
+
+        if self.game.is_over(){
+            match self.game.ended_off_the_board(){
+                true => screen.push_str("The game is over, type pgn, save <file> or quit\n"),
+                false => screen.push_str("The game is over, type pgn, save <file>, undo or quit\n"),
+            }
+        }
+
+        screen
+    }
+
+    fn prompt(&self) -> String{
+        let mut prompt = String::new();
+
+        if let GameState::Check(_) = self.game.state(){
+            prompt.push_str("Check!\n");
+        }
+
+        if let Some(reason) = self.game.claimable_draw(){
+            prompt.push_str(&format!("You may claim a draw by {} (type draw)\n", reason));
+        }
+
+        match self.game.draw_offer(){
+            Some(color) if &color == self.game.turn() => prompt.push_str(&format!("{} offered a draw, the opponent answers after your move\n", color)),
+            Some(color) => prompt.push_str(&format!("{} offers a draw (type accept or decline)\n", color)),
+            None => (),
+        }
+
+        match self.game.state(){
+            _ if self.is_engine_turn() => prompt.push_str("The engine is thinking...\n"),
+            GameState::Promoting => prompt.push_str("Promote to (Q, R, B, N): \n"),
+            _ => prompt.push_str(&format!("{} to move, enter a move or a command (help lists them): \n", self.game.turn())),
+        }
+
+        prompt
+    }
+}
+
+/// Writes a clock time as minutes and seconds, such as `4:07`
+pub fn format_time(time: Duration) -> String{
+    format!("{}:{:02}", time.as_secs() / 60, time.as_secs() % 60)
+}
+
+#[cfg(test)]
+mod tests{
+    use better_chess::{ChessError, STARTING_FEN};
+
+    use super::*;
+
+    fn play(lines: &[&str]) -> (Cli, String){
+        let mut cli = Cli::new(ClassicGame::new());
+        let mut output = Vec::new();
+
+        cli.run(lines.join("\n").as_bytes(), &mut output).unwrap();
+
+        (cli, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_parses_moves_in_san_and_coordinates(){
+        let game = ClassicGame::new();
+        let knight = Movement::from_uci("g1f3").unwrap();
+
+        assert_eq!(Command::parse("Nf3", &game), Ok(Command::Play(knight)));
+        assert_eq!(Command::parse("g1f3", &game), Ok(Command::Play(knight)));
+        assert_eq!(Command::parse("  e4 ", &game), Ok(Command::Play(Movement::from_uci("e2e4").unwrap())));
+        assert_eq!(Command::parse("save  my game.pgn", &game), Ok(Command::Save(String::from("my game.pgn"))));
+        assert_eq!(Command::parse("HELP", &game), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_short_and_invalid_lines_are_reported(){
+        let game = ClassicGame::new();
+
+        for line in ["", " ", "e", "e2", "Nf", "Ke2", "zz", "save"]{
+            assert!(Command::parse(line, &game).is_err(), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_plays_a_game_with_commands(){
+        let (cli, output) = play(&["e4", "e7e5", "Nf3", "undo", "moves", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]);
+
+        assert_eq!(cli.game.result(), GameResult::Win(Color::White, better_chess::Termination::Checkmate));
+        assert!(output.contains("Legal moves: "));
+        assert!(output.contains("White wins by checkmate! (1-0)"));
+    }
+
+    #[test]
+    fn test_errors_stay_on_screen(){
+        let (cli, output) = play(&["e5", "fen"]);
+        let screens: Vec<&str> = output.split(CLEAR_SCREEN).collect();
+
+        assert_eq!(cli.game.ply(), 0);
+        assert!(screens[2].contains("e5: Invalid move!"));
+        assert!(screens[3].contains("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+    }
+
+    #[test]
+    fn test_flip_draws_the_board_for_the_player_to_move(){
+        let (_, output) = play(&["flip", "e4"]);
+        let screens: Vec<&str> = output.split(CLEAR_SCREEN).collect();
+
+        assert!(screens[1].starts_with("8 "));
+        assert!(screens[2].starts_with("8 "));
+        assert!(screens[3].starts_with("1 "));
+    }
+
+    #[test]
+    fn test_promotion_asks_for_the_piece(){
+        let mut cli = Cli::new(Game::from_fen("8/4P1k1/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+
+        cli.handle("e7e8");
+
+        assert!(cli.screen().contains("Promote to"));
+
+        cli.handle("x");
+        cli.handle("n");
+
+        assert_eq!(cli.game.to_fen(), "4N3/6k1/8/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn test_save_and_load(){
+        let path = std::env::temp_dir().join(format!("better_chess_cli_{}.pgn", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let (_, output) = play(&["e4", "e5", &format!("save {}", path)]);
+
+        assert!(output.contains("Saved the game to"));
+
+        let (cli, _) = play(&[&format!("load {}", path)]);
+
+        assert_eq!(cli.game.moves().len(), 2);
+
+        fs::write(path, "4k3/8/8/8/8/8/8/4K2R w K - 0 1\n").unwrap();
+
+        let (cli, _) = play(&[&format!("load {}", path)]);
+
+        assert_eq!(cli.game.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+
+        let (_, output) = play(&["load /nonexistent/game.pgn"]);
+
+        assert!(output.contains("Could not read /nonexistent/game.pgn"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    fn play_against_engine(fen: &str, lines: &[&str]) -> (Cli, String){
+        let mut cli = Cli::against_engine(Game::from_fen(fen).unwrap(), Color::White, Level::new(3).unwrap());
+        let mut output = Vec::new();
+
+        cli.run(lines.join("\n").as_bytes(), &mut output).unwrap();
+
+        (cli, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_engine_answers_every_move(){
+        let (cli, output) = play_against_engine(STARTING_FEN, &["e4", "Nf3", "undo"]);
+
+        assert_eq!(cli.game.ply(), 2);
+        assert_eq!(cli.game.turn(), &Color::White);
+        assert_eq!(output.matches("The engine plays ").count(), 2);
+        assert!(output.contains("evaluation "));
+
+        let (cli, _) = play_against_engine(STARTING_FEN, &["resign"]);
+
+        assert_eq!(cli.game.state(), &GameState::Resigned(Color::White));
+    }
+
+    #[test]
+    fn test_engine_takes_a_draw_only_when_losing(){
+        let (cli, output) = play_against_engine("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", &["draw", "Qd2"]);
+
+        assert!(output.contains("The engine accepts the draw"));
+        assert_eq!(cli.game.result(), GameResult::Draw(better_chess::Termination::Agreement));
+
+        let (cli, output) = play_against_engine("3qk3/8/8/8/8/8/8/4K3 w - - 0 1", &["draw", "Kf2"]);
+
+        assert!(output.contains("The engine declines the draw"));
+        assert_eq!(cli.game.ply(), 2);
+        assert_eq!(cli.game.draw_offer(), None);
+    }
+
+    #[test]
+    fn test_resign_and_draw_by_agreement(){
+        let (cli, output) = play(&["resign"]);
+
+        assert_eq!(cli.game.state(), &GameState::Resigned(Color::White));
+        assert!(output.contains("Black wins by resignation! (0-1)"));
+
+        // The player who offered cannot accept, the opponent does after the move
+        let (cli, output) = play(&["draw", "accept", "e4", "accept"]);
+
+        assert!(output.contains(&ChessError::NoDrawOffer.to_string()));
+        assert_eq!(cli.game.result(), GameResult::Draw(better_chess::Termination::Agreement));
+    }
+}
@@ -0,0 +1,55 @@
+//! A chess library: board representation, move validation and game state.
+//!
+//! The types most programs need are re-exported at the crate root, and
+//! `use better_chess::prelude::*;` brings all of them into scope at once.
+//!
+//! ```
+//! use better_chess::prelude::*;
+//!
+//! let mut game = ClassicGame::new();
+//!
+//! let movement = Movement::new(
+//!     ChessNotationPosition::new('e', 2).to_position(),
+//!     ChessNotationPosition::new('e', 4).to_position(),
+//! );
+//!
+//! assert_eq!(game.move_piece(movement), Ok(&GameState::InProgress));
+//! assert_eq!(game.turn(), &Color::Black);
+//! ```
+
+mod chess;
+
+pub use chess::bitboard::{self, position::Position, Bitboard};
+pub use chess::board::{
+    render::{ArrowMark, AsciiRenderer, BoardRenderer, Cell, ColoredRenderer, Direction, Flipped, RenderOptions, UnicodeRenderer},
+    tile::Tile,
+    Board,
+};
+pub use chess::engine;
+pub use chess::color::Color;
+pub use chess::error::ChessError;
+pub use chess::game::{
+    castling::{Castle, CastleSide, CastlingRights},
+    chess960::Chess960Game,
+    classic::ClassicGame,
+    clock::{Clock, Increment, ManualTimeSource, Stage, SystemTimeSource, TimeControl, TimeSource},
+    draw::DrawReason,
+    fen::{FenError, STARTING_FEN},
+    history::MoveRecord,
+    result::{GameResult, Termination},
+    Game, GameState,
+};
+pub use chess::movement::{
+    absolute_position::AbsolutePosition, chess_notation::ChessNotationPosition,
+    relative_position::RelativePosition, Movement,
+};
+pub use chess::pgn::{PgnError, PgnGame, PgnReader, PgnTags};
+pub use chess::piece::{Piece, PieceType};
+
+/// Everything needed to set up and play a game.
+pub mod prelude {
+    pub use crate::{
+        AbsolutePosition, Board, Chess960Game, ChessNotationPosition, ClassicGame, Color, Game, GameState,
+        Movement, Piece, PieceType, RelativePosition, Tile,
+    };
+}
@@ -0,0 +1,477 @@
+//! The full-screen game of the binary, played with the keyboard on a drawn board
+//!
+//! A cursor walks the squares with the arrow keys or `h`, `j`, `k` and `l`. Enter or space picks
+//! up a piece of the player to move, which marks the squares it can reach, and drops it on one of
+//! them. The side panel keeps the state of the game, the clocks, the captured material and the
+//! moves in SAN. Only the 16 standard terminal colours and plain Unicode are drawn, so any Linux
+//! terminal shows it.
+
+use std::{io, time::Duration};
+
+use better_chess::{engine::level::Level, prelude::*, ChessError};
+use ratatui::{
+    backend::Backend,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    Terminal,
+};
+
+use crate::cli::Opponent;
+
+mod view;
+
+/// How long to wait for a key before drawing the clocks again
+const TICK: Duration = Duration::from_millis(250);
+
+/// The game played in the terminal with what the player is pointing at and has picked up
+pub struct App{
+    game: Game,
+    opponent: Option<Opponent>,
+    cursor: AbsolutePosition,
+    selected: Option<AbsolutePosition>,
+    /// The piece highlighted in the promotion picker, an index in `PieceType::PROMOTIONS`
+    promotion: usize,
+    flip: bool,
+    /// Whether `r` was pressed once, resigning waits for a second press
+    resigning: bool,
+    messages: Vec<String>,
+    quit: bool,
+}
+
+impl App{
+    /// Creates a game between two players sharing the keyboard
+    pub fn new(game: Game) -> Self{
+        let cursor = home_square(game.turn());
+
+        App{
+            game,
+            opponent: None,
+            cursor,
+            selected: None,
+            promotion: 0,
+            flip: false,
+            resigning: false,
+            messages: Vec::new(),
+            quit: false,
+        }
+    }
+
+    /// Creates a game where the player plays `color` and the engine the other side at `level`
+    pub fn against_engine(game: Game, color: Color, level: Level) -> Self{
+        let mut app = App::new(game);
+
+        app.cursor = home_square(&color);
+        app.opponent = Some(Opponent::new(color.opposite(), level));
+        app.messages.push(format!("You play {} against the engine at level {}", color, level));
+
+        app
+    }
+
+    /// Takes over the terminal until the player quits, then gives it back as it was
+    pub fn run(&mut self) -> io::Result<()>{
+        let mut terminal = ratatui::init();
+        let result = self.run_on(&mut terminal);
+
+        ratatui::restore();
+
+        result
+    }
+
+    /// Draws the game on `terminal` and reacts to the keys until the player quits
+    fn run_on<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()>{
+        while !self.quit{
+            self.game.check_flag();
+
+            terminal.draw(|frame| view::draw(frame, self))?;
+
+            if self.is_engine_turn(){
+                self.play_engine_move();
+                continue;
+            }
+
+            if !event::poll(TICK)?{
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()?{
+                if key.kind == KeyEventKind::Press{
+                    self.handle_key(key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles one key press
+    pub fn handle_key(&mut self, key: KeyEvent){
+        self.messages.clear();
+
+        let resigning = std::mem::take(&mut self.resigning);
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c'){
+            self.quit = true;
+            return;
+        }
+
+        if self.game.state() == &GameState::Promoting{
+            self.pick_promotion(key.code);
+            return;
+        }
+
+        match key.code{
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, 1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, -1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.select(),
+            KeyCode::Esc => self.selected = None,
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('f') => self.flip = !self.flip,
+            KeyCode::Char('d') => self.draw(),
+            KeyCode::Char('y') => {
+                let result = self.game.accept_draw().map(|_| ());
+                self.report(result);
+            },
+            KeyCode::Char('n') => {
+                let result = self.game.decline_draw();
+                self.report(result);
+            },
+            KeyCode::Char('r') => self.resign(resigning),
+            KeyCode::Char('q') => self.quit = true,
+            _ => (),
+        }
+    }
+
+    /// Returns the side drawn at the bottom of the board
+    fn perspective(&self) -> Color{
+        match (self.flip, &self.opponent){
+            (true, _) => *self.game.turn(),
+            (false, Some(opponent)) => opponent.color().opposite(),
+            (false, None) => Color::White,
+        }
+    }
+
+    /// Moves the cursor by `files` to the right and `ranks` up as the board is drawn, staying on the board
+    fn move_cursor(&mut self, files: isize, ranks: isize){
+        let (files, ranks) = match self.perspective(){
+            Color::White => (files, ranks),
+            Color::Black => (-files, -ranks),
+        };
+
+        self.cursor = AbsolutePosition::new(
+            self.cursor.file.saturating_add_signed(files).min(7),
+            self.cursor.rank.saturating_add_signed(ranks).min(7),
+        );
+    }
+
+    /// Returns the squares the selected piece can move to
+    fn targets(&self) -> Vec<AbsolutePosition>{
+        match &self.selected{
+            Some(square) => self.targets_from(square),
+            None => Vec::new(),
+        }
+    }
+
+    fn targets_from(&self, from: &AbsolutePosition) -> Vec<AbsolutePosition>{
+        self.game.legal_moves_from(from).iter().map(|movement| *movement.to()).collect()
+    }
+
+    /// Drops the selected piece on the cursor when it can go there, otherwise picks up the piece under the cursor
+    fn select(&mut self){
+        if self.game.is_over(){
+            match self.game.ended_off_the_board(){
+                true => self.messages.push(String::from("The game is over, press q to quit")),
+                false => self.messages.push(String::from("The game is over, press u to undo or q to quit")),
+            }
+            return;
+        }
+
+        if let Some(from) = self.selected.take(){
+            if self.targets_from(&from).contains(&self.cursor){
+                let result = self.game.move_piece(Movement::new(from, self.cursor)).map(|_| ());
+
+                self.report(result);
+                return;
+            }
+
+            if from == self.cursor{
+                return;
+            }
+        }
+
+        let board = self.game.board();
+
+        match board.get_tile(&self.cursor).get_piece(){
+            Some(piece) if piece.color() == self.game.turn() => match self.game.legal_moves_from(&self.cursor).is_empty(){
+                true => self.messages.push(format!("The piece on {} has no legal move", self.cursor)),
+                false => self.selected = Some(self.cursor),
+            },
+            _ => self.messages.push(format!("Pick a {} piece to move", self.game.turn())),
+        }
+    }
+
+    /// Moves through the promotion picker, or finishes the promotion with the chosen piece
+    fn pick_promotion(&mut self, code: KeyCode){
+        let count = PieceType::PROMOTIONS.len();
+
+        let piece_type = match code{
+            KeyCode::Left | KeyCode::Up | KeyCode::Char('h') | KeyCode::Char('k') => {
+                self.promotion = (self.promotion + count - 1) % count;
+                return;
+            },
+            KeyCode::Right | KeyCode::Down | KeyCode::Char('l') | KeyCode::Char('j') => {
+                self.promotion = (self.promotion + 1) % count;
+                return;
+            },
+            KeyCode::Esc | KeyCode::Char('u') => {
+                self.game.undo();
+                return;
+            },
+            KeyCode::Enter | KeyCode::Char(' ') => PieceType::PROMOTIONS[self.promotion],
+            KeyCode::Char(letter) => match PieceType::from_prefix(letter).filter(PieceType::is_promotion){
+                Some(piece_type) => piece_type,
+                None => {
+                    self.messages.push(String::from("Promote to Q, R, B or N, Esc takes the move back"));
+                    return;
+                },
+            },
+            _ => return,
+        };
+
+        self.promotion = 0;
+
+        let result = self.game.promote(piece_type).map(|_| ());
+
+        self.report(result);
+    }
+
+    /// Takes back the last move, and against the engine its reply too so the player moves again
+    fn undo(&mut self){
+        self.selected = None;
+
+        if self.game.undo().is_none(){
+            match self.game.ended_off_the_board(){
+                true => self.messages.push(format!("The game ended by {}, its moves cannot be taken back", self.game.result().termination())),
+                false => self.messages.push(String::from("There is no move to undo")),
+            }
+            return;
+        }
+
+        if self.is_engine_turn() && self.game.ply() > 0{
+            self.game.undo();
+        }
+    }
+
+    /// Claims a draw when there is one, offers one otherwise
+    fn draw(&mut self){
+        let result = match self.game.claimable_draw(){
+            Some(_) => self.game.claim_draw().map(|_| ()),
+            None => self.game.offer_draw()
+                .map(|_| self.messages.push(String::from("Draw offered, the opponent may accept (y) or decline (n)"))),
+        };
+
+        self.report(result);
+    }
+
+    /// Resigns for the player, or the player to move without an engine, on the second press of `r`
+    fn resign(&mut self, confirmed: bool){
+        if self.game.is_over(){
+            self.messages.push(String::from("The game is over"));
+            return;
+        }
+
+        if !confirmed{
+            self.resigning = true;
+            self.messages.push(String::from("Press r again to resign"));
+            return;
+        }
+
+        let color = match &self.opponent{
+            Some(opponent) => opponent.color().opposite(),
+            None => *self.game.turn(),
+        };
+
+        let result = self.game.resign(color).map(|_| ());
+
+        self.report(result);
+    }
+
+    fn report(&mut self, result: Result<(), ChessError>){
+        if let Err(error) = result{
+            self.messages.push(error.to_string());
+        }
+    }
+
+    /// Whether the engine has to move now
+    fn is_engine_turn(&self) -> bool{
+        self.opponent.as_ref().is_some_and(|opponent| opponent.is_on_move(&self.game))
+    }
+
+    /// Lets the engine answer a draw offer, then play its move
+    fn play_engine_move(&mut self){
+        if let Some(opponent) = &mut self.opponent{
+            self.messages.extend(opponent.play(&mut self.game));
+        }
+    }
+}
+
+/// Returns the square the cursor starts on, the king's pawn of `color`
+fn home_square(color: &Color) -> AbsolutePosition{
+    match color{
+        Color::White => AbsolutePosition::new(4, 1),
+        Color::Black => AbsolutePosition::new(4, 6),
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use better_chess::{ClassicGame, GameResult, Termination};
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    fn press(app: &mut App, keys: &str){
+        for key in keys.chars(){
+            let code = match key{
+                '\n' => KeyCode::Enter,
+                key => KeyCode::Char(key),
+            };
+
+            app.handle_key(KeyEvent::from(code));
+        }
+    }
+
+    fn square(name: &str) -> AbsolutePosition{
+        name.parse().unwrap()
+    }
+
+    /// Draws the app on a terminal of `width` by `height` and returns its text, one line per row
+    fn render(app: &App, width: u16, height: u16) -> String{
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+
+        terminal.draw(|frame| view::draw(frame, app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+
+        (0..height).map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
+
+    #[test]
+    fn test_cursor_selects_and_plays_moves(){
+        let mut app = App::new(ClassicGame::new());
+
+        assert_eq!(app.cursor, square("e2"));
+
+        press(&mut app, "\n");
+
+        assert_eq!(app.selected, Some(square("e2")));
+        assert_eq!(app.targets(), vec![square("e3"), square("e4")]);
+
+        press(&mut app, "kk\n");
+
+        assert_eq!(app.selected, None);
+        assert_eq!(app.game.san_moves(), vec!["e4"]);
+
+        // Black's pieces only, the cursor stops at the edge of the board
+        press(&mut app, "\n");
+
+        assert_eq!(app.selected, None);
+        assert_eq!(app.messages, vec!["Pick a Black piece to move"]);
+
+        press(&mut app, "kkkkkkkkhhhhhhhh");
+
+        assert_eq!(app.cursor, square("a8"));
+    }
+
+    #[test]
+    fn test_flipped_board_turns_the_arrows_around(){
+        let mut app = App::new(ClassicGame::new());
+
+        press(&mut app, "f");
+
+        assert_eq!(app.perspective(), Color::White);
+
+        press(&mut app, "\nkk\n");
+
+        assert_eq!(app.perspective(), Color::Black);
+
+        press(&mut app, "kl");
+
+        assert_eq!(app.cursor, square("d3"));
+    }
+
+    #[test]
+    fn test_promotion_picker(){
+        let mut app = App::new(Game::from_fen("8/4P2k/8/8/8/8/8/K7 w - - 0 1").unwrap());
+
+        app.cursor = square("e7");
+        press(&mut app, "\nk\n");
+
+        assert_eq!(app.game.state(), &GameState::Promoting);
+
+        // Esc takes the move back, the arrows walk the picker from the queen
+        app.handle_key(KeyEvent::from(KeyCode::Esc));
+
+        assert_eq!(app.game.state(), &GameState::InProgress);
+
+        app.cursor = square("e7");
+        press(&mut app, "\nk\nll\n");
+
+        assert_eq!(app.game.board().get_tile(&square("e8")).get_piece().as_ref().map(Piece::piece_type), Some(PieceType::Bishop));
+
+        app.game.undo();
+        app.cursor = square("e7");
+        press(&mut app, "\nk\nn");
+
+        assert_eq!(app.game.san_moves(), vec!["e8=N"]);
+    }
+
+    #[test]
+    fn test_resigning_takes_two_presses(){
+        let mut app = App::new(ClassicGame::new());
+
+        press(&mut app, "rk");
+
+        assert!(!app.game.is_over());
+
+        press(&mut app, "rr");
+
+        assert_eq!(app.game.result(), GameResult::Win(Color::Black, Termination::Resignation));
+    }
+
+    #[test]
+    fn test_side_panel_shows_moves_and_captures(){
+        let mut app = App::new(ClassicGame::new());
+
+        for san in ["e4", "d5", "exd5"]{
+            app.game.move_piece(app.game.parse_san(san).unwrap()).unwrap();
+        }
+
+        let screen = render(&app, 80, 30);
+
+        assert!(screen.contains("Black to move"));
+        assert!(screen.contains("1. e4 d5"));
+        assert!(screen.contains("2. exd5"));
+        assert!(screen.contains("White ♟ +1"));
+
+        // A terminal too small for the panel still draws the board
+        let screen = render(&app, 20, 10);
+
+        assert!(screen.contains('♜'));
+    }
+
+    #[test]
+    fn test_engine_replies_and_undo_takes_both_moves_back(){
+        let mut app = App::against_engine(ClassicGame::new(), Color::White, Level::MIN);
+
+        press(&mut app, "\nkk\n");
+        app.play_engine_move();
+
+        assert_eq!(app.game.ply(), 2);
+        assert!(!app.is_engine_turn());
+
+        press(&mut app, "u");
+
+        assert_eq!(app.game.ply(), 0);
+    }
+}
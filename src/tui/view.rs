@@ -0,0 +1,276 @@
+//! Draws the board and the side panel of the terminal UI
+
+use better_chess::{prelude::*, GameResult};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color as TermColor, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::cli::format_time;
+
+use super::App;
+
+const LIGHT_SQUARE: TermColor = TermColor::White;
+const DARK_SQUARE: TermColor = TermColor::Green;
+const LAST_MOVE: TermColor = TermColor::Yellow;
+const SELECTED: TermColor = TermColor::Cyan;
+const CAPTURE: TermColor = TermColor::LightMagenta;
+const CHECK: TermColor = TermColor::Red;
+const CURSOR: TermColor = TermColor::Blue;
+
+/// The narrowest side panel the board leaves room for before its squares shrink
+const PANEL_WIDTH: u16 = 30;
+
+const KEYS: &str = "arrows/hjkl move, enter/space pick and drop, esc cancel, u undo, f flip, d draw, y/n answer a draw, r resign, q quit";
+
+/// Draws the whole screen: the board on the left, the state of the game on the right
+pub fn draw(frame: &mut Frame, app: &App){
+    let square = square_size(frame.area());
+    let board_width = 2 + 8 * square.0 + 2;
+
+    let [board_area, panel_area] = Layout::horizontal([Constraint::Length(board_width), Constraint::Min(0)]).areas(frame.area());
+
+    draw_board(frame, app, board_area, square);
+    draw_panel(frame, app, panel_area);
+
+    if app.game.state() == &GameState::Promoting{
+        draw_promotion_picker(frame, app, board_area);
+    }
+}
+
+/// Returns the width and height of a square, as large as the terminal allows
+fn square_size(area: Rect) -> (u16, u16){
+    // The borders and the file letters take three rows, the rank numbers and borders four columns
+    let mut height = (area.height.saturating_sub(3) / 8).clamp(1, 3);
+
+    while height > 1 && 4 + 8 * (2 * height + 1) + PANEL_WIDTH > area.width{
+        height -= 1;
+    }
+
+    (2 * height + 1, height)
+}
+
+/// Returns the glyph of a piece, outlined for White and filled for Black as printed in books
+pub fn glyph(color: &Color, piece_type: PieceType) -> char{
+    Piece::new(*color, piece_type).icon()
+}
+
+fn draw_board(frame: &mut Frame, app: &App, area: Rect, (width, height): (u16, u16)){
+    let board = app.game.board();
+    let targets = app.targets();
+    let captures: Vec<Movement> = app.game.legal_captures();
+    let last_move = app.game.history().last().map(|record| *record.movement());
+    let checked_king = match app.game.state(){
+        GameState::Check(color) | GameState::Checkmate(color) => board.get_king_position(color),
+        _ => None,
+    };
+
+    let (ranks, files): (Vec<usize>, Vec<usize>) = match app.perspective(){
+        Color::White => ((0..8).rev().collect(), (0..8).collect()),
+        Color::Black => ((0..8).collect(), (0..8).rev().collect()),
+    };
+
+    let mut lines = Vec::new();
+
+    for rank in &ranks{
+        for row in 0..height{
+            let is_middle = row == height / 2;
+
+            let label = match is_middle{
+                true => format!("{} ", rank + 1),
+                false => String::from("  "),
+            };
+            let mut spans = vec![Span::raw(label)];
+
+            for file in &files{
+                let square = AbsolutePosition::new(*file, *rank);
+                let is_target = targets.contains(&square);
+                let is_capture = is_target && captures.iter().any(|movement| Some(movement.from()) == app.selected.as_ref() && movement.to() == &square);
+
+                let background = match square{
+                    _ if checked_king == Some(square) => CHECK,
+                    _ if app.selected == Some(square) => SELECTED,
+                    _ if is_capture => CAPTURE,
+                    _ if last_move.is_some_and(|movement| movement.from() == &square || movement.to() == &square) => LAST_MOVE,
+                    _ if (file + rank) % 2 == 0 => DARK_SQUARE,
+                    _ => LIGHT_SQUARE,
+                };
+                let style = Style::default().bg(background).fg(TermColor::Black);
+
+                let symbol = match (is_middle, board.get_tile(&square).get_piece()){
+                    (false, _) => ' ',
+                    (true, Some(piece)) => piece.icon(),
+                    (true, None) if is_target => '•',
+                    (true, None) => ' ',
+                };
+
+                let padding = " ".repeat(((width - 1) / 2 - 1) as usize);
+                let (left, right) = match is_middle && app.cursor == square{
+                    true => ("[", "]"),
+                    false => (" ", " "),
+                };
+                let cursor = style.fg(CURSOR).add_modifier(Modifier::BOLD);
+
+                spans.push(Span::styled(padding.clone(), style));
+                spans.push(Span::styled(left, cursor));
+                spans.push(Span::styled(symbol.to_string(), style));
+                spans.push(Span::styled(right, cursor));
+                spans.push(Span::styled(padding, style));
+            }
+
+            lines.push(Line::from(spans));
+        }
+    }
+
+    let letters: String = files.iter()
+        .map(|file| format!("{:^width$}", (b'a' + *file as u8) as char, width = width as usize))
+        .collect();
+    lines.push(Line::from(format!("  {}", letters)));
+
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered()), area);
+}
+
+fn draw_panel(frame: &mut Frame, app: &App, area: Rect){
+    let help_height = match area.width{
+        0 => 0,
+        // One more row than the letters need, the wrap breaks between words
+        width => (KEYS.len() as u16).div_ceil(width.saturating_sub(2).max(1)) + 3,
+    };
+    let message_height = app.messages.len() as u16 + 2;
+
+    let [game_area, moves_area, message_area, help_area] = Layout::vertical([
+        Constraint::Length(8),
+        Constraint::Min(3),
+        Constraint::Length(message_height),
+        Constraint::Length(help_height),
+    ]).areas(area);
+
+    let mut lines = vec![Line::styled(status(app), Style::default().add_modifier(Modifier::BOLD))];
+
+    match app.game.draw_offer(){
+        Some(color) if &color == app.game.turn() => lines.push(Line::from(format!("{} offered a draw, answered after the move", color))),
+        Some(color) => lines.push(Line::from(format!("{} offers a draw, y or n", color))),
+        None => (),
+    }
+
+    if let Some(reason) = app.game.claimable_draw(){
+        lines.push(Line::from(format!("A draw by {} may be claimed", reason)));
+    }
+
+    if let Some(clock) = app.game.clock(){
+        for color in [Color::White, Color::Black]{
+            let style = match clock.running() == Some(color){
+                true => Style::default().add_modifier(Modifier::REVERSED),
+                false => Style::default(),
+            };
+
+            lines.push(Line::styled(format!("{} {}", color, format_time(clock.remaining(&color))), style));
+        }
+    }
+
+    lines.extend(captured_material(&app.game).into_iter().map(Line::from));
+
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Game ")), game_area);
+    frame.render_widget(move_list(&app.game, moves_area.height.saturating_sub(2) as usize), moves_area);
+    frame.render_widget(Paragraph::new(app.messages.join("\n")).wrap(Wrap{trim: true}).block(Block::bordered()), message_area);
+    frame.render_widget(Paragraph::new(KEYS).wrap(Wrap{trim: true}).block(Block::bordered().title(" Keys ")), help_area);
+}
+
+/// Returns what the players are waiting for, or how the game ended
+fn status(app: &App) -> String{
+    let game = &app.game;
+
+    match game.result(){
+        GameResult::Win(color, termination) => return format!("{} wins by {} ({})", color, termination, game.result()),
+        GameResult::Draw(termination) => return format!("Draw by {} ({})", termination, game.result()),
+        GameResult::Unfinished(_) if game.is_over() => return String::from("The game was abandoned"),
+        GameResult::Unfinished(_) => (),
+    }
+
+    match game.state(){
+        _ if app.is_engine_turn() => String::from("The engine is thinking..."),
+        GameState::Promoting => format!("{} promotes, pick a piece", game.turn()),
+        GameState::Check(color) => format!("{} to move, in check", color),
+        _ => format!("{} to move", game.turn()),
+    }
+}
+
+/// Returns a line per player with the pieces they took and how far ahead they are in material
+fn captured_material(game: &Game) -> Vec<String>{
+    let board = game.board();
+    let ply = game.ply();
+
+    [Color::White, Color::Black].iter().map(|color| {
+        // The last move was played by the opponent of the player to move, and so on backwards
+        let taken: String = game.history().iter().enumerate()
+            .filter(|(index, _)| match (ply - 1 - index).is_multiple_of(2){
+                true => game.turn() != color,
+                false => game.turn() == color,
+            })
+            .filter_map(|(_, record)| record.captured())
+            .map(|piece_type| glyph(&color.opposite(), piece_type))
+            .collect();
+
+        let material = |color: &Color| -> i32{
+            board.get_tiles().iter()
+                .filter_map(|(tile, _)| tile.get_piece().as_ref())
+                .filter(|piece| piece.color() == color)
+                .map(|piece| piece.value() as i32)
+                .sum()
+        };
+
+        match material(color) - material(&color.opposite()){
+            lead if lead > 0 => format!("{} {} +{}", color, taken, lead),
+            _ => format!("{} {}", color, taken),
+        }
+    }).collect()
+}
+
+/// Returns the moves in SAN, a numbered line per move pair, showing the last ones that fit in `height`
+fn move_list(game: &Game, height: usize) -> Paragraph<'static>{
+    let mut moves = game.san_moves();
+
+    // A game set up with Black to move starts its first pair with an empty move
+    let first_turn = match moves.len().is_multiple_of(2){
+        true => *game.turn(),
+        false => game.turn().opposite(),
+    };
+
+    if first_turn == Color::Black{
+        moves.insert(0, String::from("..."));
+    }
+
+    let lines: Vec<Line> = moves.chunks(2).enumerate()
+        .map(|(index, pair)| Line::from(format!("{}. {}", index + 1, pair.join(" "))))
+        .collect();
+
+    let skipped = lines.len().saturating_sub(height);
+
+    Paragraph::new(lines.into_iter().skip(skipped).collect::<Vec<Line>>()).block(Block::bordered().title(" Moves "))
+}
+
+/// Draws the pieces a pawn may promote to over the middle of the board
+fn draw_promotion_picker(frame: &mut Frame, app: &App, board_area: Rect){
+    let width = (PieceType::PROMOTIONS.len() * 4 + 2) as u16;
+    let area = Rect{
+        x: board_area.x + board_area.width.saturating_sub(width) / 2,
+        y: board_area.y + board_area.height.saturating_sub(3) / 2,
+        width: width.min(board_area.width),
+        height: 3.min(board_area.height),
+    };
+
+    let spans: Vec<Span> = PieceType::PROMOTIONS.iter().enumerate().map(|(index, piece_type)| {
+        let style = match index == app.promotion{
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default(),
+        };
+
+        Span::styled(format!(" {}{} ", glyph(app.game.turn(), *piece_type), piece_type.prefix()), style)
+    }).collect();
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(Line::from(spans)).block(Block::bordered().title(" Promote ")), area);
+}